@@ -0,0 +1,27 @@
+//! AcoustID command handlers
+
+use tauri::State;
+
+use crate::domain::music::MusicFile;
+use crate::errors::{ApiResponse, Locale};
+use crate::services::{AcoustIdService, AllowedRoots};
+
+/// Identifies an untagged file by acoustic fingerprint (requires fpcalc and ACOUSTID_API_KEY)
+///
+/// Only files inside the allowed roots can be identified.
+#[tauri::command]
+pub async fn acoustid_identify(
+    service: State<'_, AcoustIdService>,
+    file_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<MusicFile> {
+    let roots = roots
+        .roots()
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    service
+        .lookup_by_fingerprint(&file_path, &roots)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
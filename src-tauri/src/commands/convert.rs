@@ -0,0 +1,29 @@
+//! Audio conversion command handlers
+
+use tauri::{AppHandle, State};
+
+use crate::errors::{ApiResponse, Locale};
+use crate::services::{AllowedRoots, ConvertService};
+
+/// Converts a local audio file to another format with ffmpeg, returning the output path
+///
+/// Progress is reported through `convert-progress` events.
+#[tauri::command]
+pub async fn convert_audio_file(
+    input_path: String,
+    output_path: String,
+    format: String,
+    bitrate: Option<u32>,
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    roots
+        .check(&input_path)
+        .and_then(|_| roots.check_output(&output_path))
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    ConvertService::convert(&input_path, &output_path, &format, bitrate, &app_handle)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
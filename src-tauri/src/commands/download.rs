@@ -1,21 +1,36 @@
 //! Download command handlers
 
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
-use crate::errors::ApiResponse;
-use crate::services::DownloadService;
+use crate::domain::spotify::SpotifyTrack;
+use crate::errors::{ApiResponse, AppError, Locale};
+use crate::services::download::{DownloadFinished, DownloadProblem, DownloadResult, SpotdlVersion};
+use crate::services::download_history::{DownloadHistory, DownloadHistoryEntry};
+use crate::services::{AllowedRoots, DownloadService, SettingsState};
 
 /// Downloads multiple Spotify tracks in segments using spotdl with controlled concurrency
+///
+/// `max_songs` raises the default limit of 100 songs per call, up to 500.
+/// `format` and `output_dir` default to the saved settings. Returns the
+/// downloaded/failed counts, partial when too many batches failed in a row.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn download_spotify_tracks_segmented(
     urls: Vec<String>,
     segment_size: usize,
     delay: u64,
     output_template: String,
-    format: String,
+    format: Option<String>,
     output_dir: Option<String>,
+    max_songs: Option<usize>,
     app_handle: AppHandle,
-) -> ApiResponse<()> {
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<DownloadFinished> {
+    let (format, output_dir) = resolve_download_options(format, output_dir, &roots, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
     DownloadService::download_tracks_segmented(
         urls,
         segment_size,
@@ -23,30 +38,153 @@ pub async fn download_spotify_tracks_segmented(
         output_template,
         format,
         output_dir,
+        max_songs,
         &app_handle,
     )
     .await
-    .map_err(|e| e.to_user_message())
+    .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Downloads a single Spotify track with comprehensive validation and error handling
+///
+/// `format` and `output_dir` default to the saved settings.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn download_single_spotify_track(
     url: String,
     output_template: String,
-    format: String,
+    format: Option<String>,
     output_dir: Option<String>,
     app_handle: AppHandle,
-) -> ApiResponse<String> {
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<DownloadResult> {
+    let (format, output_dir) = resolve_download_options(format, output_dir, &roots, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
     DownloadService::download_single_track(url, output_template, format, output_dir, &app_handle)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Downloads a whole Spotify album, letting spotdl expand it into its tracks
+///
+/// `album` is an album URL, `spotify:album:` URI or id. `output_template` defaults
+/// to `Album Artist - Album/NN - Title.ext`; `format` and `output_dir` default to
+/// the saved settings. Returns the number of tracks downloaded.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn download_spotify_album(
+    album: String,
+    output_template: Option<String>,
+    format: Option<String>,
+    output_dir: Option<String>,
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<usize> {
+    let (format, output_dir) = resolve_download_options(format, output_dir, &roots, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    DownloadService::download_spotify_album(album, output_template, format, output_dir, &app_handle)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
-/// Checks if spotdl is installed and returns its version
+/// Resumes a batch download interrupted by a crash or restart
+///
+/// Returns the number of tracks re-enqueued (0 when nothing was pending). A queue
+/// whose output folder is no longer inside an allowed root is dropped.
 #[tauri::command]
-pub async fn check_spotdl_installed() -> ApiResponse<String> {
+pub async fn resume_downloads(
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<usize> {
+    DownloadService::resume_downloads(&roots, &app_handle)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Downloads a track object as returned by the Spotify listing commands
+///
+/// `format` and `output_dir` default to the saved settings.
+#[tauri::command]
+pub async fn download_spotify_track_object(
+    track: SpotifyTrack,
+    format: Option<String>,
+    output_dir: Option<String>,
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<DownloadResult> {
+    let (format, output_dir) = resolve_download_options(format, output_dir, &roots, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    DownloadService::download_spotify_track(&track, format, output_dir, &app_handle)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets the most recent download history entries, newest first (default 100)
+#[tauri::command]
+pub fn get_download_history(
+    limit: Option<usize>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<DownloadHistoryEntry>> {
+    DownloadHistory::read_recent(limit.unwrap_or(100))
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Deletes the download history
+#[tauri::command]
+pub fn clear_download_history(locale: State<'_, Locale>) -> ApiResponse<()> {
+    DownloadHistory::clear().map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Checks downloaded files and lists the missing, empty or corrupt ones
+///
+/// Files that pass are left out, so an empty list means every download is fine.
+/// Files outside the allowed roots are reported as missing without being read.
+#[tauri::command]
+pub async fn verify_downloads(
+    paths: Vec<String>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<DownloadProblem>> {
+    let roots = roots
+        .roots()
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    DownloadService::verify_files(paths, roots)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Checks if spotdl is installed and whether its version is supported
+#[tauri::command]
+pub async fn check_spotdl_installed(locale: State<'_, Locale>) -> ApiResponse<SpotdlVersion> {
     DownloadService::check_installed()
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Fills in the download format and output folder from the settings when omitted
+///
+/// The output folder, wherever it came from, must be inside an allowed root.
+fn resolve_download_options(
+    format: Option<String>,
+    output_dir: Option<String>,
+    roots: &AllowedRoots,
+    settings: &SettingsState,
+) -> Result<(String, Option<String>), AppError> {
+    let settings = settings.get()?;
+    let output_dir = output_dir.or(settings.output_dir);
+    if let Some(dir) = &output_dir {
+        roots.check_output_dir(dir)?;
+    }
+    Ok((format.unwrap_or(settings.default_format), output_dir))
 }
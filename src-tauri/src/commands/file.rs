@@ -1,31 +1,567 @@
 //! File system command handlers
 
-use crate::domain::music::MusicFile;
-use crate::errors::ApiResponse;
-use crate::services::FileService;
+use std::collections::BTreeMap;
+
+use crate::domain::music::{
+    FailedFolder, ImportedPlaylist, LibraryMatch, Lyrics, MultiFolderScan, MusicFile, SearchField,
+    SortKey, SupportedFormats, TagNormalization, TagNormalizeOptions, YearHistogram,
+};
+use crate::domain::spotify::SpotifyTrack;
+use crate::errors::{ApiResponse, AppError, Locale};
+use crate::services::recent_folders::RecentFolder;
+use crate::services::{AllowedRoots, FileService, RecentFolders, ScanState, SettingsState};
 use crate::utils::get_default_music_folder;
-use tauri::AppHandle;
+use tauri::ipc::Response;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
 
 /// Scans a music folder for audio files and extracts their metadata
+///
+/// Symlinked folders are only followed when `follow_symlinks` is true; it
+/// defaults to the saved setting. Audio files larger than `max_file_bytes` are
+/// skipped (no limit by default). The folder is remembered for `get_recent_folders`.
 #[tauri::command]
 pub async fn scan_music_folder(
     folder_path: String,
+    follow_symlinks: Option<bool>,
+    max_file_bytes: Option<u64>,
     app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<MusicFile>> {
-    FileService::scan_music_folder_async(&folder_path, Some(app_handle))
-        .await
-        .map_err(|e| e.to_user_message())
+    let folder = roots
+        .check(&folder_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+    let follow_symlinks = resolve_follow_symlinks(follow_symlinks, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    let files = FileService::scan_music_folder_async(
+        &folder_path,
+        follow_symlinks,
+        max_file_bytes,
+        Some(app_handle),
+    )
+    .await
+    .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    RecentFolders::record(&folder, files.len());
+    Ok(files)
+}
+
+/// Gets the recently scanned folders, most recent first (at most 10)
+#[tauri::command]
+pub fn get_recent_folders() -> Vec<RecentFolder> {
+    RecentFolders::list()
+}
+
+/// Forgets the recently scanned folders
+#[tauri::command]
+pub fn clear_recent_folders(locale: State<'_, Locale>) -> ApiResponse<()> {
+    RecentFolders::clear().map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Scans several music folders (e.g. on different drives) and merges the results
+///
+/// Folders that can't be scanned, including ones outside the allowed roots, are
+/// reported in `failedFolders` without stopping the others.
+#[tauri::command]
+pub async fn scan_music_folders(
+    folder_paths: Vec<String>,
+    follow_symlinks: Option<bool>,
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<MultiFolderScan> {
+    let follow_symlinks = resolve_follow_symlinks(follow_symlinks, &settings)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+    let mut rejected = Vec::new();
+    let allowed: Vec<String> = folder_paths
+        .into_iter()
+        .filter(|path| match roots.check(path) {
+            Ok(_) => true,
+            Err(e) => {
+                rejected.push((path.clone(), e));
+                false
+            }
+        })
+        .collect();
+
+    let (files, failed) =
+        FileService::scan_music_folders_async(allowed, follow_symlinks, Some(app_handle))
+            .await
+            .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    Ok(MultiFolderScan {
+        files,
+        failed_folders: rejected
+            .into_iter()
+            .chain(failed)
+            .map(|(path, e)| FailedFolder {
+                path,
+                error: e.to_user_message_localized(*locale),
+            })
+            .collect(),
+    })
+}
+
+/// Cancels the library scan with the `scanId` from its `library-scan-start` event
+///
+/// The scan stops between files, emits `library-scan-cancelled` and returns an error.
+/// Other scans keep running. Returns false when that scan already finished.
+#[tauri::command]
+pub fn cancel_scan(scan_id: u64, state: State<'_, ScanState>) -> bool {
+    state.cancel(scan_id)
 }
 
 /// Extracts audio metadata from a file
 #[tauri::command]
-pub fn get_audio_metadata(file_path: String) -> ApiResponse<MusicFile> {
-    FileService::get_audio_metadata(&file_path).map_err(|e| e.to_user_message())
+pub fn get_audio_metadata(
+    file_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<MusicFile> {
+    roots
+        .check(&file_path)
+        .and_then(|_| FileService::get_audio_metadata(&file_path))
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets a track's lyrics from a sibling `.lrc` file or its tags, `None` if it has none
+#[tauri::command]
+pub async fn get_lyrics(
+    file_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Option<Lyrics>> {
+    roots
+        .check(&file_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || FileService::get_lyrics(&file_path))
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .and_then(|r| r)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Extracts audio metadata for many files in one call
+///
+/// Results keep the order of `file_paths`; each entry fails independently, and
+/// files outside the allowed roots fail without being read.
+#[tauri::command]
+pub async fn get_audio_metadata_batch(
+    file_paths: Vec<String>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<Result<MusicFile, String>>> {
+    let rejections: Vec<Option<AppError>> = file_paths
+        .iter()
+        .map(|path| roots.check(path).err())
+        .collect();
+    let allowed: Vec<String> = file_paths
+        .into_iter()
+        .zip(&rejections)
+        .filter(|(_, rejection)| rejection.is_none())
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut results = FileService::get_audio_metadata_batch(allowed)
+        .await
+        .into_iter();
+    Ok(rejections
+        .into_iter()
+        .filter_map(|rejection| match rejection {
+            Some(e) => Some(Err(e)),
+            None => results.next(),
+        })
+        .map(|r| r.map_err(|e| e.to_user_message_localized(*locale)))
+        .collect())
+}
+
+/// Normalizes inconsistent tags (whitespace, casing, "The" prefixes, remaster notes)
+///
+/// `options` picks the rules; with `dryRun` the changes are only reported.
+/// Results keep the order of `paths`; each entry fails independently, and
+/// files outside the allowed roots fail without being read.
+#[tauri::command]
+pub async fn normalize_tags(
+    paths: Vec<String>,
+    options: TagNormalizeOptions,
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<Result<TagNormalization, String>>> {
+    let rejections: Vec<Option<AppError>> =
+        paths.iter().map(|path| roots.check(path).err()).collect();
+    let allowed: Vec<String> = paths
+        .into_iter()
+        .zip(&rejections)
+        .filter(|(_, rejection)| rejection.is_none())
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut results = tokio::task::spawn_blocking(move || {
+        FileService::normalize_tags(&allowed, options, Some(&app_handle))
+    })
+    .await
+    .map_err(|e| {
+        AppError::Concurrency(format!("Task join error: {}", e)).to_user_message_localized(*locale)
+    })?
+    .into_iter();
+
+    Ok(rejections
+        .into_iter()
+        .filter_map(|rejection| match rejection {
+            Some(e) => Some(Err(e)),
+            None => results.next(),
+        })
+        .map(|r| r.map_err(|e| e.to_user_message_localized(*locale)))
+        .collect())
+}
+
+/// Returns a whole audio file as raw bytes (an `ArrayBuffer` in JS)
+///
+/// For tracks whose path is in the `native-path:` form, which the asset protocol
+/// can't serve; the frontend plays them from a blob URL.
+#[tauri::command]
+pub async fn read_audio_file(
+    file_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Response> {
+    roots
+        .check(&file_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || FileService::read_audio_bytes(&file_path))
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .and_then(|r| r)
+        .map(Response::new)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Returns a file's embedded album art as raw bytes (an `ArrayBuffer` in JS)
+///
+/// Lets the frontend build a blob URL for large covers instead of decoding the
+/// base64 `albumArt` field; browsers detect the image type from the bytes.
+#[tauri::command]
+pub async fn get_album_art_bytes(
+    file_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Response> {
+    roots
+        .check(&file_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    let (bytes, mime_type) =
+        tokio::task::spawn_blocking(move || FileService::read_album_art_bytes(&file_path))
+            .await
+            .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+            .and_then(|r| r)
+            .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tracing::debug!("🖼️ Album art: {} bytes ({})", bytes.len(), mime_type);
+    Ok(Response::new(bytes))
+}
+
+/// Saves a file's embedded album art next to it, e.g. as `folder.jpg`
+///
+/// The extension follows the image type. An existing cover is kept unless
+/// `overwrite` is true. Returns the cover's path.
+#[tauri::command]
+pub async fn save_album_art(
+    audio_path: String,
+    output_path: String,
+    overwrite: Option<bool>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    roots
+        .check(&audio_path)
+        .and_then(|_| roots.check_output(&output_path))
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || {
+        FileService::save_album_art(&audio_path, &output_path, overwrite.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+    .and_then(|r| r)
+    .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Reads a local image (e.g. a user-picked cover) as a base64 data URL
+///
+/// The file must be a JPEG, PNG, GIF, WebP or BMP image of at most 10 MB.
+#[tauri::command]
+pub async fn read_image_as_data_url(
+    path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    roots
+        .check(&path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || FileService::read_image_as_data_url(&path))
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .and_then(|r| r)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Opens the folder containing `path` in the system file manager
+#[tauri::command]
+pub async fn reveal_in_file_manager(
+    path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<()> {
+    roots
+        .check(&path)
+        .and_then(|_| FileService::reveal_in_file_manager(&path))
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Deletes an audio file, to the OS trash or permanently, returning the bytes freed
+///
+/// Only files inside the allowed roots can be deleted.
+#[tauri::command]
+pub async fn delete_track(
+    path: String,
+    to_trash: bool,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<u64> {
+    roots
+        .check(&path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || FileService::delete_track(&path, to_trash))
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .and_then(|r| r)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Computes waveform `[min, max]` pairs for a file (default 512 buckets, max 4096)
+#[tauri::command]
+pub async fn get_audio_waveform(
+    file_path: String,
+    bucket_count: Option<usize>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<(f32, f32)>> {
+    roots
+        .check(&file_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || {
+        FileService::compute_waveform_peaks(&file_path, bucket_count.unwrap_or(512))
+    })
+    .await
+    .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+    .and_then(|r| r)
+    .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Imports an `.m3u`, `.m3u8` or `.pls` playlist, reporting entries not found on disk
+///
+/// Entries outside the allowed roots are reported as missing.
+#[tauri::command]
+pub async fn import_playlist(
+    playlist_path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<ImportedPlaylist> {
+    roots
+        .check(&playlist_path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+    let roots = roots
+        .roots()
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || FileService::import_playlist(&playlist_path, &roots))
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .and_then(|r| r)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Saves selected local tracks, in order, as an extended M3U playlist
+///
+/// Writes `<name>.m3u8` in `destination_dir` and returns its path. Every track
+/// must be inside the allowed roots.
+#[tauri::command]
+pub async fn create_playlist_from_local_files(
+    name: String,
+    destination_dir: String,
+    file_paths: Vec<String>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    roots
+        .check(&destination_dir)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+    let roots = roots
+        .roots()
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    tokio::task::spawn_blocking(move || {
+        FileService::create_playlist(&name, &destination_dir, &file_paths, &roots)
+    })
+    .await
+    .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+    .and_then(|r| r)
+    .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Lists the folders file commands may access
+#[tauri::command]
+pub fn get_allowed_roots(
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<String>> {
+    roots
+        .list()
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Asks the user for a folder and allows file commands to access it and its
+/// subfolders, returning all roots
+///
+/// The folder comes from the native picker rather than the frontend, so only
+/// folders the user chose themselves can be allowed. Returns `None` when the
+/// dialog is cancelled. The folder is remembered across restarts.
+#[tauri::command]
+pub async fn add_allowed_root(
+    app_handle: AppHandle,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Option<Vec<String>>> {
+    let title = match *locale {
+        Locale::En => "Choose a music folder",
+        Locale::Es | Locale::Legacy => "Elige una carpeta de música",
+    };
+    let picked = tokio::task::spawn_blocking(move || {
+        app_handle
+            .dialog()
+            .file()
+            .set_title(title)
+            .blocking_pick_folder()
+    })
+    .await
+    .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+    .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    let Some(folder) = picked.as_ref().and_then(|folder| folder.as_path()) else {
+        return Ok(None);
+    };
+    roots
+        .add(&folder.to_string_lossy())
+        .map(Some)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Revokes access to a folder added with `add_allowed_root`, returning all roots
+///
+/// The music folder and `MUSICPLAYER_ALLOWED_ROOTS` entries can't be removed.
+#[tauri::command]
+pub fn remove_allowed_root(
+    path: String,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<String>> {
+    roots
+        .remove(&path)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Filters an already-scanned library without touching the disk
+///
+/// Matching ignores case and accents; `field` optionally restricts it to
+/// `title`, `artist`, `album` or `genre`.
+#[tauri::command]
+pub fn filter_library(
+    tracks: Vec<MusicFile>,
+    query: String,
+    field: Option<SearchField>,
+) -> Vec<MusicFile> {
+    FileService::filter_tracks(&tracks, &query, field)
+}
+
+/// Checks which Spotify tracks (e.g. a playlist) the local library already has
+///
+/// Returns the matched tracks with their local file and the unmatched ones, so
+/// only the missing tracks need downloading.
+#[tauri::command]
+pub fn match_spotify_to_library(
+    library: Vec<MusicFile>,
+    spotify_tracks: Vec<SpotifyTrack>,
+) -> LibraryMatch {
+    FileService::match_spotify_to_library(&library, spotify_tracks)
+}
+
+/// Sorts an already-scanned library by `title`, `artist`, `album`, `year`,
+/// `duration` or `track_number`; tracks missing the key come last
+#[tauri::command]
+pub fn sort_library(
+    tracks: Vec<MusicFile>,
+    sort_by: SortKey,
+    ascending: Option<bool>,
+) -> Vec<MusicFile> {
+    FileService::sort_tracks(tracks, sort_by, ascending.unwrap_or(true))
+}
+
+/// Counts the tracks of an already-scanned library per genre
+///
+/// Multi-genre tags count towards each genre; tracks without one are counted
+/// as "Unknown".
+#[tauri::command]
+pub fn get_genre_counts(tracks: Vec<MusicFile>) -> BTreeMap<String, usize> {
+    FileService::group_by_genre(&tracks)
+}
+
+/// Filters an already-scanned library by genre, ignoring case and accents
+///
+/// "Unknown" returns the tracks without a genre.
+#[tauri::command]
+pub fn filter_by_genre(tracks: Vec<MusicFile>, genre: String) -> Vec<MusicFile> {
+    FileService::filter_by_genre(&tracks, &genre)
+}
+
+/// Counts the tracks of an already-scanned library per release decade
+///
+/// Tracks without a plausible year are counted in `unknown`.
+#[tauri::command]
+pub fn get_year_histogram(tracks: Vec<MusicFile>) -> YearHistogram {
+    FileService::year_histogram(&tracks)
+}
+
+/// Lists the download formats and library audio extensions the backend supports
+#[tauri::command]
+pub fn get_supported_formats() -> SupportedFormats {
+    SupportedFormats::current()
 }
 
 /// Gets the default music folder path for the current operating system
 #[tauri::command]
-pub fn get_default_music_folder_cmd() -> ApiResponse<String> {
+pub fn get_default_music_folder_cmd(locale: State<'_, Locale>) -> ApiResponse<String> {
     tracing::info!("📁 Getting default music folder");
-    get_default_music_folder().map_err(|e| e.to_user_message())
+    get_default_music_folder().map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Uses the saved setting when the caller doesn't say whether to follow symlinks
+fn resolve_follow_symlinks(
+    follow_symlinks: Option<bool>,
+    settings: &SettingsState,
+) -> Result<bool, AppError> {
+    match follow_symlinks {
+        Some(follow) => Ok(follow),
+        None => Ok(settings.get()?.follow_symlinks),
+    }
 }
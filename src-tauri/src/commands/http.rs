@@ -0,0 +1,34 @@
+//! Remote resource command handlers
+
+use tauri::{AppHandle, State};
+
+use crate::errors::{ApiResponse, Locale};
+use crate::services::{ArtCacheService, HttpService, HttpState};
+
+/// Downloads remote art (e.g. a Spotify `albumImage`) as a base64 data URL
+#[tauri::command]
+pub async fn fetch_image_as_data_url(
+    url: String,
+    http: State<'_, HttpState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    HttpService::fetch_image_as_data_url(&http.client(), &url)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Downloads album art into the on-disk cache, returning local file paths
+///
+/// Paths are in the order of `urls`, `null` where the download failed. Progress
+/// is reported through `art-cache-progress` events.
+#[tauri::command]
+pub async fn cache_spotify_album_art(
+    urls: Vec<String>,
+    app_handle: AppHandle,
+    http: State<'_, HttpState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<Option<String>>> {
+    ArtCacheService::cache_album_art(&http.client(), urls, &app_handle)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
@@ -1,10 +1,11 @@
 //! Last.fm API command handlers
 
 use crate::domain::lastfm::{
-    EnrichedTrack, ProcessedAlbumInfo, ProcessedArtistInfo, ProcessedTrackInfo,
+    EnrichedTrack, ImageSize, LastFmCacheStats, ProcessedAlbumInfo, ProcessedArtistInfo,
+    ProcessedTopTrack, ProcessedTrackInfo,
 };
 use crate::domain::music::MusicFile;
-use crate::errors::ApiResponse;
+use crate::errors::{ApiResponse, Locale};
 use crate::services::lastfm::LastFmService;
 
 use tauri::State;
@@ -13,48 +14,131 @@ use tauri::State;
 // Assuming we'll register it in main.rs and pass it here.
 // For now, I'll assume we have a way to access it, likely via `State<LastFmService>`.
 
+/// Gets track info from Last.fm
+///
+/// `image_size` (`small`, `medium`, `large`, `extralarge`, `mega`) is preferred
+/// when available; by default the largest image is returned.
 #[tauri::command]
 pub async fn lastfm_get_track_info(
     service: State<'_, LastFmService>,
     artist: String,
     track: String,
+    image_size: Option<ImageSize>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<ProcessedTrackInfo> {
     service
-        .get_track_info(&artist, &track)
+        .get_track_info(&artist, &track, image_size)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
+/// Gets artist info from Last.fm, preferring `image_size` (largest by default)
 #[tauri::command]
 pub async fn lastfm_get_artist_info(
     service: State<'_, LastFmService>,
     artist: String,
+    image_size: Option<ImageSize>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<ProcessedArtistInfo> {
     service
-        .get_artist_info(&artist)
+        .get_artist_info(&artist, image_size)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
+/// Gets album info from Last.fm, preferring `image_size` (largest by default)
 #[tauri::command]
 pub async fn lastfm_get_album_info(
     service: State<'_, LastFmService>,
     artist: String,
     album: String,
+    image_size: Option<ImageSize>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<ProcessedAlbumInfo> {
     service
-        .get_album_info(&artist, &album)
+        .get_album_info(&artist, &album, image_size)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets an artist's most popular tracks from Last.fm
+///
+/// `limit` defaults to 10 and is capped at 50; an unknown artist gives an empty list.
+#[tauri::command]
+pub async fn lastfm_get_top_tracks_for_artist(
+    service: State<'_, LastFmService>,
+    artist: String,
+    limit: Option<u32>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<ProcessedTopTrack>> {
+    service
+        .get_artist_top_tracks(&artist, limit)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Enriches tracks with one album lookup per album, falling back to per-track lookups
+#[tauri::command]
+pub async fn enrich_tracks_by_album(
+    service: State<'_, LastFmService>,
+    tracks: Vec<MusicFile>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<EnrichedTrack>> {
+    service
+        .enrich_tracks_by_album(tracks)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Turns Last.fm offline mode on or off
+///
+/// Offline, lookups only return cached data and never touch the network.
+#[tauri::command]
+pub fn lastfm_set_offline(service: State<'_, LastFmService>, offline: bool) {
+    service.set_offline(offline);
+}
+
+/// Turns Last.fm name autocorrection on or off (on by default)
+///
+/// Off, misspelled names find nothing, but an intentional name is never swapped
+/// for a more popular one.
+#[tauri::command]
+pub fn lastfm_set_autocorrect(service: State<'_, LastFmService>, autocorrect: bool) {
+    service.set_autocorrect(autocorrect);
+}
+
+/// Gets Last.fm cache sizes and hit/miss counts
+#[tauri::command]
+pub async fn lastfm_cache_stats(
+    service: State<'_, LastFmService>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<LastFmCacheStats> {
+    service
+        .cache_stats()
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Empties the Last.fm cache, so every lookup asks Last.fm again
+#[tauri::command]
+pub async fn lastfm_clear_cache(
+    service: State<'_, LastFmService>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<()> {
+    service
+        .clear_cache()
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 #[tauri::command]
 pub async fn enrich_tracks_batch(
     service: State<'_, LastFmService>,
     tracks: Vec<MusicFile>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<EnrichedTrack>> {
     service
         .enrich_tracks_batch(tracks)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
@@ -0,0 +1,60 @@
+//! Logging and diagnostics command handlers
+
+use tauri::{AppHandle, State};
+
+use crate::domain::diagnostics::{AudioOutput, Diagnostics};
+use crate::errors::{ApiResponse, AppError, Locale};
+use crate::services::{DiagnosticsService, LastFmService, LogState, SpotifyState};
+
+/// Changes the log level live (`trace`, `debug`, `info`, `warn`, `error` or `off`)
+///
+/// Applies until the app restarts, when `RUST_LOG` takes over again.
+#[tauri::command]
+pub fn set_log_level(
+    level: String,
+    logging: State<'_, LogState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    logging
+        .set_level(&level)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Returns the path of the current log file, to attach to bug reports
+///
+/// Logs rotate daily and the last week is kept next to it.
+#[tauri::command]
+pub fn get_log_file_path(locale: State<'_, Locale>) -> ApiResponse<String> {
+    LogState::current_log_file()
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Collects versions of the external tools and the app's configuration
+///
+/// Meant for a "copy diagnostics" button; missing tools are reported, not errors.
+#[tauri::command]
+pub async fn get_diagnostics(
+    app_handle: AppHandle,
+    spotify: State<'_, SpotifyState>,
+    lastfm: State<'_, LastFmService>,
+) -> ApiResponse<Diagnostics> {
+    Ok(DiagnosticsService::collect(
+        app_handle.package_info().version.to_string(),
+        spotify.is_authenticated(),
+        lastfm.has_api_key(),
+    )
+    .await)
+}
+
+/// Lists the audio output devices and marks the default one
+///
+/// Informational only: playback stays in the webview. Empty when there is no
+/// device or the app was built without the `audio-devices` feature.
+#[tauri::command]
+pub async fn list_audio_outputs(locale: State<'_, Locale>) -> ApiResponse<Vec<AudioOutput>> {
+    tokio::task::spawn_blocking(DiagnosticsService::list_audio_outputs)
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
@@ -3,12 +3,22 @@
 //! This module contains all Tauri command handlers that act as thin controllers.
 //! They delegate business logic to services and convert errors to user-friendly strings.
 
+pub mod acoustid;
+pub mod convert;
 pub mod download;
 pub mod file;
+pub mod http;
 pub mod lastfm;
+pub mod logging;
+pub mod settings;
 pub mod spotify;
 
+pub use acoustid::*;
+pub use convert::*;
 pub use download::*;
 pub use file::*;
+pub use http::*;
 pub use lastfm::*;
+pub use logging::*;
+pub use settings::*;
 pub use spotify::*;
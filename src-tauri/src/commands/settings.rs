@@ -0,0 +1,30 @@
+//! Settings command handlers
+
+use tauri::State;
+
+use crate::domain::settings::Settings;
+use crate::errors::{ApiResponse, Locale};
+use crate::services::SettingsState;
+
+/// Gets the current app settings
+#[tauri::command]
+pub fn get_settings(
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Settings> {
+    settings
+        .get()
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Validates and saves the app settings, returning the stored values
+#[tauri::command]
+pub fn update_settings(
+    new_settings: Settings,
+    settings: State<'_, SettingsState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Settings> {
+    settings
+        .update(new_settings)
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
@@ -2,40 +2,108 @@
 
 use tauri::{AppHandle, State, Window};
 
-use crate::domain::spotify::{SpotifyArtist, SpotifyPlaylist, SpotifyTrack, SpotifyUserProfile};
-use crate::errors::ApiResponse;
+use crate::domain::spotify::{
+    ExportFormat, SpotifyAlbum, SpotifyArtist, SpotifyAuthStatus, SpotifyDownloadUrls,
+    SpotifyEpisode, SpotifyFollowedArtists, SpotifyPlaylist, SpotifyPlaylistPage,
+    SpotifyRecommendationTargets, SpotifyTrack, SpotifyTrackPreview, SpotifyUserProfile,
+};
+use crate::errors::{ApiResponse, Locale};
 use crate::services::spotify::{SpotifyService, SpotifyState};
+use crate::services::AllowedRoots;
 
-/// Initializes and authenticates with Spotify using Authorization Code Flow
+/// Initializes and authenticates with Spotify using the configured OAuth flow
+///
+/// `timeout_secs` is how long to wait for the browser login (30-600, default 120).
 #[tauri::command]
 pub async fn spotify_authenticate(
     state: State<'_, SpotifyState>,
     app: AppHandle,
+    timeout_secs: Option<u64>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<String> {
-    SpotifyService::authenticate(&state, &app)
+    SpotifyService::authenticate(&state, &app, timeout_secs)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Authenticates with Spotify using the PKCE flow, which needs no client secret
+///
+/// `timeout_secs` is how long to wait for the browser login (30-600, default 120).
+#[tauri::command]
+pub async fn spotify_authenticate_pkce(
+    state: State<'_, SpotifyState>,
+    app: AppHandle,
+    timeout_secs: Option<u64>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<String> {
+    SpotifyService::authenticate_pkce(&state, &app, timeout_secs)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Gets the authenticated user's profile information
+///
+/// The profile is fetched once per session and then served from memory;
+/// `force_refresh` (default false) fetches it again.
 #[tauri::command]
 pub async fn spotify_get_profile(
     state: State<'_, SpotifyState>,
+    force_refresh: Option<bool>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<SpotifyUserProfile> {
-    SpotifyService::get_profile(&state)
+    SpotifyService::get_profile(&state, force_refresh.unwrap_or(false))
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
-/// Gets the user's playlists with optional limit
+/// Gets one page of the user's playlists, with the total count for paging
+///
+/// `limit` defaults to 20 (max 50) and `offset` to 0.
 #[tauri::command]
 pub async fn spotify_get_playlists(
     state: State<'_, SpotifyState>,
     limit: Option<u32>,
+    offset: Option<u32>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<SpotifyPlaylistPage> {
+    SpotifyService::get_playlists(&state, limit, offset)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets all of the user's playlists, paging past the 50-per-request limit
+#[tauri::command]
+pub async fn spotify_get_all_playlists(
+    state: State<'_, SpotifyState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyPlaylist>> {
+    SpotifyService::get_all_playlists(&state)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets Spotify's new album releases (limit defaults to 20, max 50)
+#[tauri::command]
+pub async fn spotify_get_new_releases(
+    state: State<'_, SpotifyState>,
+    limit: Option<u32>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyAlbum>> {
+    SpotifyService::get_new_releases(&state, limit)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets Spotify's featured playlists (limit defaults to 20, max 50)
+#[tauri::command]
+pub async fn spotify_get_featured_playlists(
+    state: State<'_, SpotifyState>,
+    limit: Option<u32>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<SpotifyPlaylist>> {
-    SpotifyService::get_playlists(&state, limit)
+    SpotifyService::get_featured_playlists(&state, limit)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Gets the user's saved tracks with pagination support
@@ -44,34 +112,187 @@ pub async fn spotify_get_saved_tracks(
     state: State<'_, SpotifyState>,
     limit: Option<u32>,
     offset: Option<u32>,
+    market: Option<String>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyTrack>> {
+    SpotifyService::get_saved_tracks(&state, limit, offset, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets how many tracks the user has saved
+#[tauri::command]
+pub async fn spotify_get_saved_tracks_count(
+    state: State<'_, SpotifyState>,
+    market: Option<String>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<u32> {
+    SpotifyService::get_saved_tracks_count(&state, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets the user's saved podcast episodes with pagination support
+#[tauri::command]
+pub async fn spotify_get_saved_episodes(
+    state: State<'_, SpotifyState>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyEpisode>> {
+    SpotifyService::get_saved_episodes(&state, limit, offset)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets a single track from a raw id, `open.spotify.com/track/<id>` URL or `spotify:track:<id>` URI
+#[tauri::command]
+pub async fn spotify_get_track(
+    state: State<'_, SpotifyState>,
+    track_id: String,
+    locale: State<'_, Locale>,
+) -> ApiResponse<SpotifyTrack> {
+    SpotifyService::get_track(&state, &track_id)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets many tracks by id, URL or URI in input order; ids Spotify cannot find are skipped
+#[tauri::command]
+pub async fn spotify_get_tracks(
+    state: State<'_, SpotifyState>,
+    track_ids: Vec<String>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<SpotifyTrack>> {
-    SpotifyService::get_saved_tracks(&state, limit, offset)
+    SpotifyService::get_tracks(&state, track_ids)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Gets the user's top artists based on listening history
+///
+/// `time_range` is `short_term`, `medium_term` (default) or `long_term`.
 #[tauri::command]
 pub async fn spotify_get_top_artists(
     state: State<'_, SpotifyState>,
     limit: Option<u32>,
     time_range: Option<String>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<SpotifyArtist>> {
     SpotifyService::get_top_artists(&state, limit, time_range)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets artists related to the given artist id, URL or URI
+#[tauri::command]
+pub async fn spotify_get_related_artists(
+    state: State<'_, SpotifyState>,
+    artist_id: String,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyArtist>> {
+    SpotifyService::get_related_artists(&state, &artist_id)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets an artist's top tracks; `market` defaults to the user's country, then `US`
+#[tauri::command]
+pub async fn spotify_get_artist_top_tracks(
+    state: State<'_, SpotifyState>,
+    artist_id: String,
+    market: Option<String>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyTrack>> {
+    SpotifyService::get_artist_top_tracks(&state, &artist_id, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets recommendations from 1-5 combined artist, track and genre seeds
+#[tauri::command]
+pub async fn spotify_get_recommendations(
+    state: State<'_, SpotifyState>,
+    seed_artists: Option<Vec<String>>,
+    seed_tracks: Option<Vec<String>>,
+    seed_genres: Option<Vec<String>>,
+    targets: Option<SpotifyRecommendationTargets>,
+    limit: Option<u32>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyTrack>> {
+    SpotifyService::get_recommendations(
+        &state,
+        seed_artists.unwrap_or_default(),
+        seed_tracks.unwrap_or_default(),
+        seed_genres.unwrap_or_default(),
+        targets,
+        limit,
+    )
+    .await
+    .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets a page of followed artists; pass the returned `nextCursor` as `after` to continue
+#[tauri::command]
+pub async fn spotify_get_followed_artists(
+    state: State<'_, SpotifyState>,
+    limit: Option<u32>,
+    after: Option<String>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<SpotifyFollowedArtists> {
+    SpotifyService::get_followed_artists(&state, limit, after)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Gets the user's top tracks with optional time range and limit
+///
+/// `time_range` is `short_term`, `medium_term` (default) or `long_term`.
 #[tauri::command]
 pub async fn spotify_get_top_tracks(
     state: State<'_, SpotifyState>,
     limit: Option<u32>,
     time_range: Option<String>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<Vec<SpotifyTrack>> {
     SpotifyService::get_top_tracks(&state, limit, time_range)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Gets all saved songs in a single response
+/// Use `spotify_stream_all_liked_songs` for large libraries (>1000 songs)
+#[tauri::command]
+pub async fn spotify_get_all_liked_songs(
+    state: State<'_, SpotifyState>,
+    market: Option<String>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Vec<SpotifyTrack>> {
+    SpotifyService::get_all_liked_songs(&state, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Exports all saved songs to a JSON or CSV file, returning how many were written
+/// The file must be inside an allowed root.
+/// Emits `spotify-export-progress` events while it runs
+#[tauri::command]
+pub async fn spotify_export_liked_songs(
+    state: State<'_, SpotifyState>,
+    window: Window,
+    path: String,
+    format: ExportFormat,
+    market: Option<String>,
+    roots: State<'_, AllowedRoots>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<u32> {
+    roots
+        .check_output(&path)
+        .map_err(|e| e.to_user_message_localized(*locale))?;
+
+    SpotifyService::export_liked_songs(&state, &window, &path, format, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Transmits saved songs progressively using Tauri events
@@ -80,16 +301,43 @@ pub async fn spotify_get_top_tracks(
 pub async fn spotify_stream_all_liked_songs(
     state: State<'_, SpotifyState>,
     window: Window,
+    market: Option<String>,
+    locale: State<'_, Locale>,
 ) -> ApiResponse<()> {
-    SpotifyService::stream_all_liked_songs(&state, &window)
+    SpotifyService::stream_all_liked_songs(&state, &window, market)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Builds download URLs for a track selection, skipping local tracks
+#[tauri::command]
+pub fn spotify_build_download_urls(tracks: Vec<SpotifyTrack>) -> SpotifyDownloadUrls {
+    SpotifyService::build_download_urls(&tracks)
+}
+
+/// Checks a download URL before queuing a batch
+///
+/// Returns the track's details when authenticated; otherwise only the URL format is validated.
+#[tauri::command]
+pub async fn preview_spotify_track(
+    state: State<'_, SpotifyState>,
+    url: String,
+    locale: State<'_, Locale>,
+) -> ApiResponse<SpotifyTrackPreview> {
+    SpotifyService::preview_track(&state, &url)
         .await
-        .map_err(|e| e.to_user_message())
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
-/// Closes the Spotify session and cleans up resources
+/// Closes the Spotify session and deletes the cached token
 #[tauri::command]
-pub fn spotify_logout(state: State<'_, SpotifyState>) -> ApiResponse<()> {
-    state.clear().map_err(|e| e.to_user_message())
+pub fn spotify_logout(
+    state: State<'_, SpotifyState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<()> {
+    state
+        .clear()
+        .map_err(|e| e.to_user_message_localized(*locale))
 }
 
 /// Verifies if there's an active Spotify session
@@ -97,3 +345,42 @@ pub fn spotify_logout(state: State<'_, SpotifyState>) -> ApiResponse<()> {
 pub fn spotify_is_authenticated(state: State<'_, SpotifyState>) -> bool {
     state.is_authenticated()
 }
+
+/// Seconds until the access token expires (negative once expired), `None` without a session
+///
+/// Lets the UI refresh the session before starting a long operation.
+#[tauri::command]
+pub async fn spotify_token_expires_in(
+    state: State<'_, SpotifyState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Option<i64>> {
+    state
+        .token_expires_in()
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Refreshes the access token now, returning its new expiry (Unix seconds)
+///
+/// Fails when not authenticated or when the session can't be refreshed.
+#[tauri::command]
+pub async fn spotify_refresh_token(
+    state: State<'_, SpotifyState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<Option<i64>> {
+    SpotifyService::refresh_token(&state)
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
+
+/// Reports session, token expiry and cached display name without calling Spotify
+#[tauri::command]
+pub async fn spotify_auth_status(
+    state: State<'_, SpotifyState>,
+    locale: State<'_, Locale>,
+) -> ApiResponse<SpotifyAuthStatus> {
+    state
+        .auth_status()
+        .await
+        .map_err(|e| e.to_user_message_localized(*locale))
+}
@@ -0,0 +1,56 @@
+//! AcoustID domain types
+
+use serde::Deserialize;
+
+/// Output of `fpcalc -json`
+#[derive(Debug, Deserialize)]
+pub struct FpcalcOutput {
+    /// Audio duration in seconds
+    pub duration: f64,
+    /// Chromaprint fingerprint
+    pub fingerprint: String,
+}
+
+// Internal AcoustID API types for deserialization
+pub mod raw {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    pub struct LookupResponse {
+        pub status: String,
+        #[serde(default)]
+        pub results: Vec<LookupResult>,
+        pub error: Option<ApiError>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ApiError {
+        pub message: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct LookupResult {
+        pub score: f64,
+        #[serde(default)]
+        pub recordings: Vec<Recording>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Recording {
+        pub title: Option<String>,
+        #[serde(default)]
+        pub artists: Vec<Artist>,
+        #[serde(default)]
+        pub releasegroups: Vec<ReleaseGroup>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Artist {
+        pub name: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ReleaseGroup {
+        pub title: String,
+    }
+}
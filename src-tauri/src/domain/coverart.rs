@@ -0,0 +1,52 @@
+//! MusicBrainz / Cover Art Archive domain types
+
+use serde::Deserialize;
+
+// Internal API types for deserialization
+pub mod raw {
+    use super::*;
+
+    /// `GET /ws/2/release?query=...&fmt=json`
+    #[derive(Debug, Deserialize)]
+    pub struct ReleaseSearch {
+        #[serde(default)]
+        pub releases: Vec<Release>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Release {
+        /// Search relevance, 0-100
+        #[serde(default)]
+        pub score: u32,
+        #[serde(rename = "release-group")]
+        pub release_group: Option<ReleaseGroup>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ReleaseGroup {
+        pub id: String,
+    }
+
+    /// `GET https://coverartarchive.org/release-group/{mbid}`
+    #[derive(Debug, Deserialize)]
+    pub struct CoverArtListing {
+        #[serde(default)]
+        pub images: Vec<CoverArtImage>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct CoverArtImage {
+        #[serde(default)]
+        pub front: bool,
+        pub image: String,
+        #[serde(default)]
+        pub thumbnails: CoverArtThumbnails,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    pub struct CoverArtThumbnails {
+        #[serde(rename = "500")]
+        pub medium: Option<String>,
+        pub large: Option<String>,
+    }
+}
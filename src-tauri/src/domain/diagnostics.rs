@@ -0,0 +1,43 @@
+//! Diagnostics domain types
+
+use serde::Serialize;
+
+/// Audio output device, as reported by the OS audio host
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioOutput {
+    /// Device name as shown by the OS
+    pub name: String,
+    /// Lowest supported sample rate in Hz, `None` when the device reports no configs
+    pub min_sample_rate: Option<u32>,
+    /// Highest supported sample rate in Hz
+    pub max_sample_rate: Option<u32>,
+    /// Whether this is the system's default output
+    pub is_default: bool,
+}
+
+/// Snapshot of the app's environment for bug reports
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// App version from `tauri.conf.json`
+    pub app_version: String,
+    /// Operating system family (`windows`, `macos`, `linux`, ...)
+    pub os: String,
+    /// CPU architecture (`x86_64`, `aarch64`, ...)
+    pub arch: String,
+    /// `spotdl --version` output, `None` when not installed
+    pub spotdl_version: Option<String>,
+    /// Whether the installed spotdl is recent enough, `None` when not installed
+    pub spotdl_meets_minimum: Option<bool>,
+    /// `yt-dlp --version` output, `None` when not installed
+    pub yt_dlp_version: Option<String>,
+    /// First line of `ffmpeg -version`, `None` when not installed
+    pub ffmpeg_version: Option<String>,
+    /// Whether a Spotify session is active
+    pub spotify_authenticated: bool,
+    /// Whether a Last.fm API key is configured
+    pub lastfm_configured: bool,
+    /// Default music folder, `None` when it can't be determined
+    pub default_music_folder: Option<String>,
+}
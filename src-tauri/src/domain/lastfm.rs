@@ -46,6 +46,18 @@ pub struct ProcessedAlbumInfo {
     pub track_count: u32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedTopTrack {
+    pub name: String,
+    pub artist: String,
+    pub rank: Option<u32>,
+    pub playcount: Option<u64>,
+    pub listeners: Option<u64>,
+    pub url: String,
+    pub image: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrichedTrack {
@@ -54,6 +66,20 @@ pub struct EnrichedTrack {
     pub album_art_url: Option<String>,
 }
 
+/// Entries in each Last.fm cache and how lookups were served since startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastFmCacheStats {
+    pub track_entries: usize,
+    pub artist_entries: usize,
+    pub album_entries: usize,
+    pub artist_top_entries: usize,
+    /// Lookups answered from the cache without a request
+    pub hits: u64,
+    /// Lookups that needed a request (including conditional refreshes)
+    pub misses: u64,
+}
+
 // Internal Last.fm API types for deserialization
 #[derive(Debug, Deserialize)]
 pub struct LastFmImage {
@@ -62,6 +88,39 @@ pub struct LastFmImage {
     pub size: String,
 }
 
+/// Last.fm image sizes, smallest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageSize {
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+    Mega,
+}
+
+impl ImageSize {
+    /// Default preference: the largest image available
+    pub const LARGEST_FIRST: [ImageSize; 5] = [
+        ImageSize::Mega,
+        ImageSize::ExtraLarge,
+        ImageSize::Large,
+        ImageSize::Medium,
+        ImageSize::Small,
+    ];
+
+    /// Name Last.fm uses in the `size` field
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageSize::Small => "small",
+            ImageSize::Medium => "medium",
+            ImageSize::Large => "large",
+            ImageSize::ExtraLarge => "extralarge",
+            ImageSize::Mega => "mega",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LastFmTag {
     pub name: String,
@@ -122,6 +181,34 @@ pub mod raw {
         pub tracks: Option<TracksWrapper>,
     }
 
+    #[derive(Debug, Deserialize)]
+    pub struct TopTracksResponse {
+        pub toptracks: TopTracks,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct TopTracks {
+        #[serde(default)]
+        pub track: Vec<TopTrack>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct TopTrack {
+        pub name: String,
+        pub playcount: Option<String>,
+        pub listeners: Option<String>,
+        pub url: String,
+        pub artist: ArtistShort,
+        pub image: Option<Vec<LastFmImage>>,
+        #[serde(rename = "@attr")]
+        pub attr: Option<RankAttr>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct RankAttr {
+        pub rank: String,
+    }
+
     #[derive(Debug, Deserialize)]
     pub struct ArtistShort {
         pub name: String,
@@ -3,6 +3,10 @@
 //! This module contains all the data structures used throughout the application,
 //! including types for music files, Spotify data, and API responses.
 
+pub mod acoustid;
+pub mod coverart;
+pub mod diagnostics;
 pub mod lastfm;
 pub mod music;
+pub mod settings;
 pub mod spotify;
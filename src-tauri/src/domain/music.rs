@@ -2,16 +2,27 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::domain::spotify::SpotifyTrack;
+use crate::utils::normalize_for_search;
+
 /// Represents a local music file with extracted metadata
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MusicFile {
-    /// Absolute path to the audio file
+    /// Canonical absolute path to the audio file
+    ///
+    /// Scans and metadata lookups always return this form, so it can be used
+    /// as a stable key for dedupe and later lookups. It is never converted lossily,
+    /// so it always opens the same file: a path that isn't valid Unicode is given
+    /// as `native-path:<base64 of the OS bytes>`, which every file command accepts.
     pub path: String,
     /// Song title extracted from metadata
     pub title: Option<String>,
-    /// Artist name from metadata
+    /// Artist name from metadata, all artist frames joined for display
     pub artist: Option<String>,
+    /// Individual artists, split from multi-value frames and credits like "A feat. B"
+    #[serde(default)]
+    pub artists: Vec<String>,
     /// Album name from metadata
     pub album: Option<String>,
     /// Duration in seconds
@@ -20,8 +31,14 @@ pub struct MusicFile {
     pub year: Option<i32>,
     /// Music genre
     pub genre: Option<String>,
+    /// Track number within the album
+    pub track_number: Option<u16>,
     /// Base64 encoded album art image
     pub album_art: Option<String>,
+    /// ReplayGain track gain in dB
+    pub replaygain_track_gain: Option<f32>,
+    /// ReplayGain track peak as a linear sample amplitude
+    pub replaygain_track_peak: Option<f32>,
 }
 
 impl MusicFile {
@@ -31,20 +48,252 @@ impl MusicFile {
             path,
             title,
             artist: None,
+            artists: Vec::new(),
             album: None,
             duration: None,
             year: None,
             genre: None,
+            track_number: None,
             album_art: None,
+            replaygain_track_gain: None,
+            replaygain_track_peak: None,
         }
     }
+
+    /// Title, artist, album and genre normalized for search, one per line
+    ///
+    /// Lets a query be matched against every field with a single substring check.
+    /// Compute it once per file when filtering many files; it is not cached since
+    /// files arrive from the frontend on every call.
+    pub fn search_blob(&self) -> String {
+        [&self.title, &self.artist, &self.album, &self.genre]
+            .into_iter()
+            .flatten()
+            .map(|value| normalize_for_search(value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Result of importing an M3U/PLS playlist
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedPlaylist {
+    /// Tracks found on disk, in playlist order
+    pub tracks: Vec<MusicFile>,
+    /// Entries that don't exist, aren't supported audio files or are outside the
+    /// allowed roots, as written in the playlist
+    pub missing: Vec<String>,
+}
+
+/// Folder that could not be scanned in a multi-folder scan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedFolder {
+    /// Folder path as requested
+    pub path: String,
+    /// Why it was skipped
+    pub error: String,
+}
+
+/// Result of scanning several music folders
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiFolderScan {
+    /// Files from every scanned folder, without duplicates
+    pub files: Vec<MusicFile>,
+    /// Folders that were skipped
+    pub failed_folders: Vec<FailedFolder>,
+}
+
+/// A Spotify track found in the local library
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedTrack {
+    /// Track from Spotify
+    pub spotify: SpotifyTrack,
+    /// Local file with the same artist and title
+    pub local: MusicFile,
+}
+
+/// Spotify tracks split by whether the local library already has them
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryMatch {
+    /// Tracks with a local copy, in Spotify order
+    pub matched: Vec<MatchedTrack>,
+    /// Tracks missing from the library, in Spotify order
+    pub unmatched: Vec<SpotifyTrack>,
+}
+
+/// Track counts per release decade
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct YearHistogram {
+    /// `(decade_start, count)` pairs sorted by decade, e.g. `(1990, 12)` for 1990-1999
+    pub decades: Vec<(i32, u32)>,
+    /// Tracks without a year or with an implausible one
+    pub unknown: u32,
+}
+
+/// Where a track's lyrics were found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LyricsSource {
+    /// `.lrc` file next to the audio file (usually time-synced)
+    Lrc,
+    /// Lyrics frame in the file's tags (e.g. ID3v2 USLT)
+    Embedded,
+}
+
+/// Lyrics for a track
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lyrics {
+    /// Lyrics text; `.lrc` timestamps are kept as-is
+    pub text: String,
+    /// Where the lyrics came from
+    pub source: LyricsSource,
+}
+
+/// Metadata field a library search can be restricted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+}
+
+/// Key used to sort a scanned library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    Title,
+    Artist,
+    Album,
+    Year,
+    Duration,
+    TrackNumber,
 }
 
+/// Genre bucket for tracks without a genre tag
+pub const UNKNOWN_GENRE: &str = "Unknown";
+
 /// Supported audio file extensions
 pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "m4a", "flac", "wav", "ogg", "aac", "wma"];
 
+/// Output formats accepted for spotdl downloads
+pub const DOWNLOAD_FORMATS: &[&str] = &["mp3", "flac", "ogg", "m4a", "opus"];
+
+/// Formats the backend accepts, so UI dropdowns don't hardcode them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedFormats {
+    /// Formats spotdl can download to
+    pub download_formats: Vec<String>,
+    /// Extensions picked up when scanning the library
+    pub audio_extensions: Vec<String>,
+}
+
+impl SupportedFormats {
+    /// Builds the lists from the shared constants
+    pub fn current() -> Self {
+        Self {
+            download_formats: DOWNLOAD_FORMATS.iter().map(ToString::to_string).collect(),
+            audio_extensions: AUDIO_EXTENSIONS.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// Rules applied by `normalize_tags`; each is off unless set
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TagNormalizeOptions {
+    /// Trim and collapse whitespace in every field
+    pub trim_whitespace: bool,
+    /// Title-case fields written entirely in lower or upper case (all-caps names
+    /// like "ABBA" included)
+    pub title_case: bool,
+    /// Move a leading "The" in artist and album artist to the end ("Beatles, The")
+    pub move_leading_article: bool,
+    /// Drop remaster notes like " (Remastered 2009)" from title and album
+    pub strip_remaster_suffix: bool,
+    /// Report the changes without writing them
+    pub dry_run: bool,
+}
+
+/// One tag field changed by `normalize_tags`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagChange {
+    /// `title`, `artist`, `album`, `albumArtist` or `genre`
+    pub field: String,
+    /// Value before normalizing
+    pub before: String,
+    /// Normalized value
+    pub after: String,
+}
+
+/// Tag changes made (or, in a dry run, proposed) for one file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagNormalization {
+    /// File path as given
+    pub path: String,
+    /// Changed fields; empty when the tags were already normalized
+    pub changes: Vec<TagChange>,
+}
+
 /// Maximum depth for directory scanning (security limit)
 pub const MAX_SCAN_DEPTH: usize = 10;
 
 /// Maximum number of files to process in a single scan (security limit)
 pub const MAX_FILES_PER_SCAN: usize = 10000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_blob_folds_case_and_accents_per_field() {
+        let mut file = MusicFile::new("/music/song.mp3".to_string(), Some("Jóga".to_string()));
+        file.artist = Some("BJÖRK".to_string());
+        file.genre = Some("Électronique".to_string());
+
+        let blob = file.search_blob();
+
+        assert_eq!(blob, "joga\nbjork\nelectronique");
+        // Fields stay on separate lines so a query can't match across them
+        assert!(!blob.contains("joga bjork"));
+    }
+
+    #[test]
+    fn supported_formats_match_the_validators() {
+        use crate::utils::{is_audio_file, validate_download_format};
+        use std::path::Path;
+
+        let formats = SupportedFormats::current();
+
+        assert!(!formats.download_formats.is_empty());
+        for format in &formats.download_formats {
+            assert!(validate_download_format(format).is_ok(), "{}", format);
+        }
+        for format in ["MP3", "", "exe"] {
+            assert!(validate_download_format(format).is_err(), "{}", format);
+        }
+
+        assert!(!formats.audio_extensions.is_empty());
+        for extension in &formats.audio_extensions {
+            assert!(is_audio_file(Path::new(&format!("song.{}", extension))));
+            assert!(is_audio_file(Path::new(&format!(
+                "SONG.{}",
+                extension.to_uppercase()
+            ))));
+        }
+        for name in ["song.opus.txt", "cover.jpg", "mp3"] {
+            assert!(!is_audio_file(Path::new(name)), "{}", name);
+        }
+    }
+}
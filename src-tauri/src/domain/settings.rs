@@ -0,0 +1,47 @@
+//! App settings domain types
+
+use serde::{Deserialize, Serialize};
+
+/// Download format used when a command doesn't specify one
+pub const DEFAULT_DOWNLOAD_FORMAT: &str = "mp3";
+/// spotdl batches run at the same time unless the user changes it
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+/// Accepted range for `max_concurrent_downloads`
+pub const MIN_CONCURRENT_DOWNLOADS: usize = 1;
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+/// Failed spotdl batches in a row after which a download run gives up
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: usize = 5;
+
+/// User settings persisted in the app config directory
+///
+/// Commands fall back to these when their optional parameters are omitted.
+/// Missing fields in the settings file take their default value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Download format (one of the supported download formats)
+    pub default_format: String,
+    /// Folder downloads are written to, `None` for spotdl's working directory
+    pub output_dir: Option<String>,
+    /// spotdl batches run at the same time (1-8); they share 8 download threads
+    pub max_concurrent_downloads: usize,
+    /// Failed spotdl batches in a row that abort the rest of a download run (at least 1)
+    pub max_consecutive_failures: usize,
+    /// Cookies file passed to spotdl (`--cookie-file`), e.g. for YouTube Music Premium
+    pub cookie_file: Option<String>,
+    /// Whether folder scans follow symlinked folders
+    pub follow_symlinks: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            default_format: DEFAULT_DOWNLOAD_FORMAT.to_string(),
+            output_dir: None,
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            max_consecutive_failures: DEFAULT_MAX_CONSECUTIVE_FAILURES,
+            cookie_file: None,
+            follow_symlinks: false,
+        }
+    }
+}
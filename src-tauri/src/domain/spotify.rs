@@ -2,6 +2,18 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Session state for rendering a login badge without a network call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyAuthStatus {
+    /// Whether a Spotify client is stored
+    pub authenticated: bool,
+    /// Whether the access token is missing or past its expiry (it is refreshed on the next request)
+    pub token_expired: bool,
+    /// Display name from the cached profile, if it was fetched
+    pub display_name: Option<String>,
+}
+
 /// Spotify user profile information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,7 +24,7 @@ pub struct SpotifyUserProfile {
     pub display_name: Option<String>,
     /// User's email address
     pub email: Option<String>,
-    /// User's country code
+    /// User's country as an ISO 3166-1 alpha-2 code (e.g. `ES`)
     pub country: Option<String>,
     /// Spotify subscription type
     pub product: Option<String>,
@@ -86,8 +98,171 @@ pub struct SpotifyArtist {
     pub external_url: Option<String>,
 }
 
+/// Saved podcast episode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyEpisode {
+    /// Spotify episode ID
+    pub id: String,
+    /// Episode name
+    pub name: String,
+    /// Name of the show the episode belongs to
+    pub show: String,
+    /// Show publisher
+    pub publisher: String,
+    /// Episode description (plain text)
+    pub description: String,
+    /// Episode cover image URL
+    pub image: Option<String>,
+    /// Episode duration in milliseconds
+    pub duration_ms: u32,
+    /// Where the user stopped listening, in milliseconds
+    ///
+    /// `None` without the `user-read-playback-position` scope.
+    pub resume_position_ms: Option<u32>,
+    /// Whether the user finished the episode
+    pub fully_played: bool,
+    /// Release date as reported by Spotify (year, month or day precision)
+    pub release_date: String,
+    /// External Spotify URL
+    pub external_url: Option<String>,
+}
+
+/// Spotify album information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyAlbum {
+    /// Album ID
+    pub id: Option<String>,
+    /// Album name
+    pub name: String,
+    /// List of artist names
+    pub artists: Vec<String>,
+    /// Album type (album, single, compilation)
+    pub album_type: Option<String>,
+    /// Release date as reported by Spotify (year, month or day precision)
+    pub release_date: Option<String>,
+    /// Cover image URLs
+    pub images: Vec<String>,
+    /// External Spotify URL
+    pub external_url: Option<String>,
+}
+
+/// One page of the user's playlists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyPlaylistPage {
+    /// Playlists in this page
+    pub playlists: Vec<SpotifyPlaylist>,
+    /// Index of the first playlist of the page
+    pub offset: u32,
+    /// Total number of playlists the user has
+    pub total: u32,
+}
+
+/// One page of followed artists, paginated by cursor rather than offset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyFollowedArtists {
+    /// Artists in this page
+    pub artists: Vec<SpotifyArtist>,
+    /// Cursor to pass as `after` for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+    /// Total number of followed artists, when Spotify reports it
+    pub total: Option<u32>,
+}
+
+/// Optional tuning targets for recommendations, each in `0.0..=1.0`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyRecommendationTargets {
+    /// Target energy (intensity and activity)
+    pub target_energy: Option<f32>,
+    /// Target valence (musical positiveness)
+    pub target_valence: Option<f32>,
+}
+
+/// Download URLs built from a track selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyDownloadUrls {
+    /// `open.spotify.com/track/<id>` URLs, in selection order
+    pub urls: Vec<String>,
+    /// Tracks without a usable id (local files), which were left out
+    pub skipped: usize,
+}
+
+/// A download URL checked before queuing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotifyTrackPreview {
+    /// Canonical `open.spotify.com/track/<id>` URL
+    pub url: String,
+    /// Spotify track ID
+    pub id: String,
+    /// Track details; `None` when not authenticated, so only the URL format was checked
+    pub track: Option<SpotifyTrack>,
+}
+
+/// File format for exported track lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// JSON array of track objects
+    Json,
+    /// CSV with one track per row
+    Csv,
+}
+
 /// Spotify API configuration constants
 pub const SPOTIFY_BATCH_SIZE: u32 = 50;
 pub const MAX_RETRY_ATTEMPTS: u32 = 3;
+/// Wait before retrying a failed page, doubled after each further failure
+pub const RETRY_BASE_DELAY_MS: u64 = 1000;
 pub const OAUTH_CALLBACK_TIMEOUT_SECS: u64 = 120; // 2 minutes
+/// Bounds for a caller-provided OAuth timeout (fresh logins with 2FA take a while)
+pub const MIN_OAUTH_TIMEOUT_SECS: u64 = 30;
+pub const MAX_OAUTH_TIMEOUT_SECS: u64 = 600;
 pub const OAUTH_SERVER_ADDR: &str = "127.0.0.1:8888";
+/// Environment variable overriding the requested scopes (comma-separated)
+pub const SPOTIFY_SCOPES_ENV: &str = "SPOTIFY_SCOPES";
+/// Scopes requested when `SPOTIFY_SCOPES` is unset
+pub const DEFAULT_SPOTIFY_SCOPES: &[&str] = &[
+    "user-read-private",
+    "user-read-email",
+    "user-library-read",
+    "playlist-read-private",
+    "playlist-read-collaborative",
+    "user-top-read",
+    "user-read-recently-played",
+    "user-follow-read",
+    "user-read-playback-position",
+];
+/// Every scope Spotify's Web API defines
+pub const KNOWN_SPOTIFY_SCOPES: &[&str] = &[
+    "ugc-image-upload",
+    "user-read-playback-state",
+    "user-modify-playback-state",
+    "user-read-currently-playing",
+    "app-remote-control",
+    "streaming",
+    "playlist-read-private",
+    "playlist-read-collaborative",
+    "playlist-modify-private",
+    "playlist-modify-public",
+    "user-follow-modify",
+    "user-follow-read",
+    "user-read-playback-position",
+    "user-top-read",
+    "user-read-recently-played",
+    "user-library-modify",
+    "user-library-read",
+    "user-read-email",
+    "user-read-private",
+];
+/// Market used when neither the caller nor the cached profile provides one
+pub const DEFAULT_MARKET: &str = "US";
+/// Spotify accepts up to five seeds (artists, tracks and genres combined)
+pub const MAX_RECOMMENDATION_SEEDS: usize = 5;
+/// Maximum number of tracks the recommendations endpoint returns
+pub const MAX_RECOMMENDATIONS_LIMIT: u32 = 100;
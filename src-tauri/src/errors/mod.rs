@@ -20,6 +20,12 @@ pub enum AppError {
     #[error("Download error: {0}")]
     Download(#[from] DownloadError),
 
+    #[error("AcoustID error: {0}")]
+    AcoustId(#[from] AcoustIdError),
+
+    #[error("Conversion error: {0}")]
+    Convert(#[from] ConvertError),
+
     #[error("Validation error: {0}")]
     Validation(String),
 
@@ -34,6 +40,12 @@ pub enum AppError {
 
     #[error("External API error: {0}")]
     ExternalApi(String),
+
+    #[error("Last.fm Error {code}: {message}")]
+    LastFmApi { code: i32, message: String },
+
+    #[error("Not available offline: {0}")]
+    Offline(String),
 }
 
 /// File system related errors
@@ -42,6 +54,9 @@ pub enum FileError {
     #[error("Path does not exist: {0}")]
     NotFound(String),
 
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     #[error("Path traversal attempt detected: {0}")]
     PathTraversal(String),
 
@@ -57,9 +72,21 @@ pub enum FileError {
     #[error("Unsupported file format: {0}")]
     UnsupportedFormat(String),
 
+    #[error("Not a JPEG, PNG, GIF, WebP or BMP image: {0}")]
+    NotImage(String),
+
+    #[error("Failed to decode audio: {0}")]
+    Decode(String),
+
+    #[error("Failed to export: {0}")]
+    Export(String),
+
     #[error("Failed to read metadata: {0}")]
     MetadataRead(String),
 
+    #[error("No embedded album art: {0}")]
+    NoAlbumArt(String),
+
     #[error("Failed to canonicalize path: {0}")]
     Canonicalize(String),
 
@@ -68,6 +95,21 @@ pub enum FileError {
 
     #[error("Scan depth exceeded: max {0} levels")]
     ScanDepthExceeded(usize),
+
+    #[error("Scan cancelled")]
+    ScanCancelled,
+
+    #[error("Failed to open file manager: {0}")]
+    RevealFailed(String),
+
+    #[error("Path is outside the allowed folders: {0}")]
+    OutsideAllowedRoots(String),
+
+    #[error("Failed to move to trash: {0}")]
+    Trash(String),
+
+    #[error("Failed to write tags: {0}")]
+    TagWrite(String),
 }
 
 /// Spotify API related errors
@@ -88,12 +130,45 @@ pub enum SpotifyError {
     #[error("Failed to get saved tracks: {0}")]
     GetSavedTracks(String),
 
+    #[error("Failed to get saved episodes: {0}")]
+    GetSavedEpisodes(String),
+
     #[error("Failed to get top artists: {0}")]
     GetTopArtists(String),
 
     #[error("Failed to get top tracks: {0}")]
     GetTopTracks(String),
 
+    #[error("Failed to get track: {0}")]
+    GetTrack(String),
+
+    #[error("Failed to get related artists: {0}")]
+    GetRelatedArtists(String),
+
+    #[error("Failed to get artist top tracks: {0}")]
+    GetArtistTopTracks(String),
+
+    #[error("Failed to get followed artists: {0}")]
+    GetFollowedArtists(String),
+
+    #[error("Failed to get new releases: {0}")]
+    GetNewReleases(String),
+
+    #[error("Failed to get featured playlists: {0}")]
+    GetFeaturedPlaylists(String),
+
+    #[error("Failed to get recommendations: {0}")]
+    GetRecommendations(String),
+
+    #[error("Recommendations need between 1 and 5 seeds, got {0}")]
+    InvalidSeedCount(usize),
+
+    #[error("Invalid market: {0}")]
+    InvalidMarket(String),
+
+    #[error("Invalid Spotify id: {0}")]
+    InvalidId(String),
+
     #[error("OAuth timeout after {0} seconds")]
     OAuthTimeout(u64),
 
@@ -103,9 +178,18 @@ pub enum SpotifyError {
     #[error("Invalid authorization code")]
     InvalidAuthCode,
 
+    #[error("OAuth state mismatch: the callback did not come from this login attempt")]
+    OAuthStateMismatch,
+
     #[error("Failed to exchange token: {0}")]
     TokenExchange(String),
 
+    #[error("The session has no refresh token. Please log in again.")]
+    NoRefreshToken,
+
+    #[error("Failed to refresh token: {0}")]
+    TokenRefresh(String),
+
     #[error("Credentials not found in environment")]
     CredentialsNotFound,
 
@@ -131,6 +215,9 @@ pub enum DownloadError {
     #[error("Download failed: {0}")]
     Failed(String),
 
+    #[error("spotdl {0} is too old (minimum {1}). Upgrade with: pip install --upgrade spotdl")]
+    SpotdlOutdated(String, String),
+
     #[error("YouTube download error. Update yt-dlp: pip install --upgrade yt-dlp spotdl")]
     YouTubeError,
 
@@ -139,6 +226,47 @@ pub enum DownloadError {
 
     #[error("Output directory does not exist: {0}")]
     OutputDirNotFound(String),
+
+    #[error("Failed to persist download queue: {0}")]
+    QueuePersistence(String),
+
+    #[error("Failed to write download history: {0}")]
+    HistoryPersistence(String),
+
+    #[error("Invalid output template: {0}")]
+    InvalidTemplate(String),
+
+    #[error("Track has no Spotify id (local files cannot be downloaded): {0}")]
+    MissingTrackId(String),
+}
+
+/// Acoustic fingerprint lookup errors
+#[derive(Debug, Error)]
+pub enum AcoustIdError {
+    #[error("fpcalc is not installed. Install Chromaprint's fpcalc to identify untagged files")]
+    FpcalcNotInstalled,
+
+    #[error("AcoustID API key not configured. Set ACOUSTID_API_KEY")]
+    ApiKeyMissing,
+
+    #[error("Failed to compute fingerprint: {0}")]
+    Fingerprint(String),
+
+    #[error("No AcoustID match found for: {0}")]
+    NoMatch(String),
+}
+
+/// Audio conversion errors
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("ffmpeg is not installed. Install ffmpeg to convert audio files")]
+    FfmpegNotInstalled,
+
+    #[error("Invalid bitrate: {0} kbps (use 32-320)")]
+    InvalidBitrate(u32),
+
+    #[error("Conversion failed: {0}")]
+    Failed(String),
 }
 
 /// Type alias for API responses
@@ -147,18 +275,273 @@ pub enum DownloadError {
 /// Errors are automatically converted to user-friendly strings for the frontend.
 pub type ApiResponse<T> = Result<T, String>;
 
+/// Language used for user-facing error messages
+///
+/// Without a configured language the messages stay as the frontend already
+/// shows them (see [`Locale::Legacy`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// English messages
+    En,
+    /// Spanish messages
+    Es,
+    /// Messages as before localization: file, Spotify, download, AcoustID and
+    /// conversion errors in English, the generic wrappers in Spanish
+    #[default]
+    Legacy,
+}
+
+impl Locale {
+    /// Parses a language tag such as `en`, `en-US` or `es_ES.UTF-8`
+    ///
+    /// Unknown tags (including `C` and `POSIX`) fall back to the default locale.
+    pub fn from_tag(tag: &str) -> Self {
+        match tag.trim().to_lowercase().get(..2) {
+            Some("en") => Locale::En,
+            Some("es") => Locale::Es,
+            _ => Locale::default(),
+        }
+    }
+
+    /// Resolves the preferred locale at startup from `APP_LOCALE`, then `LANG`
+    pub fn from_env() -> Self {
+        std::env::var("APP_LOCALE")
+            .or_else(|_| std::env::var("LANG"))
+            .map(|tag| Self::from_tag(&tag))
+            .unwrap_or_default()
+    }
+}
+
 impl AppError {
     /// Converts the error to a user-friendly string for the frontend
     pub fn to_user_message(&self) -> String {
+        self.to_user_message_localized(Locale::default())
+    }
+
+    /// Converts the error to a user-friendly string in the given locale
+    pub fn to_user_message_localized(&self, locale: Locale) -> String {
+        match locale {
+            Locale::Legacy => match self {
+                AppError::File(_)
+                | AppError::Spotify(_)
+                | AppError::Download(_)
+                | AppError::AcoustId(_)
+                | AppError::Convert(_) => self.to_user_message_localized(Locale::En),
+                _ => self.to_user_message_localized(Locale::Es),
+            },
+            Locale::En => match self {
+                AppError::File(e) => e.to_string(),
+                AppError::Spotify(e) => e.to_string(),
+                AppError::Download(e) => e.to_string(),
+                AppError::AcoustId(e) => e.to_string(),
+                AppError::Convert(e) => e.to_string(),
+                AppError::Validation(msg) => msg.clone(),
+                AppError::Concurrency(msg) => format!("Concurrency error: {}", msg),
+                AppError::Io(e) => format!("Input/output error: {}", e),
+                AppError::Unknown(msg) => format!("Unknown error: {}", msg),
+                AppError::ExternalApi(msg) => format!("External API error: {}", msg),
+                AppError::LastFmApi { code, message } => {
+                    format!("Last.fm error {}: {}", code, message)
+                }
+                AppError::Offline(what) => format!("Not available offline: {}", what),
+            },
+            Locale::Es => match self {
+                AppError::File(e) => e.to_spanish(),
+                AppError::Spotify(e) => e.to_spanish(),
+                AppError::Download(e) => e.to_spanish(),
+                AppError::AcoustId(e) => e.to_spanish(),
+                AppError::Convert(e) => e.to_spanish(),
+                AppError::Validation(msg) => msg.clone(),
+                AppError::Concurrency(msg) => format!("Error de concurrencia: {}", msg),
+                AppError::Io(e) => format!("Error de entrada/salida: {}", e),
+                AppError::Unknown(msg) => format!("Error desconocido: {}", msg),
+                AppError::ExternalApi(msg) => format!("Error de API externa: {}", msg),
+                AppError::LastFmApi { code, message } => {
+                    format!("Error de Last.fm {}: {}", code, message)
+                }
+                AppError::Offline(what) => format!("No disponible sin conexión: {}", what),
+            },
+        }
+    }
+}
+
+impl FileError {
+    /// Spanish message table for file errors
+    fn to_spanish(&self) -> String {
+        match self {
+            FileError::NotFound(p) => format!("La ruta no existe: {}", p),
+            FileError::PermissionDenied(p) => format!("Permiso denegado: {}", p),
+            FileError::PathTraversal(p) => format!("Intento de path traversal detectado: {}", p),
+            FileError::InvalidPath(p) => format!("Ruta inválida: {}", p),
+            FileError::NotDirectory(p) => format!("No es un directorio: {}", p),
+            FileError::NotFile(p) => format!("No es un archivo: {}", p),
+            FileError::UnsupportedFormat(f) => format!("Formato de archivo no soportado: {}", f),
+            FileError::NotImage(p) => {
+                format!("No es una imagen JPEG, PNG, GIF, WebP o BMP: {}", p)
+            }
+            FileError::Decode(e) => format!("Error al decodificar el audio: {}", e),
+            FileError::Export(e) => format!("Error al exportar: {}", e),
+            FileError::MetadataRead(e) => format!("Error al leer metadata: {}", e),
+            FileError::NoAlbumArt(p) => format!("El archivo no tiene carátula incrustada: {}", p),
+            FileError::Canonicalize(e) => format!("Error al normalizar la ruta: {}", e),
+            FileError::ScanLimitExceeded(max) => {
+                format!("Límite de escaneo excedido: máximo {} archivos", max)
+            }
+            FileError::ScanDepthExceeded(max) => {
+                format!("Profundidad de escaneo excedida: máximo {} niveles", max)
+            }
+            FileError::ScanCancelled => "Escaneo cancelado".to_string(),
+            FileError::RevealFailed(msg) => {
+                format!("No se pudo abrir el explorador de archivos: {}", msg)
+            }
+            FileError::OutsideAllowedRoots(p) => {
+                format!("La ruta está fuera de las carpetas permitidas: {}", p)
+            }
+            FileError::Trash(msg) => format!("No se pudo mover a la papelera: {}", msg),
+            FileError::TagWrite(msg) => format!("No se pudieron escribir las etiquetas: {}", msg),
+        }
+    }
+}
+
+impl SpotifyError {
+    /// Spanish message table for Spotify errors
+    fn to_spanish(&self) -> String {
+        match self {
+            SpotifyError::NotAuthenticated => {
+                "No hay sesión activa. Autentícate primero.".to_string()
+            }
+            SpotifyError::AuthenticationFailed(e) => format!("Error de autenticación: {}", e),
+            SpotifyError::GetProfile(e) => format!("Error al obtener el perfil: {}", e),
+            SpotifyError::GetPlaylists(e) => format!("Error al obtener playlists: {}", e),
+            SpotifyError::GetSavedTracks(e) => {
+                format!("Error al obtener canciones guardadas: {}", e)
+            }
+            SpotifyError::GetSavedEpisodes(e) => {
+                format!("Error al obtener episodios guardados: {}", e)
+            }
+            SpotifyError::GetTopArtists(e) => format!("Error al obtener top artistas: {}", e),
+            SpotifyError::GetTopTracks(e) => format!("Error al obtener top canciones: {}", e),
+            SpotifyError::GetTrack(e) => format!("Error al obtener la canción: {}", e),
+            SpotifyError::GetRelatedArtists(e) => {
+                format!("Error al obtener artistas relacionados: {}", e)
+            }
+            SpotifyError::GetArtistTopTracks(e) => {
+                format!("Error al obtener top canciones del artista: {}", e)
+            }
+            SpotifyError::GetFollowedArtists(e) => {
+                format!("Error al obtener artistas seguidos: {}", e)
+            }
+            SpotifyError::GetNewReleases(e) => format!("Error al obtener lanzamientos: {}", e),
+            SpotifyError::GetFeaturedPlaylists(e) => {
+                format!("Error al obtener playlists destacadas: {}", e)
+            }
+            SpotifyError::GetRecommendations(e) => {
+                format!("Error al obtener recomendaciones: {}", e)
+            }
+            SpotifyError::InvalidSeedCount(n) => format!(
+                "Las recomendaciones necesitan entre 1 y 5 semillas, se recibieron {}",
+                n
+            ),
+            SpotifyError::InvalidMarket(m) => format!("Mercado inválido: {}", m),
+            SpotifyError::InvalidId(id) => format!("ID de Spotify inválido: {}", id),
+            SpotifyError::OAuthTimeout(secs) => {
+                format!("Tiempo de espera de OAuth agotado tras {} segundos", secs)
+            }
+            SpotifyError::OAuthServer(e) => format!("Error del servidor OAuth: {}", e),
+            SpotifyError::InvalidAuthCode => "Código de autorización inválido".to_string(),
+            SpotifyError::OAuthStateMismatch => {
+                "El parámetro state de OAuth no coincide: la respuesta no proviene de este inicio de sesión"
+                    .to_string()
+            }
+            SpotifyError::TokenExchange(e) => format!("Error al obtener el token: {}", e),
+            SpotifyError::NoRefreshToken => {
+                "La sesión no se puede renovar. Vuelve a iniciar sesión.".to_string()
+            }
+            SpotifyError::TokenRefresh(e) => format!("Error al renovar el token: {}", e),
+            SpotifyError::CredentialsNotFound => {
+                "Credenciales no encontradas en el entorno".to_string()
+            }
+            SpotifyError::ClientLock(e) => {
+                format!(
+                    "Error de concurrencia accediendo al cliente de Spotify: {}",
+                    e
+                )
+            }
+        }
+    }
+}
+
+impl DownloadError {
+    /// Spanish message table for download errors
+    fn to_spanish(&self) -> String {
         match self {
-            AppError::File(e) => e.to_string(),
-            AppError::Spotify(e) => e.to_string(),
-            AppError::Download(e) => e.to_string(),
-            AppError::Validation(msg) => msg.clone(),
-            AppError::Concurrency(msg) => format!("Error de concurrencia: {}", msg),
-            AppError::Io(e) => format!("Error de entrada/salida: {}", e),
-            AppError::Unknown(msg) => format!("Error desconocido: {}", msg),
-            AppError::ExternalApi(msg) => format!("Error de API externa: {}", msg),
+            DownloadError::SpotdlNotInstalled => {
+                "spotdl no está instalado. Instálalo con: pip install spotdl yt-dlp".to_string()
+            }
+            DownloadError::SpotdlOutdated(found, minimum) => format!(
+                "spotdl {} es demasiado antiguo (mínimo {}). Actualízalo con: pip install --upgrade spotdl",
+                found, minimum
+            ),
+            DownloadError::InvalidUrl(url) => format!("URL de Spotify inválida: {}", url),
+            DownloadError::InvalidFormat(f) => format!("Formato de salida inválido: {}", f),
+            DownloadError::Timeout(secs) => {
+                format!("Tiempo de descarga agotado tras {} segundos", secs)
+            }
+            DownloadError::Failed(e) => format!("Descarga fallida: {}", e),
+            DownloadError::YouTubeError => {
+                "Error de descarga de YouTube. Actualiza yt-dlp: pip install --upgrade yt-dlp spotdl"
+                    .to_string()
+            }
+            DownloadError::TooManySongs(max) => {
+                format!("Demasiadas canciones solicitadas: máximo {}", max)
+            }
+            DownloadError::OutputDirNotFound(dir) => {
+                format!("El directorio de salida no existe: {}", dir)
+            }
+            DownloadError::QueuePersistence(e) => {
+                format!("Error al guardar la cola de descargas: {}", e)
+            }
+            DownloadError::HistoryPersistence(e) => {
+                format!("Error al guardar el historial de descargas: {}", e)
+            }
+            DownloadError::InvalidTemplate(t) => format!("Plantilla de salida inválida: {}", t),
+            DownloadError::MissingTrackId(name) => format!(
+                "La canción no tiene ID de Spotify (los archivos locales no se pueden descargar): {}",
+                name
+            ),
+        }
+    }
+}
+
+impl AcoustIdError {
+    /// Spanish message table for AcoustID errors
+    fn to_spanish(&self) -> String {
+        match self {
+            AcoustIdError::FpcalcNotInstalled => {
+                "fpcalc no está instalado. Instala fpcalc de Chromaprint para identificar archivos sin etiquetas"
+                    .to_string()
+            }
+            AcoustIdError::ApiKeyMissing => {
+                "API key de AcoustID no configurada. Define ACOUSTID_API_KEY".to_string()
+            }
+            AcoustIdError::Fingerprint(e) => format!("Error al calcular la huella acústica: {}", e),
+            AcoustIdError::NoMatch(p) => format!("No se encontró coincidencia en AcoustID para: {}", p),
+        }
+    }
+}
+
+impl ConvertError {
+    /// Spanish message table for conversion errors
+    fn to_spanish(&self) -> String {
+        match self {
+            ConvertError::FfmpegNotInstalled => {
+                "ffmpeg no está instalado. Instala ffmpeg para convertir archivos de audio"
+                    .to_string()
+            }
+            ConvertError::InvalidBitrate(kbps) => {
+                format!("Bitrate inválido: {} kbps (usa 32-320)", kbps)
+            }
+            ConvertError::Failed(e) => format!("Conversión fallida: {}", e),
         }
     }
 }
@@ -168,3 +551,120 @@ impl From<AppError> for String {
         err.to_user_message()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One representative error per category with its English and Spanish message
+    fn samples() -> Vec<(AppError, &'static str, &'static str)> {
+        vec![
+            (
+                FileError::NotFound("/music".into()).into(),
+                "Path does not exist: /music",
+                "La ruta no existe: /music",
+            ),
+            (
+                SpotifyError::NotAuthenticated.into(),
+                "Not authenticated. Please authenticate first.",
+                "No hay sesión activa. Autentícate primero.",
+            ),
+            (
+                DownloadError::Timeout(120).into(),
+                "Download timeout after 120 seconds",
+                "Tiempo de descarga agotado tras 120 segundos",
+            ),
+            (
+                AcoustIdError::ApiKeyMissing.into(),
+                "AcoustID API key not configured. Set ACOUSTID_API_KEY",
+                "API key de AcoustID no configurada. Define ACOUSTID_API_KEY",
+            ),
+            (
+                ConvertError::InvalidBitrate(500).into(),
+                "Invalid bitrate: 500 kbps (use 32-320)",
+                "Bitrate inválido: 500 kbps (usa 32-320)",
+            ),
+            (
+                AppError::Validation("Playlist has no tracks".into()),
+                "Playlist has no tracks",
+                "Playlist has no tracks",
+            ),
+            (
+                AppError::Concurrency("lock poisoned".into()),
+                "Concurrency error: lock poisoned",
+                "Error de concurrencia: lock poisoned",
+            ),
+            (
+                AppError::Unknown("boom".into()),
+                "Unknown error: boom",
+                "Error desconocido: boom",
+            ),
+            (
+                AppError::ExternalApi("HTTP 503".into()),
+                "External API error: HTTP 503",
+                "Error de API externa: HTTP 503",
+            ),
+            (
+                AppError::LastFmApi {
+                    code: 6,
+                    message: "Artist not found".into(),
+                },
+                "Last.fm error 6: Artist not found",
+                "Error de Last.fm 6: Artist not found",
+            ),
+            (
+                AppError::Offline("track info".into()),
+                "Not available offline: track info",
+                "No disponible sin conexión: track info",
+            ),
+        ]
+    }
+
+    #[test]
+    fn english_and_spanish_tables() {
+        for (error, english, spanish) in samples() {
+            assert_eq!(error.to_user_message_localized(Locale::En), english);
+            assert_eq!(error.to_user_message_localized(Locale::Es), spanish);
+        }
+    }
+
+    #[test]
+    fn io_errors_are_wrapped_in_each_locale() {
+        let error: AppError = std::io::Error::other("disk full").into();
+        assert_eq!(
+            error.to_user_message_localized(Locale::En),
+            "Input/output error: disk full"
+        );
+        assert_eq!(
+            error.to_user_message_localized(Locale::Es),
+            "Error de entrada/salida: disk full"
+        );
+    }
+
+    #[test]
+    fn default_locale_keeps_the_pre_localization_messages() {
+        assert_eq!(Locale::default(), Locale::Legacy);
+        for (error, english, spanish) in samples() {
+            let expected = match error {
+                AppError::File(_)
+                | AppError::Spotify(_)
+                | AppError::Download(_)
+                | AppError::AcoustId(_)
+                | AppError::Convert(_) => english,
+                _ => spanish,
+            };
+            assert_eq!(error.to_user_message(), expected);
+        }
+    }
+
+    #[test]
+    fn parses_language_tags() {
+        assert_eq!(Locale::from_tag("en"), Locale::En);
+        assert_eq!(Locale::from_tag("en-US"), Locale::En);
+        assert_eq!(Locale::from_tag("es_ES.UTF-8"), Locale::Es);
+        assert_eq!(Locale::from_tag("ES"), Locale::Es);
+        assert_eq!(Locale::from_tag("C"), Locale::Legacy);
+        assert_eq!(Locale::from_tag("fr_FR"), Locale::Legacy);
+        assert_eq!(Locale::from_tag(""), Locale::Legacy);
+    }
+}
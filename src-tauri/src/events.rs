@@ -0,0 +1,77 @@
+//! Tauri event names
+//!
+//! Every event the backend emits is named here so services and the frontend share
+//! one list. Payload types live next to the code that emits them.
+
+/// Library scan of one folder started: `{ scanId, path }`
+///
+/// `scanId` identifies the scan in every scan event and in `cancel_scan`.
+pub const LIBRARY_SCAN_START: &str = "library-scan-start";
+/// Library scan progress, every few parsed files: `{ scanId, current, total, path, root }`
+///
+/// `root` is the scanned folder the counts refer to.
+pub const LIBRARY_SCAN_PROGRESS: &str = "library-scan-progress";
+/// Library scan finished, after every folder: `{ scanId, total }`
+pub const LIBRARY_SCAN_COMPLETE: &str = "library-scan-complete";
+/// Audio file left out of a scan: `{ path, reason, size?, maxBytes? }`
+///
+/// `reason` is `tooLarge` (over the scan's size limit, with `size` and `maxBytes`).
+pub const LIBRARY_SCAN_SKIPPED: &str = "library-scan-skipped";
+/// Library scan stopped by `cancel_scan`: `{ scanId, processed }`, the files parsed
+/// before stopping
+pub const LIBRARY_SCAN_CANCELLED: &str = "library-scan-cancelled";
+
+/// `normalize_tags` finished a file, successfully or not (`TagNormalizeProgress`)
+pub const TAG_NORMALIZE_PROGRESS: &str = "tag-normalize-progress";
+
+/// Browser opened for Spotify login, waiting for the callback (`AuthWaiting`)
+pub const SPOTIFY_AUTH_WAITING: &str = "spotify-auth-waiting";
+
+/// Liked-songs stream started: `{ total }`
+pub const SPOTIFY_TRACKS_START: &str = "spotify-tracks-start";
+/// One page of liked songs: `{ tracks, progress, loaded, total }`
+pub const SPOTIFY_TRACKS_BATCH: &str = "spotify-tracks-batch";
+/// Liked-songs stream gave up after retries: `{ message }`
+pub const SPOTIFY_TRACKS_ERROR: &str = "spotify-tracks-error";
+/// Liked-songs stream finished: `{ total }`
+pub const SPOTIFY_TRACKS_COMPLETE: &str = "spotify-tracks-complete";
+
+/// Liked-songs export progress (`ExportProgress`)
+pub const SPOTIFY_EXPORT_PROGRESS: &str = "spotify-export-progress";
+
+/// A song is about to be downloaded (`DownloadStarted`)
+pub const DOWNLOAD_STARTED: &str = "download-started";
+/// A song finished downloading, successfully or not (`DownloadProgress`)
+pub const DOWNLOAD_PROGRESS: &str = "download-progress";
+/// A batch download finished (`DownloadFinished`)
+pub const DOWNLOAD_FINISHED: &str = "download-finished";
+/// A batch download gave up after too many failures in a row (`DownloadAborted`)
+pub const DOWNLOAD_ABORTED: &str = "download-aborted";
+
+/// ffmpeg conversion progress (`ConvertProgress`)
+pub const CONVERT_PROGRESS: &str = "convert-progress";
+
+/// One album art URL handled by `cache_spotify_album_art` (`ArtCacheProgress`)
+pub const ART_CACHE_PROGRESS: &str = "art-cache-progress";
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    #[test]
+    fn event_names_are_unique() {
+        // Read from the source so constants added later are covered too
+        let names: Vec<&str> = include_str!("events.rs")
+            .lines()
+            .filter(|line| line.starts_with("pub const "))
+            .filter_map(|line| line.split('"').nth(1))
+            .collect();
+
+        assert!(names.contains(&super::LIBRARY_SCAN_START));
+        assert!(names.contains(&super::ART_CACHE_PROGRESS));
+        let mut seen = HashSet::new();
+        for name in &names {
+            assert!(seen.insert(name), "duplicate event name {}", name);
+        }
+    }
+}
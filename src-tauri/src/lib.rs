@@ -6,38 +6,116 @@
 mod commands;
 mod domain;
 mod errors;
+mod events;
 mod services;
+#[cfg(test)]
+mod test_support;
 mod utils;
 
 // Re-export commonly used types
 pub use errors::ApiResponse;
+pub use services::AcoustIdService;
 pub use services::LastFmService;
 pub use services::SpotifyState;
 
+use errors::Locale;
+use services::{AllowedRoots, HttpState, LogState, ScanState, SettingsState, ShutdownState};
+use tauri::{Manager, RunEvent};
+
 use commands::{
+    // AcoustID commands
+    acoustid_identify,
+    add_allowed_root,
+    cache_spotify_album_art,
+    cancel_scan,
     check_spotdl_installed,
+    clear_download_history,
+    clear_recent_folders,
+    // Conversion commands
+    convert_audio_file,
+    create_playlist_from_local_files,
+    delete_track,
     download_single_spotify_track,
+    download_spotify_album,
+    download_spotify_track_object,
     // Download commands
     download_spotify_tracks_segmented,
     enrich_tracks_batch,
+    enrich_tracks_by_album,
+    // HTTP commands
+    fetch_image_as_data_url,
+    filter_by_genre,
+    filter_library,
+    get_album_art_bytes,
+    get_allowed_roots,
     get_audio_metadata,
+    get_audio_metadata_batch,
+    get_audio_waveform,
     get_default_music_folder_cmd,
+    get_diagnostics,
+    get_download_history,
+    get_genre_counts,
+    get_log_file_path,
+    get_lyrics,
+    get_recent_folders,
+    get_settings,
+    get_supported_formats,
+    get_year_histogram,
+    import_playlist,
+    lastfm_cache_stats,
+    lastfm_clear_cache,
     lastfm_get_album_info,
     lastfm_get_artist_info,
+    lastfm_get_top_tracks_for_artist,
     // Last.fm commands
     lastfm_get_track_info,
+    lastfm_set_autocorrect,
+    lastfm_set_offline,
+    list_audio_outputs,
+    match_spotify_to_library,
+    normalize_tags,
+    preview_spotify_track,
+    read_audio_file,
+    read_image_as_data_url,
+    remove_allowed_root,
+    resume_downloads,
+    reveal_in_file_manager,
+    save_album_art,
     // File commands
     scan_music_folder,
+    scan_music_folders,
+    set_log_level,
+    sort_library,
     // Spotify commands
+    spotify_auth_status,
     spotify_authenticate,
+    spotify_authenticate_pkce,
+    spotify_build_download_urls,
+    spotify_export_liked_songs,
+    spotify_get_all_liked_songs,
+    spotify_get_all_playlists,
+    spotify_get_artist_top_tracks,
+    spotify_get_featured_playlists,
+    spotify_get_followed_artists,
+    spotify_get_new_releases,
     spotify_get_playlists,
     spotify_get_profile,
+    spotify_get_recommendations,
+    spotify_get_related_artists,
+    spotify_get_saved_episodes,
     spotify_get_saved_tracks,
+    spotify_get_saved_tracks_count,
     spotify_get_top_artists,
     spotify_get_top_tracks,
+    spotify_get_track,
+    spotify_get_tracks,
     spotify_is_authenticated,
     spotify_logout,
+    spotify_refresh_token,
     spotify_stream_all_liked_songs,
+    spotify_token_expires_in,
+    update_settings,
+    verify_downloads,
 };
 
 /// Initializes and runs the Tauri application with all plugins and command handlers
@@ -45,9 +123,12 @@ use commands::{
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     dotenv::dotenv().ok();
+    let logging = LogState::init();
     tracing::info!("🚀 Starting Music Player application");
 
     let lastfm_api_key = std::env::var("VITE_LASTFM_API_KEY").unwrap_or_default();
+    let acoustid_api_key = std::env::var("ACOUSTID_API_KEY").ok();
+    let http = HttpState::default();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -55,33 +136,118 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_http::init())
+        .manage(Locale::from_env())
         .manage(SpotifyState::default())
-        .manage(LastFmService::new(lastfm_api_key))
+        .manage(LastFmService::new(lastfm_api_key, http.client()))
+        .manage(AcoustIdService::new(acoustid_api_key, http.client()))
+        .manage(http)
+        .manage(ShutdownState::default())
+        .manage(ScanState::default())
+        .manage(AllowedRoots::load())
+        .manage(SettingsState::load())
+        .manage(logging)
         .invoke_handler(tauri::generate_handler![
             // File system commands
             scan_music_folder,
+            scan_music_folders,
+            get_recent_folders,
+            clear_recent_folders,
+            cancel_scan,
             get_audio_metadata,
+            get_audio_metadata_batch,
+            get_audio_waveform,
+            get_lyrics,
+            get_album_art_bytes,
+            save_album_art,
+            read_audio_file,
+            read_image_as_data_url,
+            reveal_in_file_manager,
+            delete_track,
+            normalize_tags,
             get_default_music_folder_cmd,
+            get_supported_formats,
+            filter_library,
+            filter_by_genre,
+            get_genre_counts,
+            get_year_histogram,
+            sort_library,
+            match_spotify_to_library,
+            import_playlist,
+            create_playlist_from_local_files,
+            get_allowed_roots,
+            add_allowed_root,
+            remove_allowed_root,
+            // Remote art
+            fetch_image_as_data_url,
+            cache_spotify_album_art,
             // Spotify commands (read-only data, no playback)
             spotify_authenticate,
+            spotify_authenticate_pkce,
             spotify_get_profile,
             spotify_get_playlists,
+            spotify_get_all_playlists,
             spotify_get_saved_tracks,
+            spotify_get_saved_tracks_count,
+            spotify_get_saved_episodes,
             spotify_get_top_artists,
             spotify_get_top_tracks,
+            spotify_get_related_artists,
+            spotify_get_artist_top_tracks,
+            spotify_get_followed_artists,
+            spotify_get_new_releases,
+            spotify_get_featured_playlists,
+            spotify_get_recommendations,
+            spotify_get_track,
+            spotify_get_tracks,
+            spotify_get_all_liked_songs,
             spotify_stream_all_liked_songs,
+            spotify_export_liked_songs,
+            spotify_build_download_urls,
+            preview_spotify_track,
             spotify_logout,
             spotify_is_authenticated,
+            spotify_auth_status,
+            spotify_token_expires_in,
+            spotify_refresh_token,
             // Download commands with spotdl
             download_spotify_tracks_segmented,
             download_single_spotify_track,
+            download_spotify_track_object,
+            download_spotify_album,
+            resume_downloads,
             check_spotdl_installed,
+            get_download_history,
+            clear_download_history,
+            verify_downloads,
             // Last.fm commands
             lastfm_get_track_info,
             lastfm_get_artist_info,
             lastfm_get_album_info,
+            lastfm_get_top_tracks_for_artist,
             enrich_tracks_batch,
+            enrich_tracks_by_album,
+            lastfm_set_offline,
+            lastfm_set_autocorrect,
+            lastfm_cache_stats,
+            lastfm_clear_cache,
+            // AcoustID commands
+            acoustid_identify,
+            // Conversion commands
+            convert_audio_file,
+            // Settings
+            get_settings,
+            update_settings,
+            // Diagnostics
+            set_log_level,
+            get_log_file_path,
+            get_diagnostics,
+            list_audio_outputs,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::ExitRequested { .. } = event {
+                app.state::<ShutdownState>().shutdown(app);
+            }
+        });
 }
@@ -0,0 +1,190 @@
+//! AcoustID service for identifying untagged files by acoustic fingerprint
+//!
+//! Computes a Chromaprint fingerprint with the external `fpcalc` tool and looks it
+//! up on the AcoustID API to recover MusicBrainz title/artist/album information.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::instrument;
+
+use crate::domain::acoustid::{raw, FpcalcOutput};
+use crate::domain::music::MusicFile;
+use crate::errors::{AcoustIdError, AppError, FileError};
+use crate::services::FileService;
+use crate::utils::validate_within_roots;
+
+const API_LOOKUP_URL: &str = "https://api.acoustid.org/v2/lookup";
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const FPCALC_TIMEOUT_SECS: u64 = 60;
+/// Minimum AcoustID score for a match to be trusted
+const MIN_MATCH_SCORE: f64 = 0.5;
+
+/// Service for AcoustID fingerprint lookups
+///
+/// Disabled unless an API key is configured through `ACOUSTID_API_KEY`.
+pub struct AcoustIdService {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl AcoustIdService {
+    /// Creates the service on top of a shared HTTP client (see `HttpState`)
+    pub fn new(api_key: Option<String>, client: reqwest::Client) -> Self {
+        Self {
+            client,
+            api_key: api_key.filter(|k| !k.trim().is_empty()),
+        }
+    }
+
+    /// Identifies a file by its acoustic fingerprint
+    ///
+    /// Returns the file's metadata with title, artist and album replaced by the best
+    /// AcoustID/MusicBrainz match. The file must be inside one of `roots`.
+    #[instrument(skip_all, fields(file_path = %file_path))]
+    pub async fn lookup_by_fingerprint(
+        &self,
+        file_path: &str,
+        roots: &[PathBuf],
+    ) -> Result<MusicFile, AppError> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .ok_or(AcoustIdError::ApiKeyMissing)?;
+        let validated_path = validate_within_roots(file_path, roots)?;
+        if !validated_path.is_file() {
+            return Err(FileError::NotFile(file_path.to_string()).into());
+        }
+
+        let fingerprint = Self::compute_fingerprint(&validated_path).await?;
+        let recording = self.lookup(api_key, &fingerprint).await?.ok_or_else(|| {
+            tracing::info!("🔎 No AcoustID match for {}", file_path);
+            AcoustIdError::NoMatch(file_path.to_string())
+        })?;
+
+        let mut music_file = FileService::get_audio_metadata(file_path)?;
+        if let Some(title) = recording.title {
+            music_file.title = Some(title);
+        }
+        if !recording.artists.is_empty() {
+            let names: Vec<&str> = recording.artists.iter().map(|a| a.name.as_str()).collect();
+            music_file.artist = Some(names.join(", "));
+        }
+        if let Some(group) = recording.releasegroups.into_iter().next() {
+            music_file.album = Some(group.title);
+        }
+        if music_file.duration.is_none() {
+            music_file.duration = Some(fingerprint.duration.round() as u32);
+        }
+
+        tracing::info!("🔎 Identified {} via AcoustID", file_path);
+        Ok(music_file)
+    }
+
+    /// Runs `fpcalc -json` on the file
+    async fn compute_fingerprint(path: &std::path::Path) -> Result<FpcalcOutput, AppError> {
+        let mut cmd = Command::new("fpcalc");
+        cmd.arg("-json").arg(path);
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        let output = timeout(Duration::from_secs(FPCALC_TIMEOUT_SECS), cmd.output())
+            .await
+            .map_err(|_| {
+                AcoustIdError::Fingerprint(format!(
+                    "fpcalc timed out after {}s",
+                    FPCALC_TIMEOUT_SECS
+                ))
+            })?
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    tracing::error!("❌ fpcalc is not installed");
+                    AcoustIdError::FpcalcNotInstalled
+                }
+                _ => AcoustIdError::Fingerprint(e.to_string()),
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AcoustIdError::Fingerprint(stderr.trim().to_string()).into());
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| AcoustIdError::Fingerprint(format!("Invalid fpcalc output: {}", e)).into())
+    }
+
+    /// Queries AcoustID and returns the best scored recording, if any
+    async fn lookup(
+        &self,
+        api_key: &str,
+        fingerprint: &FpcalcOutput,
+    ) -> Result<Option<raw::Recording>, AppError> {
+        let duration = (fingerprint.duration.round() as u64).to_string();
+        let params = [
+            ("client", api_key),
+            ("meta", "recordings releasegroups"),
+            ("duration", duration.as_str()),
+            ("fingerprint", fingerprint.fingerprint.as_str()),
+        ];
+
+        // POST keeps the long fingerprint out of the URL
+        let response = self
+            .client
+            .post(API_LOOKUP_URL)
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(e.to_string()))?;
+
+        let body: raw::LookupResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Parse Error: {}", e)))?;
+
+        if body.status != "ok" {
+            let message = body.error.map(|e| e.message).unwrap_or(body.status);
+            return Err(AppError::ExternalApi(format!(
+                "AcoustID Error: {}",
+                message
+            )));
+        }
+
+        Ok(body
+            .results
+            .into_iter()
+            .filter(|r| r.score >= MIN_MATCH_SCORE)
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .and_then(|r| r.recordings.into_iter().next()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::canonical_path;
+    use std::fs;
+
+    #[tokio::test]
+    async fn files_outside_roots_are_rejected_before_fingerprinting() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&library).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.mp3"), b"").unwrap();
+        let roots = vec![canonical_path(&library).unwrap()];
+        let service = AcoustIdService::new(Some("key".to_string()), reqwest::Client::new());
+
+        let result = service
+            .lookup_by_fingerprint(&outside.join("secret.mp3").to_string_lossy(), &roots)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::File(FileError::OutsideAllowedRoots(_)))
+        ));
+    }
+}
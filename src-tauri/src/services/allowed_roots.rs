@@ -0,0 +1,314 @@
+//! Folders file commands are confined to
+//!
+//! Paths from the frontend are only accepted inside the music folder, folders
+//! listed in `MUSICPLAYER_ALLOWED_ROOTS`, or folders the user added at runtime
+//! (persisted in `allowed_roots.json`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::errors::{AppError, FileError};
+use crate::utils::{
+    canonical_path, get_app_data_dir, get_default_music_folder, validate_directory,
+    validate_within_roots,
+};
+
+/// Environment variable listing extra allowed folders
+///
+/// Uses the platform's path list separator (`:` on Unix, `;` on Windows).
+pub const ALLOWED_ROOTS_ENV: &str = "MUSICPLAYER_ALLOWED_ROOTS";
+
+/// Allowed root folders, managed by Tauri
+pub struct AllowedRoots {
+    /// Music folder and `MUSICPLAYER_ALLOWED_ROOTS`; can't be removed at runtime
+    builtin: Vec<PathBuf>,
+    /// Folders added with `add_allowed_root`
+    user: Mutex<Vec<PathBuf>>,
+}
+
+impl AllowedRoots {
+    /// Loads the built-in roots and the folders the user added previously
+    ///
+    /// Roots that no longer exist are left out, as are built-in roots that are
+    /// too broad (the music folder falls back to the home folder when missing).
+    pub fn load() -> Self {
+        let configured = std::env::var_os(ALLOWED_ROOTS_ENV)
+            .map(|value| std::env::split_paths(&value).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let builtin: Vec<PathBuf> = get_default_music_folder()
+            .ok()
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(configured)
+            .filter_map(|root| canonical_path(&root).ok())
+            .filter(|root| {
+                let too_broad = is_too_broad(root);
+                if too_broad {
+                    tracing::warn!("⚠️ Skipping allowed root, too broad: {}", root.display());
+                }
+                !too_broad
+            })
+            .collect();
+
+        let user = Self::read_user_roots()
+            .into_iter()
+            .filter_map(|root| canonical_path(&root).ok())
+            .collect();
+
+        Self {
+            builtin,
+            user: Mutex::new(user),
+        }
+    }
+
+    /// Location of the file persisting user-added roots
+    fn file_path() -> PathBuf {
+        get_app_data_dir().join("allowed_roots.json")
+    }
+
+    /// Reads the persisted user roots, logging instead of failing on a bad file
+    fn read_user_roots() -> Vec<PathBuf> {
+        let path = Self::file_path();
+        if !path.exists() {
+            return Vec::new();
+        }
+
+        fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Could not read allowed roots: {}", e);
+                Vec::new()
+            })
+    }
+
+    /// Persists the user roots
+    fn save_user_roots(roots: &[PathBuf]) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(roots)
+            .map_err(|e| AppError::Unknown(format!("Failed to serialize roots: {}", e)))?;
+
+        let path = Self::file_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    fn lock_user(&self) -> Result<std::sync::MutexGuard<'_, Vec<PathBuf>>, AppError> {
+        self.user
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("Allowed roots lock poisoned: {}", e)))
+    }
+
+    /// All allowed roots, built-in first
+    pub fn roots(&self) -> Result<Vec<PathBuf>, AppError> {
+        let user = self.lock_user()?;
+        Ok(self.builtin.iter().chain(user.iter()).cloned().collect())
+    }
+
+    /// All allowed roots as display strings
+    pub fn list(&self) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .roots()?
+            .iter()
+            .map(|root| root.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Validates an existing path and ensures it is inside an allowed root
+    pub fn check(&self, path: &str) -> Result<PathBuf, AppError> {
+        validate_within_roots(path, &self.roots()?)
+    }
+
+    /// Ensures a file that is about to be written lands inside an allowed root
+    ///
+    /// The file itself may not exist yet, so its parent directory is checked.
+    pub fn check_output(&self, path: &str) -> Result<(), AppError> {
+        let parent = Path::new(path)
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .ok_or_else(|| FileError::InvalidPath(path.to_string()))?;
+        self.check(&parent.to_string_lossy()).map(|_| ())
+    }
+
+    /// Ensures a folder downloads are written to is inside an allowed root
+    ///
+    /// spotdl creates the folder when missing, so its closest existing ancestor
+    /// is checked instead.
+    pub fn check_output_dir(&self, dir: &str) -> Result<(), AppError> {
+        if dir.contains("..") {
+            return Err(FileError::PathTraversal(dir.to_string()).into());
+        }
+
+        let existing = Path::new(dir)
+            .ancestors()
+            .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+            .ok_or_else(|| FileError::InvalidPath(dir.to_string()))?;
+        self.check(&existing.to_string_lossy()).map(|_| ())
+    }
+
+    /// Adds a folder, returning the updated list of roots
+    ///
+    /// Filesystem roots and the home folder itself are refused, since allowing
+    /// them would lift the confinement altogether.
+    pub fn add(&self, path: &str) -> Result<Vec<String>, AppError> {
+        let root = validate_directory(path)?;
+        if is_too_broad(&root) {
+            return Err(AppError::Validation(format!(
+                "Folder is too broad to allow: {}",
+                path
+            )));
+        }
+
+        {
+            let mut user = self.lock_user()?;
+            if !self.builtin.contains(&root) && !user.contains(&root) {
+                user.push(root.clone());
+                Self::save_user_roots(&user)?;
+                tracing::info!("📁 Allowed root added: {}", root.display());
+            }
+        } // Release lock before listing
+
+        self.list()
+    }
+
+    /// Removes a user-added folder, returning the updated list of roots
+    ///
+    /// Built-in roots can't be removed; removing an unknown folder is a no-op.
+    pub fn remove(&self, path: &str) -> Result<Vec<String>, AppError> {
+        let root = canonical_path(Path::new(path)).unwrap_or_else(|_| PathBuf::from(path));
+        if self.builtin.contains(&root) {
+            return Err(AppError::Validation(format!(
+                "Built-in folder can't be removed: {}",
+                path
+            )));
+        }
+
+        {
+            let mut user = self.lock_user()?;
+            let before = user.len();
+            user.retain(|existing| *existing != root);
+            if user.len() != before {
+                Self::save_user_roots(&user)?;
+                tracing::info!("📁 Allowed root removed: {}", root.display());
+            }
+        } // Release lock before listing
+
+        self.list()
+    }
+}
+
+/// Whether allowing `root` would expose the whole disk or home folder
+fn is_too_broad(root: &Path) -> bool {
+    let is_home = dirs::home_dir()
+        .and_then(|home| canonical_path(&home).ok())
+        .is_some_and(|home| home == root);
+    root.parent().is_none() || is_home
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roots_for(root: &Path) -> AllowedRoots {
+        AllowedRoots {
+            builtin: vec![canonical_path(root).unwrap()],
+            user: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn accepts_paths_inside_a_root() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("album")).unwrap();
+        fs::write(dir.path().join("album/song.mp3"), b"").unwrap();
+        let roots = roots_for(dir.path());
+
+        let song = dir.path().join("album/song.mp3");
+        assert!(roots.check(&song.to_string_lossy()).is_ok());
+        assert!(roots
+            .check_output(&dir.path().join("album/new.m3u8").to_string_lossy())
+            .is_ok());
+        assert!(roots
+            .check_output_dir(&dir.path().join("downloads/new").to_string_lossy())
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_escape_attempts() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("secret.txt"), b"").unwrap();
+        let roots = roots_for(root.path());
+
+        let secret = outside.path().join("secret.txt");
+        assert!(roots.check(&secret.to_string_lossy()).is_err());
+        assert!(roots
+            .check_output(&outside.path().join("out.json").to_string_lossy())
+            .is_err());
+        assert!(roots
+            .check_output_dir(&outside.path().join("new").to_string_lossy())
+            .is_err());
+
+        let traversal = format!("{}/../secret.txt", root.path().display());
+        assert!(roots.check(&traversal).is_err());
+        assert!(roots.check_output_dir(&traversal).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leading_out_of_a_root() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("song.mp3"), b"").unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.path().join("link")).unwrap();
+        let roots = roots_for(root.path());
+
+        let linked = root.path().join("link/song.mp3");
+        assert!(roots.check(&linked.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn refuses_filesystem_and_home_roots() {
+        let filesystem_root = std::env::temp_dir()
+            .ancestors()
+            .last()
+            .map(Path::to_path_buf)
+            .unwrap();
+        assert!(is_too_broad(&filesystem_root));
+        let _env = crate::utils::path::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(home) = dirs::home_dir().and_then(|home| canonical_path(&home).ok()) {
+            assert!(is_too_broad(&home));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_too_broad(&canonical_path(dir.path()).unwrap()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn broad_builtin_roots_are_dropped() {
+        let home = crate::test_support::FakeHome::new();
+        let configured = home.mkdir("configured");
+        let home_path = canonical_path(home.path()).unwrap();
+        let joined = std::env::join_paths([home.path(), Path::new("/"), &configured]).unwrap();
+        std::env::set_var(ALLOWED_ROOTS_ENV, joined);
+
+        // No Music folder, so the music folder falls back to the home folder
+        assert_eq!(
+            get_default_music_folder().unwrap(),
+            home.path().to_str().unwrap()
+        );
+        let roots = AllowedRoots::load();
+
+        assert_eq!(
+            roots.roots().unwrap(),
+            vec![canonical_path(&configured).unwrap()]
+        );
+        assert!(roots.check(&home_path.to_string_lossy()).is_err());
+    }
+}
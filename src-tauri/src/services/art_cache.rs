@@ -0,0 +1,327 @@
+//! On-disk cache for Spotify album art
+//!
+//! Covers are downloaded once through the shared HTTP client and stored under
+//! the app data directory, named after a hash of their URL. The cache is trimmed
+//! to a maximum size, evicting the least recently used images first (a cache
+//! hit refreshes the file's modification time).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::instrument;
+
+use crate::errors::AppError;
+use crate::events;
+use crate::services::HttpService;
+use crate::utils::get_app_data_dir;
+
+/// Images downloaded at the same time
+const MAX_CONCURRENT_DOWNLOADS: usize = 6;
+/// Size the cache is trimmed to after downloading
+const MAX_CACHE_BYTES: u64 = 200 * 1024 * 1024;
+/// Extensions cached images may have, by mime type
+const IMAGE_EXTENSIONS: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/webp", "webp"),
+    ("image/gif", "gif"),
+];
+
+/// Album art caching progress event payload
+#[derive(Serialize, Clone)]
+pub struct ArtCacheProgress {
+    /// URL that was just handled
+    pub url: String,
+    /// Whether the image was already cached
+    pub cached: bool,
+    /// Whether the image is now available locally
+    pub success: bool,
+    /// URLs handled so far
+    pub completed: usize,
+    /// URLs requested
+    pub total: usize,
+}
+
+/// Service for caching remote album art on disk
+pub struct ArtCacheService;
+
+impl ArtCacheService {
+    /// Makes every URL available locally, returning the cached file paths
+    ///
+    /// Results are in the same order as `urls`; a URL that couldn't be downloaded
+    /// yields `None`. Emits `art-cache-progress` after each URL.
+    #[instrument(skip_all, fields(count = urls.len()))]
+    pub async fn cache_album_art(
+        client: &reqwest::Client,
+        urls: Vec<String>,
+        app_handle: &AppHandle,
+    ) -> Result<Vec<Option<String>>, AppError> {
+        let cache_dir = Self::cache_dir();
+        fs::create_dir_all(&cache_dir)?;
+
+        let paths = Self::cache_into(client, &cache_dir, urls, |progress| {
+            let _ = app_handle.emit(events::ART_CACHE_PROGRESS, progress);
+        })
+        .await;
+
+        Self::evict(&cache_dir, MAX_CACHE_BYTES)?;
+        Ok(paths)
+    }
+
+    /// Caches every URL into `cache_dir`, at most [`MAX_CONCURRENT_DOWNLOADS`]
+    /// at a time, reporting each one to `on_progress`
+    async fn cache_into(
+        client: &reqwest::Client,
+        cache_dir: &Path,
+        urls: Vec<String>,
+        on_progress: impl Fn(ArtCacheProgress),
+    ) -> Vec<Option<String>> {
+        let total = urls.len();
+        let completed = AtomicUsize::new(0);
+        let on_progress = &on_progress;
+
+        let paths: Vec<Option<String>> = stream::iter(urls)
+            .map(|url| {
+                let completed = &completed;
+                async move {
+                    let cached = Self::cached_path(cache_dir, &url);
+                    let path = match cached.clone() {
+                        Some(path) => Some(path),
+                        None => Self::download(client, cache_dir, &url)
+                            .await
+                            .map_err(|e| tracing::warn!("🖼️ Failed to cache {}: {}", url, e))
+                            .ok(),
+                    };
+
+                    on_progress(ArtCacheProgress {
+                        url,
+                        cached: cached.is_some(),
+                        success: path.is_some(),
+                        completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                        total,
+                    });
+                    path.map(|p| p.to_string_lossy().to_string())
+                }
+            })
+            .buffered(MAX_CONCURRENT_DOWNLOADS)
+            .collect()
+            .await;
+
+        tracing::info!(
+            "🖼️ Album art cached: {}/{} available",
+            paths.iter().filter(|p| p.is_some()).count(),
+            total
+        );
+        paths
+    }
+
+    /// Directory holding the cached images
+    fn cache_dir() -> PathBuf {
+        get_app_data_dir().join("album_art_cache")
+    }
+
+    /// Returns the cached file for `url`, marking it as recently used
+    fn cached_path(cache_dir: &Path, url: &str) -> Option<PathBuf> {
+        let key = Self::cache_key(url);
+        let path = IMAGE_EXTENSIONS
+            .iter()
+            .map(|(_, extension)| cache_dir.join(format!("{}.{}", key, extension)))
+            .find(|path| path.is_file())?;
+
+        if let Err(e) = fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            tracing::debug!("🖼️ Could not refresh {}: {}", path.display(), e);
+        }
+        Some(path)
+    }
+
+    /// Downloads `url` into the cache
+    ///
+    /// The image is written to a temporary file first so an interrupted download
+    /// never leaves a truncated image behind.
+    async fn download(
+        client: &reqwest::Client,
+        cache_dir: &Path,
+        url: &str,
+    ) -> Result<PathBuf, AppError> {
+        let (bytes, mime_type) = HttpService::fetch_image(client, url).await?;
+        let extension = IMAGE_EXTENSIONS
+            .iter()
+            .find(|(mime, _)| *mime == mime_type)
+            .map(|(_, extension)| *extension)
+            .ok_or_else(|| {
+                AppError::ExternalApi(format!("Unsupported image type '{}': {}", mime_type, url))
+            })?;
+
+        let key = Self::cache_key(url);
+        let path = cache_dir.join(format!("{}.{}", key, extension));
+        let temp_path = cache_dir.join(format!("{}.part", key));
+        tokio::fs::write(&temp_path, &bytes).await?;
+        tokio::fs::rename(&temp_path, &path).await?;
+        Ok(path)
+    }
+
+    /// Deletes the least recently used images until the cache fits in `max_bytes`
+    fn evict(cache_dir: &Path, max_bytes: u64) -> Result<(), AppError> {
+        let mut entries: Vec<(PathBuf, u64, SystemTime)> = fs::read_dir(cache_dir)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                metadata.is_file().then(|| {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    (entry.path(), metadata.len(), modified)
+                })
+            })
+            .collect();
+
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if size <= max_bytes {
+            return Ok(());
+        }
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        let mut removed = 0;
+        for (path, len, _) in entries {
+            if size <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    size -= len;
+                    removed += 1;
+                }
+                Err(e) => tracing::warn!("🖼️ Failed to evict {}: {}", path.display(), e),
+            }
+        }
+
+        tracing::info!("🖼️ Evicted {} cached images", removed);
+        Ok(())
+    }
+
+    /// Stable file name for `url` (64-bit FNV-1a, hex)
+    fn cache_key(url: &str) -> String {
+        let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{:016x}", hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const JPEG_BYTES: &[u8] = b"\xff\xd8\xff\xe0fake jpeg";
+
+    fn jpeg_server() -> MockServer {
+        MockServer::start(|request| {
+            if request.url.starts_with("/missing") {
+                return MockResponse::new(404, "not found");
+            }
+            MockResponse::new(200, JPEG_BYTES).with_header("Content-Type", "image/jpeg")
+        })
+    }
+
+    /// Progress events arrive as downloads finish, so compare them by URL
+    fn sorted(progress: &Mutex<Vec<(String, bool, bool)>>) -> Vec<(String, bool, bool)> {
+        let mut progress = progress.lock().unwrap().clone();
+        progress.sort();
+        progress
+    }
+
+    #[tokio::test]
+    async fn downloads_on_a_miss_and_reuses_the_file_on_a_hit() {
+        let server = jpeg_server();
+        let dir = tempfile::tempdir().unwrap();
+        let client = reqwest::Client::new();
+        let urls = vec![
+            format!("{}/cover.jpg", server.url()),
+            format!("{}/missing.jpg", server.url()),
+        ];
+
+        let progress = Mutex::new(Vec::new());
+        let first = ArtCacheService::cache_into(&client, dir.path(), urls.clone(), |p| {
+            progress.lock().unwrap().push((p.url, p.cached, p.success))
+        })
+        .await;
+
+        let cached = first[0].as_deref().unwrap();
+        assert!(cached.ends_with(".jpg"));
+        assert_eq!(fs::read(cached).unwrap(), JPEG_BYTES);
+        assert_eq!(first[1], None);
+        assert_eq!(
+            sorted(&progress),
+            [
+                (urls[0].clone(), false, true),
+                (urls[1].clone(), false, false)
+            ]
+        );
+        assert_eq!(server.requests().len(), 2);
+
+        progress.lock().unwrap().clear();
+        let second = ArtCacheService::cache_into(&client, dir.path(), urls.clone(), |p| {
+            progress.lock().unwrap().push((p.url, p.cached, p.success))
+        })
+        .await;
+
+        assert_eq!(second, first);
+        assert_eq!(
+            sorted(&progress),
+            [
+                (urls[0].clone(), true, true),
+                (urls[1].clone(), false, false)
+            ]
+        );
+        // Only the missing image is requested again
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn downloads_at_most_the_concurrency_limit_at_once() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            MockServer::start(move |_| {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                MockResponse::new(200, JPEG_BYTES).with_header("Content-Type", "image/jpeg")
+            })
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let urls: Vec<String> = (0..20)
+            .map(|i| format!("{}/cover{}.jpg", server.url(), i))
+            .collect();
+
+        // tiny_http can queue a new connection behind workers parked on idle
+        // keep-alive connections until they time out, so don't keep any
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap();
+
+        let paths = ArtCacheService::cache_into(&client, dir.path(), urls, |_| {}).await;
+
+        assert!(paths.iter().all(Option::is_some));
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(
+            (2..=MAX_CONCURRENT_DOWNLOADS).contains(&peak),
+            "peak of {} downloads",
+            peak
+        );
+    }
+}
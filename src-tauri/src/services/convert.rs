@@ -0,0 +1,328 @@
+//! Audio conversion service using ffmpeg
+//!
+//! Transcodes local files between the formats the app can download (e.g. FLAC to
+//! MP3) without going through spotdl, reporting progress from ffmpeg's stderr.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tokio::time::{timeout, Duration};
+use tracing::instrument;
+
+use crate::errors::{AppError, ConvertError};
+use crate::events;
+use crate::services::ShutdownState;
+use crate::utils::{validate_download_format, validate_file, validate_output_path};
+
+/// Upper bound for a single conversion
+const CONVERT_TIMEOUT_SECS: u64 = 600;
+/// Bitrate used for lossy formats when the caller doesn't pick one
+const DEFAULT_BITRATE_KBPS: u32 = 320;
+/// Bitrates accepted for lossy formats, in kbps
+const MIN_BITRATE_KBPS: u32 = 32;
+const MAX_BITRATE_KBPS: u32 = 320;
+
+/// Conversion progress event payload
+#[derive(Serialize, Clone)]
+pub struct ConvertProgress {
+    /// File being converted
+    pub input_path: String,
+    /// File being written
+    pub output_path: String,
+    /// Seconds of audio converted so far
+    pub seconds: f64,
+    /// Input duration in seconds, when ffmpeg reported it
+    pub duration: Option<f64>,
+    /// Percentage converted (0-100), when the duration is known
+    pub percent: Option<f64>,
+}
+
+/// Service for converting audio files with ffmpeg
+pub struct ConvertService;
+
+impl ConvertService {
+    /// Converts `input_path` to `format`, writing `output_path`
+    ///
+    /// `format` is one of the download formats and must match the output extension.
+    /// `bitrate` (kbps, 32-320) applies to lossy formats and defaults to 320; it is
+    /// ignored for FLAC. An existing output file is overwritten.
+    #[instrument(skip_all, fields(input_path = %input_path, format = %format))]
+    pub async fn convert(
+        input_path: &str,
+        output_path: &str,
+        format: &str,
+        bitrate: Option<u32>,
+        app_handle: &AppHandle,
+    ) -> Result<String, AppError> {
+        validate_download_format(format)?;
+        let input = validate_file(input_path)?;
+        let output = validate_output_path(output_path)?;
+
+        let extension = output.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !extension.eq_ignore_ascii_case(format) {
+            return Err(AppError::Validation(format!(
+                "Output file must have the .{} extension: {}",
+                format, output_path
+            )));
+        }
+        if output
+            .canonicalize()
+            .is_ok_and(|existing| existing == input)
+        {
+            return Err(AppError::Validation(format!(
+                "Output file is the same as the input: {}",
+                output_path
+            )));
+        }
+
+        let bitrate = bitrate.unwrap_or(DEFAULT_BITRATE_KBPS);
+        if !(MIN_BITRATE_KBPS..=MAX_BITRATE_KBPS).contains(&bitrate) {
+            return Err(ConvertError::InvalidBitrate(bitrate).into());
+        }
+
+        let mut cmd = Command::new("ffmpeg");
+        cmd.args(Self::build_args(&input, &output, format, bitrate))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        let child = cmd.spawn().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                tracing::error!("❌ ffmpeg is not installed");
+                ConvertError::FfmpegNotInstalled
+            }
+            _ => ConvertError::Failed(e.to_string()),
+        })?;
+
+        let progress = ConvertProgress {
+            input_path: input_path.to_string(),
+            output_path: output_path.to_string(),
+            seconds: 0.0,
+            duration: None,
+            percent: None,
+        };
+
+        let shutdown = app_handle.state::<ShutdownState>();
+        let cancelled = shutdown.cancellation_token();
+        let _guard = shutdown.track_process();
+
+        // Dropping the run future drops the child, which kills ffmpeg
+        let (status, last_line) = tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => {
+                return Err(ConvertError::Failed("cancelled: app is shutting down".into()).into());
+            }
+            result = timeout(
+                Duration::from_secs(CONVERT_TIMEOUT_SECS),
+                Self::run_ffmpeg(child, progress, app_handle),
+            ) => result.map_err(|_| {
+                ConvertError::Failed(format!("ffmpeg timed out after {}s", CONVERT_TIMEOUT_SECS))
+            })??,
+        };
+
+        if !status.success() {
+            // ffmpeg prints the reason for failing last
+            return Err(ConvertError::Failed(last_line).into());
+        }
+
+        tracing::info!("🎚️ Converted {} to {}", input_path, output_path);
+        Ok(output_path.to_string())
+    }
+
+    /// Builds the ffmpeg arguments for converting `input` into `output`
+    ///
+    /// Only the audio streams are kept (embedded cover art would need a video
+    /// codec in formats like OGG); tags are copied from the input.
+    fn build_args(input: &Path, output: &Path, format: &str, bitrate: u32) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["-hide_banner", "-nostdin", "-y", "-i"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        args.push(input.into());
+        args.extend(
+            ["-map", "0:a", "-map_metadata", "0"]
+                .into_iter()
+                .map(OsString::from),
+        );
+        args.extend(
+            Self::codec_args(format, bitrate)
+                .into_iter()
+                .map(OsString::from),
+        );
+        args.push(output.into());
+        args
+    }
+
+    /// Encoder arguments for a download format
+    fn codec_args(format: &str, bitrate: u32) -> Vec<String> {
+        let codec = match format {
+            "mp3" => "libmp3lame",
+            "ogg" => "libvorbis",
+            "m4a" => "aac",
+            "opus" => "libopus",
+            // Lossless: no bitrate to set
+            _ => return vec!["-c:a".into(), "flac".into()],
+        };
+
+        vec![
+            "-c:a".into(),
+            codec.into(),
+            "-b:a".into(),
+            format!("{}k", bitrate),
+        ]
+    }
+
+    /// Waits for ffmpeg, emitting progress for every `time=` status update
+    ///
+    /// Returns the exit status and the last stderr line, which holds the error
+    /// message when ffmpeg fails.
+    async fn run_ffmpeg(
+        mut child: Child,
+        mut progress: ConvertProgress,
+        app_handle: &AppHandle,
+    ) -> Result<(ExitStatus, String), AppError> {
+        let mut stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ConvertError::Failed("ffmpeg stderr was not captured".into()))?;
+
+        let mut buf = [0u8; 4096];
+        let mut pending = Vec::new();
+        let mut last_line = String::new();
+
+        loop {
+            let read = stderr.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buf[..read]);
+
+            // Status updates end in '\r', regular log lines in '\n'
+            while let Some(end) = pending.iter().position(|&b| b == b'\r' || b == b'\n') {
+                let raw: Vec<u8> = pending.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&raw);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+
+                if progress.duration.is_none() {
+                    progress.duration = Self::parse_duration(line);
+                }
+                if let Some(seconds) = Self::parse_time(line) {
+                    progress.seconds = seconds;
+                    progress.percent = progress
+                        .duration
+                        .filter(|d| *d > 0.0)
+                        .map(|d| (seconds / d * 100.0).min(100.0));
+                    let _ = app_handle.emit(events::CONVERT_PROGRESS, progress.clone());
+                }
+                last_line = line.to_string();
+            }
+        }
+
+        let status = child.wait().await?;
+        Ok((status, last_line))
+    }
+
+    /// Parses the input duration from a `Duration: 00:03:20.45, start: ...` line
+    fn parse_duration(line: &str) -> Option<f64> {
+        let rest = line.strip_prefix("Duration:")?.trim_start();
+        Self::parse_timestamp(rest.split(',').next()?)
+    }
+
+    /// Parses the position from a `size=... time=00:01:02.50 bitrate=...` status line
+    fn parse_time(line: &str) -> Option<f64> {
+        let start = line.find("time=")? + "time=".len();
+        Self::parse_timestamp(line[start..].split_whitespace().next()?)
+    }
+
+    /// Parses an ffmpeg `HH:MM:SS.ss` timestamp into seconds (`N/A` yields `None`)
+    fn parse_timestamp(value: &str) -> Option<f64> {
+        let mut parts = value.trim().splitn(3, ':');
+        let hours: f64 = parts.next()?.parse().ok()?;
+        let minutes: f64 = parts.next()?.parse().ok()?;
+        let seconds: f64 = parts.next()?.parse().ok()?;
+        Some(hours * 3600.0 + minutes * 60.0 + seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(format: &str, bitrate: u32) -> Vec<String> {
+        let output = format!("/music/out file.{}", format);
+        ConvertService::build_args(
+            Path::new("/music/in.wav"),
+            Path::new(&output),
+            format,
+            bitrate,
+        )
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+    }
+
+    #[test]
+    fn builds_lossy_ffmpeg_arguments() {
+        assert_eq!(
+            args("mp3", 192),
+            [
+                "-hide_banner",
+                "-nostdin",
+                "-y",
+                "-i",
+                "/music/in.wav",
+                "-map",
+                "0:a",
+                "-map_metadata",
+                "0",
+                "-c:a",
+                "libmp3lame",
+                "-b:a",
+                "192k",
+                "/music/out file.mp3",
+            ]
+        );
+        for (format, codec) in [("ogg", "libvorbis"), ("m4a", "aac"), ("opus", "libopus")] {
+            let args = args(format, 320);
+            assert_eq!(args[9..13], ["-c:a", codec, "-b:a", "320k"]);
+        }
+    }
+
+    #[test]
+    fn flac_gets_no_bitrate() {
+        let args = args("flac", 320);
+        assert_eq!(args[9..], ["-c:a", "flac", "/music/out file.flac"]);
+        assert!(!args.iter().any(|arg| arg == "-b:a"));
+    }
+
+    #[test]
+    fn parses_ffmpeg_progress_lines() {
+        assert_eq!(
+            ConvertService::parse_duration(
+                "Duration: 00:03:20.50, start: 0.000000, bitrate: 1010 kb/s"
+            ),
+            Some(200.5)
+        );
+        assert_eq!(
+            ConvertService::parse_time(
+                "size=    1024kB time=00:01:02.50 bitrate= 320.0kbits/s speed=41x"
+            ),
+            Some(62.5)
+        );
+        assert_eq!(
+            ConvertService::parse_time("size=N/A time=N/A bitrate=N/A"),
+            None
+        );
+    }
+}
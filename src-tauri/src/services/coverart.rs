@@ -0,0 +1,244 @@
+//! Album cover lookup through MusicBrainz and the Cover Art Archive
+//!
+//! Used as a fallback when Last.fm has no image for an album: the album is
+//! searched on MusicBrainz and the front cover of the matching release group is
+//! taken from the Cover Art Archive.
+
+use std::sync::LazyLock;
+
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::instrument;
+
+use crate::domain::coverart::raw;
+use crate::errors::AppError;
+
+const ENDPOINTS: Endpoints<'static> = Endpoints {
+    musicbrainz_search: "https://musicbrainz.org/ws/2/release/",
+    cover_art_archive: "https://coverartarchive.org/release-group",
+};
+/// MusicBrainz rejects requests without an identifying User-Agent
+const USER_AGENT: &str = concat!(
+    "musicplayer/",
+    env!("CARGO_PKG_VERSION"),
+    " ( https://github.com/yosimar43/musicplayer )"
+);
+/// MusicBrainz allows one request per second per client
+const MUSICBRAINZ_MIN_INTERVAL_MS: u64 = 1000;
+/// Releases scored below this are not considered a match
+const MIN_MATCH_SCORE: u32 = 90;
+/// Releases checked on the Cover Art Archive before giving up
+const MAX_CANDIDATES: usize = 3;
+
+/// Time of the last MusicBrainz request, shared by every lookup
+static MUSICBRAINZ_THROTTLE: LazyLock<Mutex<Option<Instant>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Base URLs of the two APIs, pointed at a local server in tests
+struct Endpoints<'a> {
+    musicbrainz_search: &'a str,
+    cover_art_archive: &'a str,
+}
+
+/// Service for finding album covers outside Last.fm
+pub struct CoverArtService;
+
+impl CoverArtService {
+    /// Finds a front cover URL for an album
+    ///
+    /// Returns `Ok(None)` when MusicBrainz has no confident match or none of the
+    /// matches has cover art.
+    #[instrument(skip_all, fields(artist = %artist, album = %album))]
+    pub async fn fetch_album_cover(
+        client: &reqwest::Client,
+        artist: &str,
+        album: &str,
+    ) -> Result<Option<String>, AppError> {
+        Self::lookup(client, &ENDPOINTS, artist, album).await
+    }
+
+    async fn lookup(
+        client: &reqwest::Client,
+        endpoints: &Endpoints<'_>,
+        artist: &str,
+        album: &str,
+    ) -> Result<Option<String>, AppError> {
+        let release_groups =
+            Self::search_release_groups(client, endpoints.musicbrainz_search, artist, album)
+                .await?;
+
+        for release_group in release_groups.iter().take(MAX_CANDIDATES) {
+            if let Some(url) =
+                Self::front_cover(client, endpoints.cover_art_archive, release_group).await?
+            {
+                tracing::info!("🖼️ Cover Art Archive image for {} - {}", artist, album);
+                return Ok(Some(url));
+            }
+        }
+
+        tracing::debug!("🖼️ No Cover Art Archive image for {} - {}", artist, album);
+        Ok(None)
+    }
+
+    /// Searches MusicBrainz, returning the release groups of confident matches
+    async fn search_release_groups(
+        client: &reqwest::Client,
+        search_url: &str,
+        artist: &str,
+        album: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let query = format!(
+            "release:\"{}\" AND artist:\"{}\"",
+            Self::escape_query(album),
+            Self::escape_query(artist)
+        );
+
+        Self::throttle().await;
+        let response = client
+            .get(search_url)
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .query(&[("query", query.as_str()), ("fmt", "json"), ("limit", "10")])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::ExternalApi(format!("MusicBrainz search failed: {}", e)))?;
+
+        let search: raw::ReleaseSearch = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Parse Error: {}", e)))?;
+
+        let mut groups: Vec<String> = Vec::new();
+        for release in search.releases {
+            if release.score < MIN_MATCH_SCORE {
+                continue;
+            }
+            if let Some(group) = release.release_group {
+                if !groups.contains(&group.id) {
+                    groups.push(group.id);
+                }
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Returns the front cover of a release group, `None` if it has none
+    async fn front_cover(
+        client: &reqwest::Client,
+        cover_art_url: &str,
+        release_group: &str,
+    ) -> Result<Option<String>, AppError> {
+        let response = client
+            .get(format!("{}/{}", cover_art_url, release_group))
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Cover Art Archive failed: {}", e)))?;
+
+        // 404 just means nobody uploaded art for this release group
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let listing: raw::CoverArtListing = response
+            .error_for_status()
+            .map_err(|e| AppError::ExternalApi(format!("Cover Art Archive failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Parse Error: {}", e)))?;
+
+        Ok(listing
+            .images
+            .into_iter()
+            .find(|image| image.front)
+            .map(|image| {
+                image
+                    .thumbnails
+                    .medium
+                    .or(image.thumbnails.large)
+                    .unwrap_or(image.image)
+            }))
+    }
+
+    /// Waits until a MusicBrainz request is allowed
+    ///
+    /// The lock is held while sleeping so concurrent lookups queue up.
+    async fn throttle() {
+        let mut last_request = MUSICBRAINZ_THROTTLE.lock().await;
+        if let Some(last) = *last_request {
+            let min_interval = Duration::from_millis(MUSICBRAINZ_MIN_INTERVAL_MS);
+            let elapsed = last.elapsed();
+            if elapsed < min_interval {
+                sleep(min_interval - elapsed).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// Escapes Lucene special characters inside a quoted search term
+    fn escape_query(term: &str) -> String {
+        term.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn finds_the_front_cover_of_the_first_release_group_with_art() {
+        let server = MockServer::start(|request| match request.path() {
+            "/ws/2/release/" => MockResponse::new(
+                200,
+                r#"{"releases": [
+                    {"score": 100, "release-group": {"id": "no-art"}},
+                    {"score": 40, "release-group": {"id": "weak-match"}},
+                    {"score": 95, "release-group": {"id": "no-art"}},
+                    {"score": 92, "release-group": {"id": "with-art"}}
+                ]}"#,
+            ),
+            "/release-group/with-art" => MockResponse::new(
+                200,
+                r#"{"images": [
+                    {"front": false, "image": "http://img/back.jpg"},
+                    {"front": true, "image": "http://img/front.jpg",
+                     "thumbnails": {"500": "http://img/front-500.jpg", "large": "http://img/front-large.jpg"}}
+                ]}"#,
+            ),
+            _ => MockResponse::new(404, ""),
+        });
+        let endpoints = Endpoints {
+            musicbrainz_search: &format!("{}/ws/2/release/", server.url()),
+            cover_art_archive: &format!("{}/release-group", server.url()),
+        };
+
+        let cover = CoverArtService::lookup(
+            &reqwest::Client::new(),
+            &endpoints,
+            "Artist",
+            "Album \"Live\"",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(cover.as_deref(), Some("http://img/front-500.jpg"));
+
+        let requests = server.requests();
+        let paths: Vec<&str> = requests.iter().map(|request| request.path()).collect();
+        // Duplicates and weak matches are never looked up
+        assert_eq!(
+            paths,
+            [
+                "/ws/2/release/",
+                "/release-group/no-art",
+                "/release-group/with-art"
+            ]
+        );
+        assert_eq!(
+            requests[0].query("query").as_deref(),
+            Some(r#"release:"Album \"Live\"" AND artist:"Artist""#)
+        );
+        assert!(requests
+            .iter()
+            .all(|request| request.header("User-Agent") == Some(USER_AGENT)));
+    }
+}
@@ -0,0 +1,148 @@
+//! Diagnostics for support requests
+//!
+//! Probes the external tools the app shells out to and gathers the
+//! configuration that usually explains a bug report.
+
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+use tracing::instrument;
+
+use crate::domain::diagnostics::{AudioOutput, Diagnostics};
+use crate::services::DownloadService;
+use crate::utils::get_default_music_folder;
+
+/// Upper bound for each tool probe
+const PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// Service for collecting diagnostics
+pub struct DiagnosticsService;
+
+impl DiagnosticsService {
+    /// Collects diagnostics, probing spotdl, yt-dlp and ffmpeg concurrently
+    ///
+    /// A tool that is missing or doesn't answer within 5 seconds is reported as
+    /// not installed; this never fails.
+    #[instrument(skip_all)]
+    pub async fn collect(
+        app_version: String,
+        spotify_authenticated: bool,
+        lastfm_configured: bool,
+    ) -> Diagnostics {
+        let (spotdl, yt_dlp_version, ffmpeg_version) = tokio::join!(
+            DownloadService::check_installed(),
+            Self::tool_version("yt-dlp", "--version"),
+            Self::tool_version("ffmpeg", "-version"),
+        );
+        let spotdl = spotdl.ok();
+
+        Diagnostics {
+            app_version,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            spotdl_meets_minimum: spotdl.as_ref().map(|s| s.meets_minimum),
+            spotdl_version: spotdl.map(|s| s.version),
+            yt_dlp_version,
+            ffmpeg_version,
+            spotify_authenticated,
+            lastfm_configured,
+            default_music_folder: get_default_music_folder().ok(),
+        }
+    }
+
+    /// Runs `program version_flag` and returns the first line of its output
+    async fn tool_version(program: &str, version_flag: &str) -> Option<String> {
+        let mut cmd = Command::new(program);
+        cmd.arg(version_flag).kill_on_drop(true);
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        let output = timeout(Duration::from_secs(PROBE_TIMEOUT_SECS), cmd.output())
+            .await
+            .map_err(|_| {
+                tracing::warn!(
+                    "⚠️ {} did not answer within {}s",
+                    program,
+                    PROBE_TIMEOUT_SECS
+                )
+            })
+            .ok()?
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+    }
+
+    /// Lists the host's audio output devices, for display only (nothing is played)
+    ///
+    /// Needs the `audio-devices` feature; without it, or when the host has no
+    /// output device or can't be queried, the list is empty.
+    #[instrument(skip_all)]
+    pub fn list_audio_outputs() -> Vec<AudioOutput> {
+        #[cfg(feature = "audio-devices")]
+        {
+            use cpal::traits::{DeviceTrait, HostTrait};
+
+            let host = cpal::default_host();
+            let default_name = host.default_output_device().and_then(|d| d.name().ok());
+            let devices = match host.output_devices() {
+                Ok(devices) => devices,
+                Err(e) => {
+                    tracing::warn!("⚠️ Could not list audio outputs: {}", e);
+                    return Vec::new();
+                }
+            };
+
+            devices
+                .filter_map(|device| {
+                    let name = device.name().ok()?;
+                    let rates: Vec<(u32, u32)> = device
+                        .supported_output_configs()
+                        .map(|configs| {
+                            configs
+                                .map(|c| (c.min_sample_rate().0, c.max_sample_rate().0))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    Some(AudioOutput {
+                        is_default: default_name.as_ref() == Some(&name),
+                        name,
+                        min_sample_rate: rates.iter().map(|(min, _)| *min).min(),
+                        max_sample_rate: rates.iter().map(|(_, max)| *max).max(),
+                    })
+                })
+                .collect()
+        }
+
+        #[cfg(not(feature = "audio-devices"))]
+        {
+            tracing::debug!("Built without the audio-devices feature, no outputs listed");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_audio_outputs_never_panics() {
+        // CI machines usually have no sound card, which must give an empty list
+        let outputs = DiagnosticsService::list_audio_outputs();
+
+        assert!(outputs.iter().filter(|o| o.is_default).count() <= 1);
+        for output in &outputs {
+            assert!(
+                output.min_sample_rate <= output.max_sample_rate,
+                "{:?}",
+                output
+            );
+        }
+        #[cfg(not(feature = "audio-devices"))]
+        assert!(outputs.is_empty());
+    }
+}
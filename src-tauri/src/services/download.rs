@@ -3,24 +3,58 @@
 //! Handles downloading tracks with controlled concurrency, progress reporting,
 //! and comprehensive error handling.
 
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
 use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
 use serde::Serialize;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-#[cfg(windows)]
+use tokio::sync::Semaphore;
 use tokio::time::{timeout, Duration};
 use tracing::instrument;
 
+use crate::domain::spotify::SpotifyTrack;
 use crate::errors::{AppError, DownloadError};
+use crate::events;
+use crate::services::download_history::DownloadHistory;
+use crate::services::download_queue::DownloadQueue;
+use crate::services::{AllowedRoots, FileService, SettingsState, ShutdownState};
 use crate::utils::{
-    extract_song_id, validate_download_format, validate_output_path,
-    validate_spotify_url,
+    extract_song_id, sanitize_output_template, spotify_album_url, spotify_track_url,
+    validate_batch_size, validate_download_format, validate_or_create_output_path,
+    validate_spotify_url, validate_within_roots, validate_writable, Jitter,
 };
 
 /// Download configuration constants
 const SPOTDL_TIMEOUT_SECS: u64 = 120;
-const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+/// Oldest spotdl whose CLI flags (`--audio`, `--preload`, ...) we rely on
+const MIN_SPOTDL_VERSION: (u64, u64, u64) = (4, 2, 0);
 const BATCH_SIZE: usize = 12;
+/// spotdl download threads shared by all concurrent batches of a run
+const SPOTDL_THREAD_BUDGET: usize = 8;
+/// Songs allowed per batch download unless the caller raises it
+const MAX_SONGS_PER_BATCH: usize = 100;
+/// Absolute ceiling for a caller-provided `max_songs`
+const MAX_SONGS_CEILING: usize = 500;
+/// Bounds for the pause between batch starts, before and after jitter
+const MIN_DELAY_SECS: u64 = 2;
+const MAX_DELAY_SECS: u64 = 10;
+/// Fraction of the configured delay added or removed at random
+const DELAY_JITTER: f64 = 0.25;
+/// spotdl template used when downloading from a track object: `Artist - Title.ext`
+const TRACK_OUTPUT_TEMPLATE: &str = "{artists} - {title}.{output-ext}";
+/// spotdl template for album downloads: one folder per album, tracks in order
+const ALBUM_OUTPUT_TEMPLATE: &str =
+    "{album-artist} - {album}/{track-number} - {title}.{output-ext}";
+/// A whole album runs in one spotdl process, so it gets far longer than a batch
+const ALBUM_TIMEOUT_SECS: u64 = 1800;
 
 /// Download progress event payload
 #[derive(Serialize, Clone)]
@@ -35,9 +69,56 @@ pub struct DownloadProgress {
     pub status: String,
     /// Spotify URL being downloaded
     pub url: String,
+    /// File spotdl wrote, when it could be determined
+    pub output_path: Option<String>,
+    /// Estimated seconds until the whole run finishes, once a batch has completed
+    pub eta_secs: Option<f32>,
+}
+
+/// Result of a single track download
+#[derive(Serialize, Clone)]
+pub struct DownloadResult {
+    /// Status message
+    pub message: String,
+    /// File spotdl wrote, `None` when it could not be determined from its output
+    pub output_path: Option<String>,
 }
 
-/// Download completion event
+/// Emitted when a song starts downloading, before spotdl runs
+#[derive(Serialize, Clone)]
+pub struct DownloadStarted {
+    /// Song name or identifier
+    pub song: String,
+    /// Current index in the download queue
+    pub index: usize,
+    /// Total number of songs to download
+    pub total: usize,
+    /// Spotify URL being downloaded
+    pub url: String,
+}
+
+/// URLs of a spawned batch and its spotdl result, `None` when it was skipped
+type BatchOutcome = (Vec<String>, Option<Result<(), AppError>>);
+
+/// Per-song event of a spotdl batch, in the order `report_batch` sends them
+enum BatchEvent {
+    Started(DownloadStarted),
+    /// Progress with the final status, and whether the batch succeeded
+    Finished(DownloadProgress, bool),
+}
+
+/// Download abort event, sent before `DownloadFinished`
+#[derive(Serialize, Clone)]
+pub struct DownloadAborted {
+    /// Explanation for the user
+    pub message: String,
+    /// Failed batches in a row that stopped the run
+    pub consecutive_failures: usize,
+    /// Songs never attempted; they stay queued for `resume_downloads`
+    pub total_skipped: usize,
+}
+
+/// Download completion event, also returned by batch downloads
 #[derive(Serialize, Clone)]
 pub struct DownloadFinished {
     /// Final status message
@@ -48,6 +129,40 @@ pub struct DownloadFinished {
     pub total_failed: usize,
 }
 
+/// What is wrong with a downloaded file
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadProblemKind {
+    /// Missing, or not a readable file
+    Missing,
+    /// Zero bytes, usually an interrupted write
+    Empty,
+    /// Not a supported audio format
+    NotAudio,
+    /// Audio stream that does not parse, e.g. truncated by yt-dlp
+    Corrupt,
+}
+
+/// A downloaded file that failed verification
+#[derive(Serialize, Clone)]
+pub struct DownloadProblem {
+    /// File path as given
+    pub path: String,
+    /// What is wrong with it
+    pub kind: DownloadProblemKind,
+    /// Error details
+    pub message: String,
+}
+
+/// Installed spotdl version
+#[derive(Serialize, Clone)]
+pub struct SpotdlVersion {
+    /// Version reported by `spotdl --version`
+    pub version: String,
+    /// Whether it is at least the minimum supported version
+    pub meets_minimum: bool,
+}
+
 /// Download error event (currently unused but available for future use)
 #[allow(dead_code)]
 #[derive(Serialize, Clone)]
@@ -61,8 +176,10 @@ pub struct DownloadService;
 
 impl DownloadService {
     /// Checks if spotdl is installed and returns its version
+    ///
+    /// A version string that cannot be parsed is assumed to be recent enough.
     #[instrument(skip_all)]
-    pub async fn check_installed() -> Result<String, AppError> {
+    pub async fn check_installed() -> Result<SpotdlVersion, AppError> {
         const CHECK_TIMEOUT_SECS: u64 = 5;
 
         let mut cmd = Command::new("spotdl");
@@ -80,8 +197,18 @@ impl DownloadService {
             Ok(Ok(output)) if output.status.success() => {
                 let version = String::from_utf8_lossy(&output.stdout);
                 let version_str = version.trim().to_string();
+                let meets_minimum = match Self::parse_version(&version_str) {
+                    Some(parsed) => Self::meets_minimum(parsed),
+                    None => {
+                        tracing::warn!("⚠️ Could not parse spotdl version: {}", version_str);
+                        true
+                    }
+                };
                 tracing::info!("✅ spotdl found: {}", version_str);
-                Ok(version_str)
+                Ok(SpotdlVersion {
+                    version: version_str,
+                    meets_minimum,
+                })
             }
             Ok(Ok(_)) => {
                 tracing::error!("❌ spotdl does not respond correctly");
@@ -98,16 +225,60 @@ impl DownloadService {
         }
     }
 
+    /// Checks that a supported spotdl is installed before downloading
+    async fn ensure_supported() -> Result<(), AppError> {
+        let installed = Self::check_installed().await?;
+        if !installed.meets_minimum {
+            let (major, minor, patch) = MIN_SPOTDL_VERSION;
+            return Err(DownloadError::SpotdlOutdated(
+                installed.version,
+                format!("{}.{}.{}", major, minor, patch),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Parses `major.minor.patch` out of `spotdl --version` output (e.g. `4.2.5`,
+    /// `v4.3.0-beta.1`), returning whether it carries a pre-release suffix
+    fn parse_version(output: &str) -> Option<((u64, u64, u64), bool)> {
+        let token = output
+            .split_whitespace()
+            .map(|t| t.trim_start_matches('v'))
+            .find(|t| t.starts_with(|c: char| c.is_ascii_digit()))?;
+
+        let (core, pre_release) = match token.find(['-', '+']) {
+            Some(i) => (&token[..i], token[i..].starts_with('-')),
+            None => (token, false),
+        };
+
+        let mut parts = core.split('.').map(|p| p.parse::<u64>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+
+        Some(((major, minor, patch), pre_release))
+    }
+
+    /// Compares a parsed version against the minimum; a pre-release of the minimum
+    /// itself (e.g. `4.2.0-rc1`) is older than the release
+    fn meets_minimum((version, pre_release): ((u64, u64, u64), bool)) -> bool {
+        version > MIN_SPOTDL_VERSION || (version == MIN_SPOTDL_VERSION && !pre_release)
+    }
+
     /// Downloads a batch of Spotify tracks with progress reporting
+    #[allow(clippy::too_many_arguments)]
     async fn download_batch_with_progress(
         urls: Vec<String>,
         output_template: String,
         format: String,
         output_dir: Option<String>,
         start_index: usize,
-        total: usize,
+        threads: usize,
+        eta: Arc<Mutex<EtaTracker>>,
         app_handle: AppHandle,
     ) -> Result<(), AppError> {
+        let total = eta.lock().map(|eta| eta.total).unwrap_or(urls.len());
         let mut cmd = Command::new("spotdl");
         cmd.arg("download");
 
@@ -126,63 +297,108 @@ impl DownloadService {
 
         cmd.arg("--format").arg(&format);
         cmd.arg("--audio").arg("youtube-music").arg("youtube");
-        cmd.arg("--threads").arg("4"); // 🔥 acelera sin bajar calidad
+        cmd.arg("--threads").arg(threads.to_string());
         cmd.arg("--print-errors");
         cmd.arg("--preload"); // Preload download URLs to speed up mass downloads
         cmd.arg("--max-retries").arg("5"); // Increase retries for better reliability in mass downloads
+        Self::add_cookie_file(&mut cmd, &app_handle);
 
         #[cfg(windows)]
         {
             cmd.creation_flags(0x08000000);
         }
 
-        let result = timeout(
-            Duration::from_secs(SPOTDL_TIMEOUT_SECS),
-            cmd.output()
-        ).await;
-
-        match result {
-            Ok(Ok(output)) if output.status.success() => {
-                // Emitir progreso por canción
-                for (i, url) in urls.iter().enumerate() {
-                    let song = extract_song_id(url);
-                    let _ = app_handle.emit("download-progress", DownloadProgress {
-                        song,
-                        index: start_index + i,
-                        total,
-                        status: "✅ Descargada".into(),
-                        url: url.clone(),
-                    });
-                }
-                Ok(())
+        let run = Self::run_spotdl(cmd, SPOTDL_TIMEOUT_SECS, &app_handle);
+        Self::report_batch(&urls, start_index, total, &eta, run, |event| match event {
+            BatchEvent::Started(started) => {
+                let _ = app_handle.emit(events::DOWNLOAD_STARTED, started);
             }
-            _ => {
-                for (i, url) in urls.iter().enumerate() {
-                    let song = extract_song_id(url);
-                    let _ = app_handle.emit("download-progress", DownloadProgress {
-                        song,
-                        index: start_index + i,
-                        total,
-                        status: "❌ Error en descarga".into(),
-                        url: url.clone(),
-                    });
-                }
-                Err(DownloadError::Failed("Error descargando batch".to_string()).into())
+            BatchEvent::Finished(progress, success) => {
+                DownloadHistory::record(&progress.song, &progress.url, &format, success);
+                let _ = app_handle.emit(events::DOWNLOAD_PROGRESS, progress);
             }
+        })
+        .await
+    }
+
+    /// Awaits a batch's spotdl `run`, sending each song's events to `emit`
+    ///
+    /// spotdl downloads the songs of a batch concurrently without reporting when
+    /// each one starts, so every song gets its `Started` event before the process
+    /// runs and its `Finished` event once it exits, all with the batch's outcome.
+    async fn report_batch(
+        urls: &[String],
+        start_index: usize,
+        total: usize,
+        eta: &Mutex<EtaTracker>,
+        run: impl std::future::Future<
+            Output = Result<
+                Result<std::process::Output, std::io::Error>,
+                tokio::time::error::Elapsed,
+            >,
+        >,
+        mut emit: impl FnMut(BatchEvent),
+    ) -> Result<(), AppError> {
+        for (i, url) in urls.iter().enumerate() {
+            emit(BatchEvent::Started(DownloadStarted {
+                song: extract_song_id(url),
+                index: start_index + i,
+                total,
+                url: url.clone(),
+            }));
+        }
+
+        let started = Instant::now();
+        let result = run.await;
+        let eta_secs = eta
+            .lock()
+            .ok()
+            .and_then(|mut eta| eta.record(urls.len(), started.elapsed()));
+
+        let success = matches!(&result, Ok(Ok(output)) if output.status.success());
+        let status = if success {
+            "✅ Descargada"
+        } else {
+            "❌ Error en descarga"
+        };
+        for (i, url) in urls.iter().enumerate() {
+            let progress = DownloadProgress {
+                song: extract_song_id(url),
+                index: start_index + i,
+                total,
+                status: status.into(),
+                url: url.clone(),
+                output_path: None,
+                eta_secs,
+            };
+            emit(BatchEvent::Finished(progress, success));
+        }
+
+        if success {
+            Ok(())
+        } else {
+            Err(DownloadError::Failed("Error descargando batch".to_string()).into())
         }
     }
 
     /// Downloads multiple Spotify tracks in batches using spotdl with real concurrency
+    ///
+    /// At most `max_songs` URLs are accepted (default 100, clamped to 500). Batch
+    /// starts are spaced by `delay` seconds ±25%, so requests don't follow a fixed
+    /// cadence that YouTube can throttle. Returns the counts sent in
+    /// `download-finished`, which are partial when the run was aborted.
     #[instrument(skip_all, fields(url_count = urls.len()))]
+    #[allow(clippy::too_many_arguments)]
     pub async fn download_tracks_segmented(
         urls: Vec<String>,
         _segment_size: usize, // ya no importa
-        _delay: u64,          // eliminado
+        delay: u64,
         output_template: String,
         format: String,
         output_dir: Option<String>,
+        max_songs: Option<usize>,
         app_handle: &AppHandle,
-    ) -> Result<(), AppError> {
+    ) -> Result<DownloadFinished, AppError> {
         tracing::info!("📥 Starting batched download of {} tracks", urls.len());
 
         // Input validations
@@ -190,84 +406,313 @@ impl DownloadService {
             tracing::warn!("📥 Empty URL list provided");
             return Err(DownloadError::Failed("Lista de URLs vacía".to_string()).into());
         }
+        validate_batch_size(urls.len(), Self::max_songs_limit(max_songs))?;
 
         // Validate format
         validate_download_format(&format)?;
+        let output_template = sanitize_output_template(&output_template)?;
 
         // Validate all URLs
         for url in &urls {
             validate_spotify_url(url)?;
         }
 
-        // Validate output directory if provided
+        // Validate output directory if provided, creating it when missing and checking it is writable
         if let Some(ref dir) = output_dir {
-            validate_output_path(dir)?;
+            validate_writable(&validate_or_create_output_path(dir)?)?;
         }
 
         // Check if spotdl is installed
-        Self::check_installed().await?;
+        Self::ensure_supported().await?;
 
         let total = urls.len();
         let mut downloaded = 0;
         let mut failed = 0;
+        let mut skipped = 0;
+
+        // Persist the batch so it can be resumed if the app dies mid-download
+        let mut queue = DownloadQueue::new(&urls, &output_template, &format, output_dir.as_deref());
+        Self::persist_queue(&queue);
 
-        tracing::info!("📥 Downloading {} songs in batches of {} (max concurrent: {})",
-            total, BATCH_SIZE, MAX_CONCURRENT_DOWNLOADS);
+        let settings = app_handle.state::<SettingsState>().get()?;
+        let max_concurrent = settings.max_concurrent_downloads;
+        let threads = Self::threads_per_process(max_concurrent);
+        tracing::info!(
+            "📥 Downloading {} songs in batches of {} (max concurrent: {}, {} threads each)",
+            total,
+            BATCH_SIZE,
+            max_concurrent,
+            threads
+        );
 
         let batches: Vec<Vec<String>> = urls
             .chunks(BATCH_SIZE)
             .map(|c| c.to_vec())
             .collect();
 
+        // One semaphore for the whole run bounds how many spotdl processes run at
+        // once; batches wait for a permit in order instead of in fixed-size waves
+        let permits = Arc::new(Semaphore::new(max_concurrent));
+        let breaker = FailureBreaker::new(settings.max_consecutive_failures);
+        let eta = Arc::new(Mutex::new(EtaTracker::new(total, max_concurrent)));
         let mut tasks = FuturesUnordered::new();
+        let mut jitter = Jitter::from_clock();
 
         for (batch_idx, batch) in batches.into_iter().enumerate() {
+            // The breaker closes the semaphore; later batches are never started
+            if permits.is_closed() {
+                skipped += batch.len();
+                continue;
+            }
+            if batch_idx > 0 {
+                // Count batches finishing meanwhile so the breaker can trip before
+                // the next one starts
+                let pause = tokio::time::sleep(Self::batch_delay(&mut jitter, delay));
+                tokio::pin!(pause);
+                loop {
+                    tokio::select! {
+                        _ = &mut pause => break,
+                        Some(res) = tasks.next() => {
+                            if Self::record_batch(
+                                res,
+                                &mut queue,
+                                &breaker,
+                                &mut downloaded,
+                                &mut failed,
+                                &mut skipped,
+                            ) {
+                                permits.close();
+                            }
+                        }
+                    }
+                }
+                if permits.is_closed() {
+                    skipped += batch.len();
+                    continue;
+                }
+            }
+
             let app = app_handle.clone();
             let out = output_template.clone();
             let fmt = format.clone();
             let dir = output_dir.clone();
+            let permits = Arc::clone(&permits);
+            let eta = Arc::clone(&eta);
 
             let start_index = batch_idx * BATCH_SIZE + 1;
 
+            // Keep the batch size so a panicked task is still counted in full
+            let songs = batch.len();
             let task = tokio::spawn(async move {
-                Self::download_batch_with_progress(
-                    batch,
+                let download = Self::download_batch_with_progress(
+                    batch.clone(),
                     out,
                     fmt,
                     dir,
                     start_index,
-                    total,
+                    threads,
+                    eta,
                     app,
-                ).await
+                );
+                let result = Self::with_permit(&permits, download).await;
+                (batch, result)
             });
+            tasks.push(task.map(move |res| res.map_err(|e| (songs, e))));
+        }
 
-            tasks.push(task);
-
-            if tasks.len() >= MAX_CONCURRENT_DOWNLOADS {
-                if let Some(res) = tasks.next().await {
-                    match res {
-                        Ok(Ok(_)) => downloaded += BATCH_SIZE,
-                        _ => failed += BATCH_SIZE,
-                    }
-                }
+        while let Some(res) = tasks.next().await {
+            if Self::record_batch(
+                res,
+                &mut queue,
+                &breaker,
+                &mut downloaded,
+                &mut failed,
+                &mut skipped,
+            ) {
+                permits.close();
             }
         }
 
-        while let Some(res) = tasks.next().await {
-            match res {
-                Ok(Ok(_)) => downloaded += BATCH_SIZE,
-                _ => failed += BATCH_SIZE,
+        if queue.pending.is_empty() {
+            if let Err(e) = DownloadQueue::clear() {
+                tracing::warn!("⚠️ Could not delete download queue file: {}", e);
             }
+        } else {
+            tracing::info!(
+                "📥 {} tracks left in the queue for resume_downloads",
+                queue.pending.len()
+            );
+        }
+
+        let aborted = permits.is_closed();
+        if aborted {
+            let _ = app_handle.emit(
+                events::DOWNLOAD_ABORTED,
+                DownloadAborted {
+                    message: format!(
+                        "Descarga detenida tras {} lotes fallidos seguidos; es probable que spotdl o yt-dlp necesiten actualizarse",
+                        breaker.limit
+                    ),
+                    consecutive_failures: breaker.limit,
+                    total_skipped: skipped,
+                },
+            );
         }
 
-        let _ = app_handle.emit("download-finished", DownloadFinished {
-            message: "✅ Descarga completada".into(),
+        let finished = DownloadFinished {
+            message: if aborted {
+                "⛔ Descarga interrumpida".into()
+            } else {
+                "✅ Descarga completada".into()
+            },
             total_downloaded: downloaded.min(total),
             total_failed: failed.min(total),
-        });
+        };
+        let _ = app_handle.emit(events::DOWNLOAD_FINISHED, finished.clone());
 
-        tracing::info!("📥 Download completed: {} downloaded, {} failed", downloaded.min(total), failed.min(total));
-        Ok(())
+        tracing::info!(
+            "📥 Download completed: {} downloaded, {} failed",
+            finished.total_downloaded,
+            finished.total_failed
+        );
+        Ok(finished)
+    }
+
+    /// Runs `work` once a permit is free, `None` if the semaphore was closed first
+    async fn with_permit<T>(
+        permits: &Arc<Semaphore>,
+        work: impl std::future::Future<Output = T>,
+    ) -> Option<T> {
+        let _permit = Arc::clone(permits).acquire_owned().await.ok()?;
+        Some(work.await)
+    }
+
+    /// spotdl `--threads` for each of `max_concurrent` processes
+    ///
+    /// Every process runs its own download threads, so the budget is split
+    /// between them to keep the total number of downloads bounded.
+    fn threads_per_process(max_concurrent: usize) -> usize {
+        (SPOTDL_THREAD_BUDGET / max_concurrent.max(1)).max(1)
+    }
+
+    /// `base_secs` clamped to the delay bounds, ±[`DELAY_JITTER`], clamped again
+    fn batch_delay(jitter: &mut Jitter, base_secs: u64) -> Duration {
+        let min = Duration::from_secs(MIN_DELAY_SECS);
+        let max = Duration::from_secs(MAX_DELAY_SECS);
+        let base = Duration::from_secs(base_secs).clamp(min, max);
+        jitter.apply(base, DELAY_JITTER).clamp(min, max)
+    }
+
+    /// Resolves the per-batch song limit: the default, or the override clamped to the ceiling
+    fn max_songs_limit(max_songs: Option<usize>) -> usize {
+        max_songs
+            .unwrap_or(MAX_SONGS_PER_BATCH)
+            .min(MAX_SONGS_CEILING)
+    }
+
+    /// Counts a finished batch, feeds its outcome to the breaker and drops the
+    /// batch's URLs from the persisted queue on success
+    ///
+    /// spotdl reports a batch as a whole, so the breaker counts batches rather
+    /// than songs. A batch without a result was skipped after the run was
+    /// aborted. Returns true when this batch trips the breaker.
+    fn record_batch(
+        res: Result<BatchOutcome, (usize, tokio::task::JoinError)>,
+        queue: &mut DownloadQueue,
+        breaker: &FailureBreaker,
+        downloaded: &mut usize,
+        failed: &mut usize,
+        skipped: &mut usize,
+    ) -> bool {
+        let success = match res {
+            Ok((batch, Some(Ok(())))) => {
+                *downloaded += batch.len();
+                queue.mark_completed(&batch);
+                Self::persist_queue(queue);
+                true
+            }
+            Ok((batch, Some(Err(_)))) => {
+                *failed += batch.len();
+                false
+            }
+            Ok((batch, None)) => {
+                *skipped += batch.len();
+                return false;
+            }
+            // The task panicked; its URLs stay queued for resume_downloads
+            Err((songs, e)) => {
+                tracing::error!("❌ Download batch task failed: {}", e);
+                *failed += songs;
+                false
+            }
+        };
+
+        let tripped = breaker.record(success);
+        if tripped {
+            tracing::warn!(
+                "📥 {} batches failed in a row, aborting the remaining downloads",
+                breaker.limit
+            );
+        }
+        tripped
+    }
+
+    /// Saves the queue, logging instead of failing so downloads keep going
+    fn persist_queue(queue: &DownloadQueue) {
+        if let Err(e) = queue.save() {
+            tracing::warn!("⚠️ Could not save download queue: {}", e);
+        }
+    }
+
+    /// Resumes the batch left in the persisted queue, returning how many URLs were re-enqueued
+    ///
+    /// URLs whose batch already finished were removed from the queue, so they are
+    /// not downloaded again. Returns 0 when there is nothing to resume.
+    #[instrument(skip_all)]
+    pub async fn resume_downloads(
+        roots: &AllowedRoots,
+        app_handle: &AppHandle,
+    ) -> Result<usize, AppError> {
+        let Some(queue) = Self::load_resumable_queue(roots)? else {
+            tracing::info!("📥 No interrupted downloads to resume");
+            return Ok(0);
+        };
+
+        let remaining = queue.pending.len();
+        tracing::info!("📥 Resuming {} pending downloads", remaining);
+
+        Self::download_tracks_segmented(
+            queue.pending,
+            BATCH_SIZE,
+            MIN_DELAY_SECS,
+            queue.output_template,
+            queue.format,
+            queue.output_dir,
+            Some(remaining),
+            app_handle,
+        )
+        .await?;
+
+        Ok(remaining)
+    }
+
+    /// Loads the persisted queue, checking its output folder like a new download's
+    ///
+    /// The queue file could have been edited, or the folder's root removed since;
+    /// such a queue is dropped rather than resumed.
+    fn load_resumable_queue(roots: &AllowedRoots) -> Result<Option<DownloadQueue>, AppError> {
+        let Some(queue) = DownloadQueue::load()? else {
+            return Ok(None);
+        };
+
+        if let Some(dir) = &queue.output_dir {
+            if let Err(e) = roots.check_output_dir(dir) {
+                tracing::warn!("⚠️ Dropping download queue for {}: {}", dir, e);
+                DownloadQueue::clear()?;
+                return Err(e);
+            }
+        }
+        Ok(Some(queue))
     }
 
     /// Downloads a single Spotify track with comprehensive validation and error handling
@@ -278,14 +723,17 @@ impl DownloadService {
         format: String,
         output_dir: Option<String>,
         app_handle: &AppHandle,
-    ) -> Result<String, AppError> {
+    ) -> Result<DownloadResult, AppError> {
         validate_spotify_url(&url)?;
         validate_download_format(&format)?;
+        let output_template = sanitize_output_template(&output_template)?;
 
         if let Some(ref dir) = output_dir {
-            validate_output_path(dir)?;
+            validate_writable(&validate_or_create_output_path(dir)?)?;
         }
 
+        Self::ensure_supported().await?;
+
         let song_name = extract_song_id(&url);
         let full_output_path = Self::build_output_path(&output_template, output_dir.as_deref());
 
@@ -301,15 +749,250 @@ impl DownloadService {
         cmd.arg("--audio").arg("youtube-music").arg("youtube");
         cmd.arg("--threads").arg("1"); // Conservative threading for single downloads
         cmd.arg("--print-errors");
+        Self::add_cookie_file(&mut cmd, app_handle);
 
         #[cfg(windows)]
         {
             cmd.creation_flags(0x08000000);
         }
 
-        let result = timeout(Duration::from_secs(300), cmd.output()).await;
+        Self::emit_started(app_handle, &url, 1, 1);
+        let result = Self::run_spotdl(cmd, 300, app_handle).await;
+
+        Self::handle_download_result(
+            result,
+            &song_name,
+            &url,
+            output_dir.as_deref(),
+            &format,
+            app_handle,
+        )
+        .await
+    }
+
+    /// Downloads a track picked from a Spotify listing (saved, top, search results...)
+    ///
+    /// Builds the canonical `open.spotify.com/track/<id>` URL and names the file
+    /// `Artist - Title.ext`. Local tracks (no id) are rejected.
+    #[instrument(skip_all, fields(track = %track.name, format = %format))]
+    pub async fn download_spotify_track(
+        track: &SpotifyTrack,
+        format: String,
+        output_dir: Option<String>,
+        app_handle: &AppHandle,
+    ) -> Result<DownloadResult, AppError> {
+        let url = Self::track_url(track)?;
+        Self::download_single_track(
+            url,
+            TRACK_OUTPUT_TEMPLATE.to_string(),
+            format,
+            output_dir,
+            app_handle,
+        )
+        .await
+    }
+
+    /// Downloads a whole album, letting spotdl expand it into its tracks
+    ///
+    /// Accepts an album URL, `spotify:album:` URI or raw id. Files are named
+    /// `Album Artist - Album/NN - Title.ext` unless `output_template` is given.
+    /// `download-progress` is emitted for each track as spotdl reports it, then
+    /// `download-finished`. Returns the number of tracks downloaded.
+    #[instrument(skip_all, fields(album = %album, format = %format))]
+    pub async fn download_spotify_album(
+        album: String,
+        output_template: Option<String>,
+        format: String,
+        output_dir: Option<String>,
+        app_handle: &AppHandle,
+    ) -> Result<usize, AppError> {
+        let url = spotify_album_url(&album)?;
+        validate_download_format(&format)?;
+        let output_template =
+            sanitize_output_template(output_template.as_deref().unwrap_or(ALBUM_OUTPUT_TEMPLATE))?;
+
+        if let Some(ref dir) = output_dir {
+            validate_writable(&validate_or_create_output_path(dir)?)?;
+        }
+
+        Self::ensure_supported().await?;
+
+        let mut cmd = Command::new("spotdl");
+        cmd.args(Self::album_args(
+            &url,
+            &output_template,
+            &format,
+            output_dir.as_deref(),
+        ));
+        Self::add_cookie_file(&mut cmd, app_handle);
+
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(0x08000000);
+        }
+
+        tracing::info!("📥 Downloading album {}", url);
+        let mut total = 0;
+        let mut downloaded = 0;
+        let result = Self::run_spotdl_streaming(cmd, ALBUM_TIMEOUT_SECS, app_handle, |line| {
+            if let Some(count) = Self::parse_found_count(line) {
+                total = count;
+                return;
+            }
+            let Some((name, target)) = Self::parse_downloaded_line(line) else {
+                return;
+            };
+
+            // Current spotdl prints the track URL, older releases the file path
+            let (track_url, output_path) = if target.starts_with("http") {
+                (target.to_string(), None)
+            } else {
+                (
+                    url.clone(),
+                    (!target.is_empty()).then(|| target.to_string()),
+                )
+            };
+            downloaded += 1;
+            DownloadHistory::record(name, &track_url, &format, true);
+            let _ = app_handle.emit(
+                events::DOWNLOAD_PROGRESS,
+                DownloadProgress {
+                    song: name.to_string(),
+                    index: downloaded,
+                    total: total.max(downloaded),
+                    status: "✅ Descargada".into(),
+                    url: track_url,
+                    output_path,
+                    eta_secs: None,
+                },
+            );
+        })
+        .await;
+
+        let failed = total.saturating_sub(downloaded);
+        let outcome = match result {
+            Ok(Ok(status)) if status.success() => Ok(downloaded),
+            Ok(Ok(status)) => Err(DownloadError::Failed(format!(
+                "spotdl exited with {}",
+                status
+            ))),
+            Ok(Err(e)) => Err(DownloadError::Failed(e.to_string())),
+            Err(_) => Err(DownloadError::Timeout(ALBUM_TIMEOUT_SECS)),
+        };
+
+        let _ = app_handle.emit(
+            events::DOWNLOAD_FINISHED,
+            DownloadFinished {
+                message: if outcome.is_ok() {
+                    "✅ Descarga completada".into()
+                } else {
+                    "❌ Error en descarga".into()
+                },
+                total_downloaded: downloaded,
+                total_failed: failed,
+            },
+        );
+
+        tracing::info!(
+            "📥 Album download finished: {} downloaded, {} failed",
+            downloaded,
+            failed
+        );
+        outcome.map_err(Into::into)
+    }
+
+    /// Checks downloaded files, returning one problem per bad file in input order
+    ///
+    /// A file passes when it exists, is not empty, has a supported audio format
+    /// and its audio stream parses. The UI can offer to download the rest again.
+    #[instrument(skip_all, fields(file_count = paths.len()))]
+    pub async fn verify_files(
+        paths: Vec<String>,
+        roots: Vec<PathBuf>,
+    ) -> Result<Vec<DownloadProblem>, AppError> {
+        tokio::task::spawn_blocking(move || {
+            paths
+                .iter()
+                .filter_map(|path| Self::verify_file(path, &roots))
+                .collect()
+        })
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+    }
+
+    /// First problem found with one downloaded file, if any
+    fn verify_file(path: &str, roots: &[PathBuf]) -> Option<DownloadProblem> {
+        let problem = |kind, message: String| {
+            tracing::warn!("📥 Bad download {}: {}", path, message);
+            Some(DownloadProblem {
+                path: path.to_string(),
+                kind,
+                message,
+            })
+        };
+
+        let file = match validate_within_roots(path, roots) {
+            Ok(file) if file.is_file() => file,
+            Ok(_) => return problem(DownloadProblemKind::Missing, "Not a file".into()),
+            Err(e) => return problem(DownloadProblemKind::Missing, e.to_string()),
+        };
+        match std::fs::metadata(&file) {
+            Ok(metadata) if metadata.len() == 0 => {
+                return problem(DownloadProblemKind::Empty, "File is empty".into());
+            }
+            Err(e) => return problem(DownloadProblemKind::Missing, e.to_string()),
+            Ok(_) => {}
+        }
+        if let Err(e) = FileService::get_audio_metadata(path) {
+            return problem(DownloadProblemKind::NotAudio, e.to_string());
+        }
+        if let Err(e) = FileService::probe_audio(&file) {
+            return problem(DownloadProblemKind::Corrupt, e.to_string());
+        }
+        None
+    }
+
+    /// Builds the spotdl arguments for downloading the album at `url`
+    fn album_args(
+        url: &str,
+        output_template: &str,
+        format: &str,
+        output_dir: Option<&str>,
+    ) -> Vec<String> {
+        let mut args = vec!["download".to_string(), url.to_string()];
+        if let Some(path) = Self::build_output_path(output_template, output_dir) {
+            args.extend(["--output".to_string(), path]);
+        }
+        args.extend(
+            [
+                "--format",
+                format,
+                "--audio",
+                "youtube-music",
+                "youtube",
+                "--threads",
+                "4",
+                "--print-errors",
+            ]
+            .into_iter()
+            .map(String::from),
+        );
+        args
+    }
+
+    /// Reads the track count from spotdl's `Found <n> songs in <album>` line
+    fn parse_found_count(line: &str) -> Option<usize> {
+        let (count, _) = line.trim().strip_prefix("Found ")?.split_once(" song")?;
+        count.parse().ok()
+    }
 
-        Self::handle_download_result(result, &song_name, &url, app_handle).await
+    /// Builds the canonical track URL from a track's id (raw id or `spotify:track:` URI)
+    fn track_url(track: &SpotifyTrack) -> Result<String, AppError> {
+        let id = track
+            .id
+            .as_deref()
+            .ok_or_else(|| DownloadError::MissingTrackId(track.name.clone()))?;
+        spotify_track_url(id)
     }
 
     /// Builds the output path from template and directory
@@ -322,13 +1005,104 @@ impl DownloadService {
         }
     }
 
+    /// Passes the cookies file from the settings to spotdl, if one is set
+    fn add_cookie_file(cmd: &mut Command, app_handle: &AppHandle) {
+        let cookie_file = app_handle
+            .state::<SettingsState>()
+            .get()
+            .ok()
+            .and_then(|settings| settings.cookie_file);
+        if let Some(path) = cookie_file {
+            cmd.arg("--cookie-file").arg(path);
+        }
+    }
 
+    /// Emits `download-started` so the UI can show the in-flight song
+    fn emit_started(app_handle: &AppHandle, url: &str, index: usize, total: usize) {
+        let _ = app_handle.emit(
+            events::DOWNLOAD_STARTED,
+            DownloadStarted {
+                song: extract_song_id(url),
+                index,
+                total,
+                url: url.to_string(),
+            },
+        );
+    }
+
+    /// Runs a spotdl command, killing it on timeout or when the app shuts down
+    ///
+    /// A shutdown is reported as an `Interrupted` I/O error so callers treat it like
+    /// any other failed run.
+    async fn run_spotdl(
+        mut cmd: Command,
+        timeout_secs: u64,
+        app_handle: &AppHandle,
+    ) -> Result<Result<std::process::Output, std::io::Error>, tokio::time::error::Elapsed> {
+        // Dropping the output future drops the child, which must not outlive us
+        cmd.kill_on_drop(true);
+
+        let shutdown = app_handle.state::<ShutdownState>();
+        let cancelled = shutdown.cancellation_token();
+        let _guard = shutdown.track_process();
+
+        tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => Ok(Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "cancelled: app is shutting down",
+            ))),
+            result = timeout(Duration::from_secs(timeout_secs), cmd.output()) => result,
+        }
+    }
+
+    /// Like [`Self::run_spotdl`], but hands each stdout line to `on_line` as spotdl
+    /// prints it instead of collecting the output
+    async fn run_spotdl_streaming(
+        mut cmd: Command,
+        timeout_secs: u64,
+        app_handle: &AppHandle,
+        mut on_line: impl FnMut(&str),
+    ) -> Result<Result<ExitStatus, std::io::Error>, tokio::time::error::Elapsed> {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        let shutdown = app_handle.state::<ShutdownState>();
+        let cancelled = shutdown.cancellation_token();
+        let _guard = shutdown.track_process();
+
+        let run = async {
+            let mut child = cmd.spawn()?;
+            if let Some(stdout) = child.stdout.take() {
+                let mut lines = BufReader::new(stdout).split(b'\n');
+                while let Some(line) = lines.next_segment().await? {
+                    on_line(&String::from_utf8_lossy(&line));
+                }
+            }
+            child.wait().await
+        };
+
+        tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => Ok(Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "cancelled: app is shutting down",
+            ))),
+            result = timeout(Duration::from_secs(timeout_secs), run) => result,
+        }
+    }
 
     /// Processes the download command output and returns status message
+    ///
+    /// On success the file spotdl wrote is returned as well, when it can be determined.
     fn process_download_output(
         result: Result<Result<std::process::Output, std::io::Error>, tokio::time::error::Elapsed>,
         song_name: &str,
-    ) -> Result<String, AppError> {
+        output_dir: Option<&str>,
+        format: &str,
+    ) -> Result<(String, Option<String>), AppError> {
         tracing::debug!("🔍 Procesando resultado de descarga para: {}", song_name);
         
         match result {
@@ -346,8 +1120,13 @@ impl DownloadService {
                     tracing::warn!("📥 YouTube error for {}: Update yt-dlp", song_name);
                     return Err(DownloadError::YouTubeError.into());
                 }
-                tracing::info!("📥 Successfully downloaded: {}", song_name);
-                Ok("✅ Descargada".to_string())
+                let output_path = Self::parse_output_path(&stdout, output_dir, format);
+                tracing::info!(
+                    "📥 Successfully downloaded: {} ({})",
+                    song_name,
+                    output_path.as_deref().unwrap_or("path unknown")
+                );
+                Ok(("✅ Descargada".to_string(), output_path))
             }
             Ok(Ok(output)) => {
                 let stderr = String::from_utf8_lossy(&output.stderr);
@@ -365,11 +1144,11 @@ impl DownloadService {
                     .take(100)
                     .collect::<String>();
                 tracing::error!("📥 Download failed for {}: {}", song_name, error_msg);
-                Ok(format!("❌ {}", error_msg))
+                Ok((format!("❌ {}", error_msg), None))
             }
             Ok(Err(e)) => {
                 tracing::error!("📥 Command execution error for {}: {}", song_name, e);
-                Ok(format!("⚠️ Error: {}", e))
+                Ok((format!("⚠️ Error: {}", e), None))
             }
             Err(_) => {
                 tracing::error!(
@@ -377,46 +1156,85 @@ impl DownloadService {
                     song_name,
                     SPOTDL_TIMEOUT_SECS
                 );
-                Ok("⏱️ Timeout (>5min)".to_string())
+                Ok(("⏱️ Timeout (>5min)".to_string(), None))
             }
         }
     }
 
+    /// Finds the file spotdl wrote from its `Downloaded "<name>": <target>` line
+    ///
+    /// Older spotdl releases print the file path as the target and it is used as is.
+    /// Current ones print the source URL, so the file is looked up as
+    /// `<output_dir>/<name>.<format>`, which matches spotdl's default naming.
+    /// Returns `None` when neither works (e.g. a template with subfolders).
+    fn parse_output_path(stdout: &str, output_dir: Option<&str>, format: &str) -> Option<String> {
+        let (name, target) = stdout.lines().find_map(Self::parse_downloaded_line)?;
+
+        if !target.is_empty() && !target.starts_with("http") {
+            return Some(target.to_string());
+        }
+
+        let candidate = std::path::Path::new(output_dir?).join(format!("{}.{}", name, format));
+        candidate
+            .is_file()
+            .then(|| candidate.to_string_lossy().into_owned())
+    }
+
+    /// Splits a `Downloaded "<name>": <target>` line into its name and target
+    fn parse_downloaded_line(line: &str) -> Option<(&str, &str)> {
+        let (name, target) = line
+            .trim()
+            .strip_prefix("Downloaded \"")?
+            .rsplit_once("\": ")?;
+        Some((name, target.trim()))
+    }
+
     /// Handles download result for single track download
     async fn handle_download_result(
         result: Result<Result<std::process::Output, std::io::Error>, tokio::time::error::Elapsed>,
         song_name: &str,
         url: &str,
+        output_dir: Option<&str>,
+        format: &str,
         app_handle: &AppHandle,
-    ) -> Result<String, AppError> {
-        match Self::process_download_output(result, song_name) {
-            Ok(status) => {
+    ) -> Result<DownloadResult, AppError> {
+        match Self::process_download_output(result, song_name, output_dir, format) {
+            Ok((status, output_path)) => {
+                DownloadHistory::record(song_name, url, format, status.starts_with("✅"));
                 let _ = app_handle.emit(
-                    "download-progress",
+                    events::DOWNLOAD_PROGRESS,
                     DownloadProgress {
                         song: song_name.to_string(),
                         index: 1,
                         total: 1,
                         status: status.clone(),
                         url: url.to_string(),
+                        output_path: output_path.clone(),
+                        eta_secs: None,
                     },
                 );
 
                 if status.starts_with("✅") {
-                    Ok(format!("✅ {} descargada correctamente", song_name))
+                    Ok(DownloadResult {
+                        message: format!("✅ {} descargada correctamente", song_name),
+                        output_path,
+                    })
                 } else {
                     Err(DownloadError::Failed(status).into())
                 }
             }
             Err(e) => {
+                DownloadHistory::record(song_name, url, format, false);
                 let _ = app_handle.emit(
-                    "download-progress",
+                    events::DOWNLOAD_PROGRESS,
                     DownloadProgress {
                         song: song_name.to_string(),
                         index: 1,
                         total: 1,
                         status: "⚠️ Error de YouTube".to_string(),
                         url: url.to_string(),
+                        output_path: None,
+                        eta_secs: None,
                     },
                 );
                 Err(e)
@@ -424,3 +1242,650 @@ impl DownloadService {
         }
     }
 }
+
+/// Stops a download run once too many batches fail in a row
+///
+/// Every batch failing usually means spotdl or yt-dlp broke (e.g. after a YouTube
+/// change), so grinding through the rest would only waste time.
+struct FailureBreaker {
+    consecutive: AtomicUsize,
+    limit: usize,
+}
+
+impl FailureBreaker {
+    fn new(limit: usize) -> Self {
+        Self {
+            consecutive: AtomicUsize::new(0),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Records a finished batch; returns true for the failure that reaches the limit
+    ///
+    /// Any success resets the count.
+    fn record(&self, success: bool) -> bool {
+        if success {
+            self.consecutive.store(0, Ordering::SeqCst);
+            false
+        } else {
+            self.consecutive.fetch_add(1, Ordering::SeqCst) + 1 == self.limit
+        }
+    }
+}
+
+/// Estimates how long a download run has left from recent batch durations
+///
+/// Durations are measured from when a batch gets its permit, so time spent
+/// waiting for a free slot doesn't inflate the average. The first batch to
+/// finish pays for spotdl's start-up and the ramp-up to full concurrency, so it
+/// isn't sampled.
+struct EtaTracker {
+    /// Songs in the run
+    total: usize,
+    /// Songs whose batch hasn't finished
+    remaining: usize,
+    /// Batches running at the same time
+    concurrency: usize,
+    /// Seconds per song of the most recent batches
+    samples: VecDeque<f32>,
+    /// Whether the warm-up batch has finished
+    warmed_up: bool,
+}
+
+impl EtaTracker {
+    /// Batches averaged for the estimate
+    const WINDOW: usize = 5;
+
+    fn new(total: usize, concurrency: usize) -> Self {
+        Self {
+            total,
+            remaining: total,
+            concurrency: concurrency.max(1),
+            samples: VecDeque::with_capacity(Self::WINDOW),
+            warmed_up: false,
+        }
+    }
+
+    /// Records a finished batch of `songs` and returns the new estimate
+    ///
+    /// Returns `None` until a batch after the warm-up one has finished.
+    fn record(&mut self, songs: usize, elapsed: Duration) -> Option<f32> {
+        self.remaining = self.remaining.saturating_sub(songs);
+        if !self.warmed_up {
+            self.warmed_up = true;
+        } else if songs > 0 {
+            if self.samples.len() == Self::WINDOW {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(elapsed.as_secs_f32() / songs as f32);
+        }
+        self.estimate()
+    }
+
+    /// Average seconds per song × songs left ÷ batches running in parallel
+    fn estimate(&self) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let average = self.samples.iter().sum::<f32>() / self.samples.len() as f32;
+        Some(average * self.remaining as f32 / self.concurrency as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_spotdl_versions() {
+        assert_eq!(
+            DownloadService::parse_version("4.2.5"),
+            Some(((4, 2, 5), false))
+        );
+        assert_eq!(
+            DownloadService::parse_version("spotdl v4.3.0-beta.1\n"),
+            Some(((4, 3, 0), true))
+        );
+        assert_eq!(
+            DownloadService::parse_version("4.2.0+build.7"),
+            Some(((4, 2, 0), false))
+        );
+        assert_eq!(
+            DownloadService::parse_version("5"),
+            Some(((5, 0, 0), false))
+        );
+        assert_eq!(DownloadService::parse_version("spotdl"), None);
+        assert_eq!(DownloadService::parse_version("4.x.1"), None);
+    }
+
+    #[test]
+    fn compares_against_the_minimum_version() {
+        assert!(DownloadService::meets_minimum(((4, 2, 0), false)));
+        assert!(DownloadService::meets_minimum(((4, 10, 0), false)));
+        assert!(DownloadService::meets_minimum(((4, 2, 1), true)));
+        assert!(DownloadService::meets_minimum(((5, 0, 0), true)));
+        assert!(!DownloadService::meets_minimum(((4, 2, 0), true)));
+        assert!(!DownloadService::meets_minimum(((4, 1, 9), false)));
+        assert!(!DownloadService::meets_minimum(((3, 9, 9), false)));
+    }
+
+    #[test]
+    fn breaker_trips_after_consecutive_failed_batches() {
+        let breaker = FailureBreaker::new(5);
+        let tripped: Vec<bool> = (0..5).map(|_| breaker.record(false)).collect();
+        assert_eq!(tripped, [false, false, false, false, true]);
+        // Only the failure reaching the limit reports the trip
+        assert!(!breaker.record(false));
+    }
+
+    #[test]
+    fn breaker_resets_on_success() {
+        let breaker = FailureBreaker::new(5);
+        for _ in 0..10 {
+            for _ in 0..4 {
+                assert!(!breaker.record(false));
+            }
+            assert!(!breaker.record(true));
+        }
+    }
+
+    fn batch_urls(batch: usize) -> Vec<String> {
+        (0..BATCH_SIZE)
+            .map(|i| format!("https://open.spotify.com/track/{}x{}", batch, i))
+            .collect()
+    }
+
+    #[test]
+    fn full_failed_batch_counts_once() {
+        let urls = batch_urls(0);
+        let mut queue = DownloadQueue::new(&urls, "", "mp3", None);
+        let breaker = FailureBreaker::new(5);
+        let (mut downloaded, mut failed, mut skipped) = (0, 0, 0);
+
+        let failure = DownloadError::Failed("batch".to_string()).into();
+        let tripped = DownloadService::record_batch(
+            Ok((urls.clone(), Some(Err(failure)))),
+            &mut queue,
+            &breaker,
+            &mut downloaded,
+            &mut failed,
+            &mut skipped,
+        );
+
+        assert!(!tripped);
+        assert_eq!((downloaded, failed, skipped), (0, BATCH_SIZE, 0));
+        assert_eq!(breaker.consecutive.load(Ordering::SeqCst), 1);
+        assert_eq!(queue.pending, urls);
+    }
+
+    #[test]
+    fn interleaved_successful_batches_keep_the_breaker_closed() {
+        let all: Vec<String> = (0..20).flat_map(batch_urls).collect();
+        let mut queue = DownloadQueue::new(&all, "", "mp3", None);
+        let breaker = FailureBreaker::new(5);
+        let (mut downloaded, mut failed, mut skipped) = (0, 0, 0);
+
+        for batch in 0..20 {
+            // Four failures, then a success, over and over
+            let result = if batch % 5 == 4 {
+                Ok(())
+            } else {
+                Err(DownloadError::Failed("batch".to_string()).into())
+            };
+            let tripped = DownloadService::record_batch(
+                Ok((batch_urls(batch), Some(result))),
+                &mut queue,
+                &breaker,
+                &mut downloaded,
+                &mut failed,
+                &mut skipped,
+            );
+            assert!(!tripped, "tripped at batch {}", batch);
+        }
+
+        assert_eq!(downloaded, 4 * BATCH_SIZE);
+        assert_eq!(failed, 16 * BATCH_SIZE);
+        assert_eq!(queue.pending.len(), 16 * BATCH_SIZE);
+    }
+
+    #[tokio::test]
+    async fn panicked_batch_counts_its_own_songs_and_stays_queued() {
+        let all: Vec<String> = (0..14)
+            .map(|i| format!("https://open.spotify.com/track/{}", i))
+            .collect();
+        let mut queue = DownloadQueue::new(&all, "", "mp3", None);
+        let breaker = FailureBreaker::new(5);
+        let (mut downloaded, mut failed, mut skipped) = (0, 0, 0);
+
+        // Last, short batch of a 14-song run
+        let panicked = tokio::spawn(async { panic!("spotdl task panicked") })
+            .await
+            .unwrap_err();
+        let tripped = DownloadService::record_batch(
+            Err((2, panicked)),
+            &mut queue,
+            &breaker,
+            &mut downloaded,
+            &mut failed,
+            &mut skipped,
+        );
+
+        assert!(!tripped);
+        assert_eq!((downloaded, failed, skipped), (0, 2, 0));
+        assert_eq!(queue.pending, all);
+    }
+
+    #[test]
+    fn eta_skips_the_warm_up_batch() {
+        let mut eta = EtaTracker::new(48, 2);
+        assert_eq!(eta.record(12, Duration::from_secs(120)), None);
+        // 2 s/song × 24 songs left ÷ 2 batches in parallel
+        assert_eq!(eta.record(12, Duration::from_secs(24)), Some(24.0));
+    }
+
+    #[test]
+    fn eta_averages_recent_batches() {
+        let mut eta = EtaTracker::new(100, 2);
+        eta.record(10, Duration::from_secs(100));
+        assert_eq!(eta.record(10, Duration::from_secs(20)), Some(80.0));
+        // (2 + 4) / 2 = 3 s/song × 70 left ÷ 2
+        assert_eq!(eta.record(10, Duration::from_secs(40)), Some(105.0));
+
+        // Only the last WINDOW batches count: five at 1 s/song push out the rest
+        for _ in 0..EtaTracker::WINDOW {
+            eta.record(2, Duration::from_secs(2));
+        }
+        assert_eq!(eta.estimate(), Some(30.0));
+    }
+
+    #[test]
+    fn eta_reaches_zero_when_done() {
+        let mut eta = EtaTracker::new(20, 1);
+        eta.record(10, Duration::from_secs(50));
+        assert_eq!(eta.record(10, Duration::from_secs(10)), Some(0.0));
+    }
+
+    #[test]
+    fn max_songs_defaults_and_is_capped() {
+        assert_eq!(DownloadService::max_songs_limit(None), MAX_SONGS_PER_BATCH);
+        assert_eq!(DownloadService::max_songs_limit(Some(20)), 20);
+        assert_eq!(
+            DownloadService::max_songs_limit(Some(MAX_SONGS_CEILING + 1)),
+            MAX_SONGS_CEILING
+        );
+    }
+
+    #[test]
+    fn batches_over_the_limit_are_rejected() {
+        let max_songs = DownloadService::max_songs_limit(Some(20));
+
+        assert!(validate_batch_size(max_songs, max_songs).is_ok());
+        assert!(matches!(
+            validate_batch_size(max_songs + 1, max_songs),
+            Err(AppError::Download(DownloadError::TooManySongs(20)))
+        ));
+    }
+
+    #[test]
+    fn overrides_above_the_ceiling_are_clamped() {
+        let max_songs = DownloadService::max_songs_limit(Some(1000));
+        assert_eq!(max_songs, 500);
+
+        assert!(validate_batch_size(500, max_songs).is_ok());
+        assert!(matches!(
+            validate_batch_size(501, max_songs),
+            Err(AppError::Download(DownloadError::TooManySongs(500)))
+        ));
+    }
+
+    #[test]
+    fn thread_budget_is_split_between_processes() {
+        assert_eq!(DownloadService::threads_per_process(1), 8);
+        assert_eq!(DownloadService::threads_per_process(4), 2);
+        assert_eq!(DownloadService::threads_per_process(3), 2);
+        assert_eq!(DownloadService::threads_per_process(8), 1);
+        assert_eq!(DownloadService::threads_per_process(0), 8);
+        for max_concurrent in 1..=8 {
+            let threads = DownloadService::threads_per_process(max_concurrent);
+            assert!(threads * max_concurrent <= SPOTDL_THREAD_BUDGET);
+        }
+    }
+
+    #[tokio::test]
+    async fn in_flight_batches_never_exceed_the_limit() {
+        let limit = 3;
+        let permits = Arc::new(Semaphore::new(limit));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..20)
+            .map(|_| {
+                let permits = Arc::clone(&permits);
+                let in_flight = Arc::clone(&in_flight);
+                let peak = Arc::clone(&peak);
+                tokio::spawn(async move {
+                    DownloadService::with_permit(&permits, async {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                    })
+                    .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert!(task.await.unwrap().is_some());
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), limit);
+    }
+
+    #[tokio::test]
+    async fn closed_semaphore_skips_waiting_batches() {
+        let permits = Arc::new(Semaphore::new(1));
+        permits.close();
+        assert_eq!(
+            DownloadService::with_permit(&permits, async { 1 }).await,
+            None
+        );
+    }
+
+    #[test]
+    fn batch_delay_stays_within_jitter_and_bounds() {
+        let min = Duration::from_secs(MIN_DELAY_SECS);
+        let max = Duration::from_secs(MAX_DELAY_SECS);
+        let mut jitter = Jitter::new(7);
+
+        for _ in 0..1_000 {
+            let delay = DownloadService::batch_delay(&mut jitter, 4);
+            assert!(delay >= Duration::from_secs(3) && delay <= Duration::from_secs(5));
+
+            // Out-of-range settings are clamped before and after the jitter
+            for base_secs in [0, MIN_DELAY_SECS, MAX_DELAY_SECS, 60] {
+                let delay = DownloadService::batch_delay(&mut jitter, base_secs);
+                assert!(
+                    delay >= min && delay <= max,
+                    "{:?} from {}",
+                    delay,
+                    base_secs
+                );
+            }
+        }
+
+        let delays: Vec<Duration> = (0..10)
+            .map(|_| DownloadService::batch_delay(&mut jitter, 4))
+            .collect();
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[tokio::test]
+    async fn verification_flags_empty_corrupt_and_non_audio_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_string_lossy().into_owned();
+        crate::test_support::write_silent_mp3(&dir.path().join("good.mp3"));
+        std::fs::write(path("empty.mp3"), b"").unwrap();
+        std::fs::write(path("notes.txt"), b"not a song").unwrap();
+        std::fs::write(path("truncated.mp3"), b"ID3 cut off mid-download").unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let stray = outside.path().join("song.mp3");
+        crate::test_support::write_silent_mp3(&stray);
+        let stray = stray.to_string_lossy().into_owned();
+        let roots = vec![crate::utils::canonical_path(dir.path()).unwrap()];
+
+        let problems = DownloadService::verify_files(
+            vec![
+                path("good.mp3"),
+                path("empty.mp3"),
+                path("notes.txt"),
+                path("truncated.mp3"),
+                path("missing.mp3"),
+                stray.clone(),
+            ],
+            roots,
+        )
+        .await
+        .unwrap();
+
+        let found: Vec<_> = problems
+            .iter()
+            .map(|problem| (problem.path.clone(), problem.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (path("empty.mp3"), DownloadProblemKind::Empty),
+                (path("notes.txt"), DownloadProblemKind::NotAudio),
+                (path("truncated.mp3"), DownloadProblemKind::Corrupt),
+                (path("missing.mp3"), DownloadProblemKind::Missing),
+                (stray, DownloadProblemKind::Missing),
+            ]
+        );
+    }
+
+    /// stdout of a spotdl 4.2 single-track download
+    const SPOTDL_STDOUT: &str = "\
+Processing query: https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC
+Downloaded \"Rick Astley - Never Gonna Give You Up\": https://music.youtube.com/watch?v=lYBUbBu4W08
+";
+
+    #[test]
+    fn downloaded_lines_are_split_into_name_and_target() {
+        let line = SPOTDL_STDOUT.lines().nth(1).unwrap();
+        assert_eq!(
+            DownloadService::parse_downloaded_line(line),
+            Some((
+                "Rick Astley - Never Gonna Give You Up",
+                "https://music.youtube.com/watch?v=lYBUbBu4W08"
+            ))
+        );
+        // Quotes inside the name are kept
+        assert_eq!(
+            DownloadService::parse_downloaded_line(
+                "  Downloaded \"Queen - \"39\" (Live)\": /music/39.mp3 \r"
+            ),
+            Some(("Queen - \"39\" (Live)", "/music/39.mp3"))
+        );
+        for line in [
+            SPOTDL_STDOUT.lines().next().unwrap(),
+            "Skipping Rick Astley - Never Gonna Give You Up (file already exists)",
+            "Downloaded Rick Astley",
+        ] {
+            assert_eq!(DownloadService::parse_downloaded_line(line), None);
+        }
+    }
+
+    #[test]
+    fn output_path_comes_from_the_target_or_the_default_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().to_string_lossy().into_owned();
+        let parse = |stdout: &str, format| {
+            DownloadService::parse_output_path(stdout, Some(&output_dir), format)
+        };
+
+        // Current spotdl prints the source URL, so the file is looked up by name
+        let expected = dir.path().join("Rick Astley - Never Gonna Give You Up.mp3");
+        assert_eq!(parse(SPOTDL_STDOUT, "mp3"), None);
+        std::fs::write(&expected, b"").unwrap();
+        assert_eq!(
+            parse(SPOTDL_STDOUT, "mp3"),
+            Some(expected.to_string_lossy().into_owned())
+        );
+        assert_eq!(parse(SPOTDL_STDOUT, "flac"), None);
+        assert_eq!(
+            DownloadService::parse_output_path(SPOTDL_STDOUT, None, "mp3"),
+            None
+        );
+
+        // Older releases print the written file
+        assert_eq!(
+            parse("Downloaded \"A - B\": /music/A - B.flac\n", "mp3").as_deref(),
+            Some("/music/A - B.flac")
+        );
+        assert_eq!(parse("Nothing to download\n", "mp3"), None);
+    }
+
+    /// Events of one reported batch as `(event, url, status)`
+    async fn batch_events(urls: &[String], exit_ok: bool) -> Vec<(&'static str, String, String)> {
+        let eta = Mutex::new(EtaTracker::new(urls.len(), 1));
+        let run = async move {
+            if exit_ok {
+                Ok(Ok(std::process::Output {
+                    status: ExitStatus::default(),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                }))
+            } else {
+                Ok(Err(std::io::Error::other("spotdl crashed")))
+            }
+        };
+
+        let mut events = Vec::new();
+        let result = DownloadService::report_batch(urls, 1, urls.len(), &eta, run, |event| {
+            events.push(match event {
+                BatchEvent::Started(started) => ("started", started.url, String::new()),
+                BatchEvent::Finished(progress, success) => {
+                    assert_eq!(success, exit_ok);
+                    ("finished", progress.url, progress.status)
+                }
+            })
+        })
+        .await;
+        assert_eq!(result.is_ok(), exit_ok);
+        events
+    }
+
+    #[tokio::test]
+    async fn each_song_starts_before_it_finishes() {
+        let urls = batch_urls(0);
+
+        for exit_ok in [true, false] {
+            let events = batch_events(&urls, exit_ok).await;
+            assert_eq!(events.len(), urls.len() * 2);
+            for url in &urls {
+                let position = |kind| {
+                    events
+                        .iter()
+                        .position(|(event, event_url, _)| *event == kind && event_url == url)
+                        .unwrap()
+                };
+                assert!(position("started") < position("finished"), "{}", url);
+            }
+            let expected = if exit_ok { "✅" } else { "❌" };
+            assert!(events
+                .iter()
+                .filter(|(event, _, _)| *event == "finished")
+                .all(|(_, _, status)| status.starts_with(expected)));
+        }
+    }
+
+    #[test]
+    fn album_args_place_the_output_before_the_options() {
+        let url = "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy";
+        let args = DownloadService::album_args(url, "{title}.{output-ext}", "flac", Some("/music"));
+        assert_eq!(
+            args,
+            [
+                "download",
+                url,
+                "--output",
+                "/music/{title}.{output-ext}",
+                "--format",
+                "flac",
+                "--audio",
+                "youtube-music",
+                "youtube",
+                "--threads",
+                "4",
+                "--print-errors",
+            ]
+        );
+
+        // Without a directory or template spotdl picks its own output
+        let args = DownloadService::album_args(url, "", "mp3", None);
+        assert!(!args.contains(&"--output".to_string()));
+        let args = DownloadService::album_args(url, "", "mp3", Some("/music"));
+        assert_eq!(args[2..4], ["--output", "/music"]);
+    }
+
+    #[test]
+    fn found_count_is_read_from_spotdl_output() {
+        assert_eq!(
+            DownloadService::parse_found_count("Found 12 songs in Currents (album)"),
+            Some(12)
+        );
+        assert_eq!(
+            DownloadService::parse_found_count("  Found 1 song in Single\n"),
+            Some(1)
+        );
+        for line in [
+            "Found many songs in Currents",
+            "Downloaded \"Found 3 songs\": https://music.youtube.com/watch?v=x",
+            "Processing query: https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy",
+        ] {
+            assert_eq!(DownloadService::parse_found_count(line), None, "{}", line);
+        }
+    }
+
+    fn track_with_id(id: Option<&str>) -> SpotifyTrack {
+        SpotifyTrack {
+            id: id.map(ToString::to_string),
+            name: "Local File".to_string(),
+            artists: vec!["Artist".to_string()],
+            album: "Album".to_string(),
+            album_image: None,
+            duration_ms: 180_000,
+            popularity: None,
+            preview_url: None,
+            external_url: None,
+        }
+    }
+
+    #[test]
+    fn track_url_needs_an_id() {
+        let expected = "https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC";
+        for id in [
+            "4uLU6hMCjMI75M1A2tKUQC",
+            "spotify:track:4uLU6hMCjMI75M1A2tKUQC",
+        ] {
+            assert_eq!(
+                DownloadService::track_url(&track_with_id(Some(id))).unwrap(),
+                expected
+            );
+        }
+
+        assert!(matches!(
+            DownloadService::track_url(&track_with_id(None)),
+            Err(AppError::Download(DownloadError::MissingTrackId(name))) if name == "Local File"
+        ));
+        assert!(DownloadService::track_url(&track_with_id(Some(
+            "spotify:album:4uLU6hMCjMI75M1A2tKUQC"
+        )))
+        .is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn queue_outside_the_allowed_roots_is_dropped() {
+        let home = crate::test_support::FakeHome::new();
+        let music = home.mkdir("Music");
+        let outside = tempfile::tempdir().unwrap();
+        let urls = vec!["https://open.spotify.com/track/abc".to_string()];
+        let roots = AllowedRoots::load();
+
+        let inside = music.join("downloads");
+        DownloadQueue::new(&urls, "", "mp3", inside.to_str())
+            .save()
+            .unwrap();
+        let queue = DownloadService::load_resumable_queue(&roots)
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.pending, urls);
+
+        DownloadQueue::new(&urls, "", "mp3", outside.path().to_str())
+            .save()
+            .unwrap();
+        assert!(DownloadService::load_resumable_queue(&roots).is_err());
+        assert!(DownloadQueue::load().unwrap().is_none());
+    }
+}
@@ -0,0 +1,259 @@
+//! Persistent download history
+//!
+//! Appends one JSON record per finished track to `download_history.jsonl` in the
+//! app data dir, so users can see what they downloaded over time. The file is
+//! capped at [`MAX_HISTORY_BYTES`]; past that the oldest entries are dropped.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, DownloadError};
+use crate::utils::get_app_data_dir;
+
+/// Serializes appends from concurrent batch tasks
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Size past which the history is trimmed to its newest half (several thousand entries)
+const MAX_HISTORY_BYTES: u64 = 1024 * 1024;
+
+/// How much of the file is read at a time, from the end, by `read_recent`
+const TAIL_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// One finished download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadHistoryEntry {
+    /// Song name or identifier
+    pub song: String,
+    /// Spotify URL that was downloaded
+    pub url: String,
+    /// Audio format requested
+    pub format: String,
+    /// Unix timestamp (seconds) when the download finished
+    pub timestamp: u64,
+    /// Whether spotdl reported success
+    pub success: bool,
+}
+
+impl DownloadHistoryEntry {
+    /// Creates an entry stamped with the current time
+    pub fn now(song: &str, url: &str, format: &str, success: bool) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            song: song.to_string(),
+            url: url.to_string(),
+            format: format.to_string(),
+            timestamp,
+            success,
+        }
+    }
+}
+
+/// Reads and writes the download history file
+pub struct DownloadHistory;
+
+impl DownloadHistory {
+    /// Location of the history file
+    fn file_path() -> PathBuf {
+        get_app_data_dir().join("download_history.jsonl")
+    }
+
+    /// Appends an entry to the history file
+    pub fn append(entry: &DownloadHistoryEntry) -> Result<(), AppError> {
+        Self::append_to(&Self::file_path(), entry)
+    }
+
+    fn append_to(path: &Path, entry: &DownloadHistoryEntry) -> Result<(), AppError> {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| DownloadError::HistoryPersistence(e.to_string()))?;
+
+        let _lock = HISTORY_LOCK
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("History lock poisoned: {}", e)))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)?;
+
+        if file.metadata()?.len() > MAX_HISTORY_BYTES {
+            drop(file);
+            Self::trim(path)?;
+        }
+        Ok(())
+    }
+
+    /// Keeps only the newest half of the file, cut at a line boundary
+    fn trim(path: &Path) -> Result<(), AppError> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let keep_from = len.saturating_sub(MAX_HISTORY_BYTES / 2);
+
+        let mut kept = Vec::new();
+        file.seek(SeekFrom::Start(keep_from))?;
+        file.read_to_end(&mut kept)?;
+        let kept = complete_lines(&kept, keep_from == 0);
+
+        // Write then rename so a crash mid-write never loses the whole history
+        let tmp = path.with_extension("jsonl.tmp");
+        fs::write(&tmp, kept)?;
+        fs::rename(&tmp, path)?;
+
+        tracing::info!("📜 Download history trimmed to {} bytes", kept.len());
+        Ok(())
+    }
+
+    /// Records an entry, logging instead of failing so downloads are not affected
+    pub fn record(song: &str, url: &str, format: &str, success: bool) {
+        let entry = DownloadHistoryEntry::now(song, url, format, success);
+        if let Err(e) = Self::append(&entry) {
+            tracing::warn!("⚠️ Could not write download history: {}", e);
+        }
+    }
+
+    /// Reads the most recent `limit` entries, newest first
+    ///
+    /// Only the end of the file is read, a chunk at a time until there are enough
+    /// entries. Lines that fail to parse (e.g. a write cut short by a crash) are
+    /// skipped.
+    pub fn read_recent(limit: usize) -> Result<Vec<DownloadHistoryEntry>, AppError> {
+        Self::read_recent_from(&Self::file_path(), limit)
+    }
+
+    fn read_recent_from(path: &Path, limit: usize) -> Result<Vec<DownloadHistoryEntry>, AppError> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut start = file.metadata()?.len();
+        let mut tail = Vec::new();
+        loop {
+            let chunk_start = start.saturating_sub(TAIL_CHUNK_BYTES);
+            let mut chunk = vec![0; (start - chunk_start) as usize];
+            file.seek(SeekFrom::Start(chunk_start))?;
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&tail);
+            tail = chunk;
+            start = chunk_start;
+
+            let (entries, skipped) = parse_newest(complete_lines(&tail, start == 0), limit);
+            if entries.len() >= limit || start == 0 {
+                if skipped > 0 {
+                    tracing::warn!("⚠️ Skipped {} corrupt download history lines", skipped);
+                }
+                return Ok(entries);
+            }
+        }
+    }
+
+    /// Deletes the history file
+    pub fn clear() -> Result<(), AppError> {
+        let _lock = HISTORY_LOCK
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("History lock poisoned: {}", e)))?;
+
+        match fs::remove_file(Self::file_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Drops the first, possibly partial, line unless `bytes` starts at the file start
+fn complete_lines(bytes: &[u8], at_file_start: bool) -> &[u8] {
+    if at_file_start {
+        return bytes;
+    }
+    bytes
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(&[], |newline| &bytes[newline + 1..])
+}
+
+/// Parses up to `limit` entries from the end of `bytes`, returning them with the
+/// number of corrupt lines skipped on the way
+fn parse_newest(bytes: &[u8], limit: usize) -> (Vec<DownloadHistoryEntry>, usize) {
+    let mut skipped = 0;
+    let entries = String::from_utf8_lossy(bytes)
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).map_err(|_| skipped += 1).ok())
+        .take(limit)
+        .collect();
+    (entries, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(song: &str) -> DownloadHistoryEntry {
+        DownloadHistoryEntry::now(song, "https://open.spotify.com/track/x", "mp3", true)
+    }
+
+    #[test]
+    fn reads_recent_entries_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        for song in ["one", "two", "three"] {
+            DownloadHistory::append_to(&path, &entry(song)).unwrap();
+        }
+        // A write cut short by a crash
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"song\":\"fo").unwrap();
+
+        let recent = DownloadHistory::read_recent_from(&path, 2).unwrap();
+        let songs: Vec<&str> = recent.iter().map(|e| e.song.as_str()).collect();
+        assert_eq!(songs, ["three", "two"]);
+
+        assert_eq!(
+            DownloadHistory::read_recent_from(&path, 10).unwrap().len(),
+            3
+        );
+        assert!(
+            DownloadHistory::read_recent_from(&dir.path().join("missing"), 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn trims_the_oldest_entries_past_the_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let line = serde_json::to_string(&entry("old")).unwrap();
+        let count = MAX_HISTORY_BYTES as usize / (line.len() + 1);
+        fs::write(&path, format!("{}\n", line).repeat(count)).unwrap();
+
+        DownloadHistory::append_to(&path, &entry("newest")).unwrap();
+
+        let len = fs::metadata(&path).unwrap().len();
+        assert!(len <= MAX_HISTORY_BYTES / 2, "{} bytes left", len);
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents
+            .lines()
+            .all(|line| serde_json::from_str::<DownloadHistoryEntry>(line).is_ok()));
+
+        // Spans several tail chunks
+        let recent = DownloadHistory::read_recent_from(&path, usize::MAX).unwrap();
+        assert_eq!(recent.len(), contents.lines().count());
+        assert_eq!(recent[0].song, "newest");
+        assert!(recent[1..].iter().all(|e| e.song == "old"));
+    }
+}
@@ -0,0 +1,164 @@
+//! Persistent download queue
+//!
+//! Keeps the pending URLs of a batch download on disk so an interrupted batch
+//! (crash, forced quit) can be resumed without redownloading finished tracks.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, DownloadError};
+use crate::utils::get_app_data_dir;
+
+/// Bumped when the file layout changes; other versions are ignored on load
+const QUEUE_FILE_VERSION: u32 = 1;
+
+/// Download batch state as stored in `download_queue.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQueue {
+    /// File layout version
+    pub version: u32,
+    /// URLs not downloaded yet, in the original order
+    pub pending: Vec<String>,
+    /// spotdl output template
+    pub output_template: String,
+    /// Audio format (mp3, flac, ...)
+    pub format: String,
+    /// Output directory, `None` for spotdl's default
+    pub output_dir: Option<String>,
+}
+
+impl DownloadQueue {
+    /// Creates a queue for a new batch
+    pub fn new(
+        urls: &[String],
+        output_template: &str,
+        format: &str,
+        output_dir: Option<&str>,
+    ) -> Self {
+        Self {
+            version: QUEUE_FILE_VERSION,
+            pending: urls.to_vec(),
+            output_template: output_template.to_string(),
+            format: format.to_string(),
+            output_dir: output_dir.map(ToString::to_string),
+        }
+    }
+
+    /// Location of the queue file
+    fn file_path() -> PathBuf {
+        get_app_data_dir().join("download_queue.json")
+    }
+
+    /// Loads the saved queue, `None` when there is nothing to resume
+    pub fn load() -> Result<Option<Self>, AppError> {
+        Self::load_from(&Self::file_path())
+    }
+
+    fn load_from(path: &Path) -> Result<Option<Self>, AppError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = fs::read_to_string(path)?;
+        let queue: Self = serde_json::from_str(&json)
+            .map_err(|e| DownloadError::QueuePersistence(format!("Invalid queue file: {}", e)))?;
+
+        if queue.version != QUEUE_FILE_VERSION || queue.pending.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(queue))
+    }
+
+    /// Writes the queue to disk, replacing any previous one
+    pub fn save(&self) -> Result<(), AppError> {
+        self.save_to(&Self::file_path())
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| DownloadError::QueuePersistence(e.to_string()))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write then rename so a crash mid-write never leaves a truncated file
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)?;
+
+        Ok(())
+    }
+
+    /// Removes finished URLs from the pending list
+    pub fn mark_completed(&mut self, urls: &[String]) {
+        self.pending.retain(|url| !urls.contains(url));
+    }
+
+    /// Deletes the queue file once the batch is done
+    pub fn clear() -> Result<(), AppError> {
+        match fs::remove_file(Self::file_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(ids: &[&str]) -> Vec<String> {
+        ids.iter()
+            .map(|id| format!("https://open.spotify.com/track/{}", id))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_and_skips_completed_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("download_queue.json");
+        let all = urls(&["a", "b", "c", "d"]);
+
+        let mut queue = DownloadQueue::new(&all, "{title}.{output-ext}", "flac", Some("/music"));
+        queue.save_to(&path).unwrap();
+        queue.mark_completed(&all[..2]);
+        queue.save_to(&path).unwrap();
+
+        let resumed = DownloadQueue::load_from(&path).unwrap().unwrap();
+        assert_eq!(resumed.pending, all[2..]);
+        assert_eq!(resumed.output_template, "{title}.{output-ext}");
+        assert_eq!(resumed.format, "flac");
+        assert_eq!(resumed.output_dir.as_deref(), Some("/music"));
+    }
+
+    #[test]
+    fn nothing_to_resume_once_every_url_completed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("download_queue.json");
+        assert!(DownloadQueue::load_from(&path).unwrap().is_none());
+
+        let all = urls(&["a", "b"]);
+        let mut queue = DownloadQueue::new(&all, "", "mp3", None);
+        queue.mark_completed(&all);
+        queue.save_to(&path).unwrap();
+        assert!(DownloadQueue::load_from(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn ignores_other_file_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("download_queue.json");
+        let mut queue = DownloadQueue::new(&urls(&["a"]), "", "mp3", None);
+        queue.version = QUEUE_FILE_VERSION + 1;
+        queue.save_to(&path).unwrap();
+        assert!(DownloadQueue::load_from(&path).unwrap().is_none());
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(DownloadQueue::load_from(&path).is_err());
+    }
+}
@@ -1,19 +1,228 @@
 //! File system service for scanning and reading music files
 
-use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::SystemTime;
+use lofty::config::ParseOptions;
+use lofty::file::{TaggedFile, TaggedFileExt};
+use lofty::probe::Probe;
+use lofty::tag::ItemKey;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tauri::{AppHandle, Emitter, Manager};
 use tracing::instrument;
+use url::Url;
 use walkdir::WalkDir;
 use rayon::prelude::*;
+use serde::Serialize;
 
-use crate::domain::music::{MusicFile, MAX_FILES_PER_SCAN, MAX_SCAN_DEPTH};
+use crate::domain::music::{
+    ImportedPlaylist, LibraryMatch, Lyrics, LyricsSource, MatchedTrack, MusicFile, SearchField,
+    SortKey, TagChange, TagNormalization, TagNormalizeOptions, YearHistogram, MAX_FILES_PER_SCAN,
+    MAX_SCAN_DEPTH, UNKNOWN_GENRE,
+};
+use crate::domain::spotify::SpotifyTrack;
 use crate::errors::{AppError, FileError};
-use crate::utils::{is_audio_file, validate_directory, validate_file};
+use crate::events;
+use crate::utils::{
+    canonical_path, collapse_whitespace, export_m3u, is_audio_file, move_leading_article,
+    normalize_for_matching, normalize_for_search, parse_m3u, parse_pls, parse_replaygain_value,
+    path_to_string, split_artists, strip_remaster_suffix, title_case, validate_directory,
+    validate_file, validate_output_path, validate_path, validate_playlist_name,
+    validate_within_roots, PLAYLIST_EXTENSIONS,
+};
 
-/// Maximum number of threads to use for parallel processing
-const MAX_SCAN_THREADS: usize = 4;
+/// Seconds a local file's length may differ from a Spotify track's and still match
+const MATCH_DURATION_TOLERANCE_SECS: u32 = 5;
+
+/// Upper bound on tag-parsing threads; the pool uses fewer on smaller machines
+const MAX_SCAN_THREADS: usize = 8;
+
+/// Emit a `library-scan-progress` event every this many parsed files
+const SCAN_PROGRESS_INTERVAL: usize = 50;
+
+/// Maximum number of files parsed at once by `get_audio_metadata_batch`
+const MAX_METADATA_BATCH_CONCURRENCY: usize = 8;
+
+/// Emitted by `normalize_tags` after each file, successful or not
+#[derive(Serialize, Clone)]
+pub struct TagNormalizeProgress {
+    /// Number of files handled so far
+    pub current: usize,
+    /// Number of files to normalize
+    pub total: usize,
+    /// File just handled
+    pub path: String,
+}
+
+/// Tag field a normalization rule can change: name, getter, setter, and whether
+/// the remaster and leading-article rules apply to it
+type TagField = (
+    &'static str,
+    fn(&dyn audiotags::AudioTag) -> Option<&str>,
+    fn(&mut dyn audiotags::AudioTag, &str),
+    bool,
+    bool,
+);
+
+/// Fields `normalize_tags` touches
+const NORMALIZED_TAG_FIELDS: [TagField; 5] = [
+    ("title", |tag| tag.title(), |tag, v| tag.set_title(v), true, false),
+    ("artist", |tag| tag.artist(), |tag, v| tag.set_artist(v), false, true),
+    ("album", |tag| tag.album_title(), |tag, v| tag.set_album_title(v), true, false),
+    ("albumArtist", |tag| tag.album_artist(), |tag, v| tag.set_album_artist(v), false, true),
+    ("genre", |tag| tag.genre(), |tag, v| tag.set_genre(v), false, false),
+];
+
+/// Largest image `read_image_as_data_url` accepts, in bytes
+const MAX_IMAGE_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Longest side of an image returned by `read_image_as_data_url`, in pixels
+const MAX_IMAGE_DIMENSION: u32 = 1024;
+
+/// Upper bound for the number of waveform buckets a caller can request
+const MAX_WAVEFORM_BUCKETS: usize = 4096;
+
+/// Frames reduced to one min/max pair while decoding, before bucketing
+const WAVEFORM_CHUNK_FRAMES: usize = 256;
+
+/// Waveforms kept in memory; the cache is cleared when it fills up
+const WAVEFORM_CACHE_CAPACITY: usize = 64;
+
+/// File path, modification time and bucket count of a computed waveform
+type WaveformKey = (PathBuf, SystemTime, usize);
+
+/// Cached waveform peaks by key
+type WaveformCache = HashMap<WaveformKey, Vec<(f32, f32)>>;
+
+/// Computed waveforms, keyed so an edited file is decoded again
+static WAVEFORM_CACHE: LazyLock<Mutex<WaveformCache>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Device and inode of a visited directory
+#[cfg(unix)]
+type DirIdentity = (u64, u64);
+
+/// Canonical path of a visited directory
+#[cfg(not(unix))]
+type DirIdentity = std::path::PathBuf;
+
+/// Files found by a multi-folder scan, plus each folder that failed with its error
+pub type FolderScan = (Vec<MusicFile>, Vec<(String, AppError)>);
+
+/// Cancellation flags of the running library scans, managed by Tauri
+///
+/// Each scan gets its own id and flag, so cancelling one leaves the others,
+/// including scans started after the cancel, running.
+#[derive(Default)]
+pub struct ScanState {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl ScanState {
+    fn lock_running(&self) -> MutexGuard<'_, HashMap<u64, Arc<AtomicBool>>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers a new scan, returning its id and the flag it polls
+    fn begin(&self) -> (u64, Arc<AtomicBool>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.lock_running().insert(id, Arc::clone(&cancelled));
+        (id, cancelled)
+    }
+
+    /// Forgets a scan that returned
+    fn finish(&self, id: u64) {
+        self.lock_running().remove(&id);
+    }
+
+    /// Asks a scan to stop; it finishes the files in flight and returns `ScanCancelled`
+    ///
+    /// Returns false when no scan with that id is running.
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.lock_running().get(&id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Asks every running scan to stop
+    pub fn cancel_all(&self) {
+        for cancelled in self.lock_running().values() {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Cancellation flag and parsed-file count shared by the steps of one scan
+struct ScanControl<'a> {
+    app_handle: Option<&'a AppHandle>,
+    /// Id reported in the scan events; 0 when the scan isn't tracked
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    processed: AtomicUsize,
+}
+
+impl<'a> ScanControl<'a> {
+    /// Starts tracking a scan; without a managed `ScanState` it can't be cancelled
+    fn begin(app_handle: Option<&'a AppHandle>) -> Self {
+        let (id, cancelled) = app_handle
+            .and_then(|app| app.try_state::<ScanState>())
+            .map(|state| state.begin())
+            .unwrap_or_default();
+
+        Self {
+            app_handle,
+            id,
+            cancelled,
+            processed: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with `ScanCancelled` once `cancel_scan` was called, emitting the
+    /// cancelled event with the number of files parsed so far
+    fn check(&self) -> Result<(), AppError> {
+        if !self.is_cancelled() {
+            return Ok(());
+        }
+
+        let processed = self.processed.load(Ordering::SeqCst);
+        tracing::info!("📁 Scan cancelled after {} files", processed);
+        if let Some(app) = self.app_handle {
+            let _ = app.emit(
+                events::LIBRARY_SCAN_CANCELLED,
+                serde_json::json!({ "scanId": self.id, "processed": processed }),
+            );
+        }
+        Err(FileError::ScanCancelled.into())
+    }
+}
+
+impl Drop for ScanControl<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.app_handle.and_then(|app| app.try_state::<ScanState>()) {
+            state.finish(self.id);
+        }
+    }
+}
 
 /// Service for file system operations
 pub struct FileService;
@@ -22,12 +231,19 @@ impl FileService {
     /// Async version of scan_music_folder that runs in a blocking thread
     pub async fn scan_music_folder_async(
         folder_path: &str,
+        follow_symlinks: bool,
+        max_file_bytes: Option<u64>,
         app_handle: Option<AppHandle>,
     ) -> Result<Vec<MusicFile>, AppError> {
         let folder_path = folder_path.to_string();
         
         tokio::task::spawn_blocking(move || {
-            Self::scan_music_folder(&folder_path, app_handle.as_ref())
+            Self::scan_music_folder(
+                &folder_path,
+                follow_symlinks,
+                max_file_bytes,
+                app_handle.as_ref(),
+            )
         })
         .await
         .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))?
@@ -36,49 +252,209 @@ impl FileService {
     /// Scans a music folder for audio files and extracts their metadata
     ///
     /// Limited to MAX_FILES_PER_SCAN files and MAX_SCAN_DEPTH directory levels for security.
-    #[instrument(skip_all, fields(folder_path = %folder_path))]
+    ///
+    /// Symlinks are not followed unless `follow_symlinks` is set. Following them lets
+    /// libraries made of linked folders be scanned, but a link can point anywhere on
+    /// disk, so the scan may leave the chosen folder entirely. Loops are guarded by
+    /// WalkDir's ancestor check plus a set of already visited directories.
+    ///
+    /// Audio files larger than `max_file_bytes` (e.g. multi-GB concatenated sets)
+    /// are skipped before their tags are read and reported with a
+    /// `library-scan-skipped` event. There is no size limit by default.
+    ///
+    /// `cancel_scan` stops the scan between files with `ScanCancelled`.
+    #[instrument(skip_all, fields(folder_path = %folder_path, follow_symlinks, max_file_bytes))]
     pub fn scan_music_folder(
         folder_path: &str,
+        follow_symlinks: bool,
+        max_file_bytes: Option<u64>,
         app_handle: Option<&AppHandle>,
     ) -> Result<Vec<MusicFile>, AppError> {
         let validated_path = validate_directory(folder_path)?;
+        let control = ScanControl::begin(app_handle);
 
-        // Emit scan start event
-        if let Some(app) = app_handle {
-            let _ = app.emit(
-                "library-scan-start",
-                serde_json::json!({ "path": folder_path }),
-            );
-        }
+        Self::emit_scan_start(&control, folder_path);
 
-        // First, collect all audio file paths
-        let mut audio_paths = Vec::new();
-        let mut file_count = 0;
-        
-        for entry in WalkDir::new(&validated_path)
-            .follow_links(false) // Security: don't follow symlinks
-            .max_depth(MAX_SCAN_DEPTH)
+        let audio_paths = Self::collect_audio_paths(
+            &validated_path,
+            follow_symlinks,
+            MAX_FILES_PER_SCAN,
+            max_file_bytes,
+            &control,
+        )?;
+        let music_files = Self::extract_metadata_parallel(&audio_paths, folder_path, &control)?;
+
+        Self::emit_scan_complete(&control, music_files.len());
+
+        tracing::info!("📁 Scan completed: found {} audio files", music_files.len());
+        Ok(music_files)
+    }
+
+    /// Async version of scan_music_folders that runs in a blocking thread
+    pub async fn scan_music_folders_async(
+        folder_paths: Vec<String>,
+        follow_symlinks: bool,
+        app_handle: Option<AppHandle>,
+    ) -> Result<FolderScan, AppError> {
+        tokio::task::spawn_blocking(move || {
+            Self::scan_music_folders(&folder_paths, follow_symlinks, app_handle.as_ref())
+        })
+        .await
+        .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))?
+    }
+
+    /// Scans several music folders and merges their files
+    ///
+    /// Folders are scanned one after another with the single-folder rules; files
+    /// reached from more than one folder are kept once. MAX_FILES_PER_SCAN applies
+    /// to all folders combined. A folder that can't be scanned is returned with its
+    /// error instead of aborting the others; only hitting the file limit or
+    /// `cancel_scan` fails the whole scan.
+    #[instrument(skip_all, fields(folder_count = folder_paths.len(), follow_symlinks))]
+    pub fn scan_music_folders(
+        folder_paths: &[String],
+        follow_symlinks: bool,
+        app_handle: Option<&AppHandle>,
+    ) -> Result<FolderScan, AppError> {
+        let control = ScanControl::begin(app_handle);
+        let mut music_files = Vec::new();
+        let mut failed_folders = Vec::new();
+        let mut seen_files = HashSet::new();
+
+        for folder_path in folder_paths {
+            let validated_path = match validate_directory(folder_path) {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::warn!("📁 Skipping folder {}: {}", folder_path, e);
+                    failed_folders.push((folder_path.clone(), e));
+                    continue;
+                }
+            };
+
+            Self::emit_scan_start(&control, folder_path);
+
+            let audio_paths: Vec<String> = Self::collect_audio_paths(
+                &validated_path,
+                follow_symlinks,
+                MAX_FILES_PER_SCAN,
+                None,
+                &control,
+            )?
             .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            // Limit number of files processed
-            if file_count >= MAX_FILES_PER_SCAN {
+            .filter(|path| seen_files.insert(path.clone()))
+            .collect();
+
+            // Only files not already found through an earlier folder count
+            if seen_files.len() > MAX_FILES_PER_SCAN {
                 tracing::warn!("📁 Reached maximum file limit: {}", MAX_FILES_PER_SCAN);
                 return Err(FileError::ScanLimitExceeded(MAX_FILES_PER_SCAN).into());
             }
 
+            music_files.extend(Self::extract_metadata_parallel(
+                &audio_paths,
+                folder_path,
+                &control,
+            )?);
+        }
+
+        Self::emit_scan_complete(&control, music_files.len());
+
+        tracing::info!(
+            "📁 Scan of {} folders completed: found {} audio files, {} folders failed",
+            folder_paths.len(),
+            music_files.len(),
+            failed_folders.len()
+        );
+        Ok((music_files, failed_folders))
+    }
+
+    /// Walks a validated folder and returns the paths of its audio files
+    ///
+    /// Fails with `ScanLimitExceeded` when the folder holds more than `limit` audio files.
+    /// Files over `max_file_bytes` are skipped and don't count towards the limit.
+    fn collect_audio_paths(
+        root: &Path,
+        follow_symlinks: bool,
+        limit: usize,
+        max_file_bytes: Option<u64>,
+        control: &ScanControl,
+    ) -> Result<Vec<String>, AppError> {
+        let mut audio_paths = Vec::new();
+        let mut visited_dirs = HashSet::new();
+
+        let mut walker = WalkDir::new(root)
+            .follow_links(follow_symlinks) // Security: off unless explicitly requested
+            .max_depth(MAX_SCAN_DEPTH)
+            .into_iter();
+
+        while let Some(entry) = walker.next() {
+            control.check()?;
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    if err.loop_ancestor().is_some() {
+                        tracing::warn!("📁 Symlink loop detected, skipping: {}", err);
+                    } else if err.io_error().map(|io| io.kind()) == Some(ErrorKind::PermissionDenied) {
+                        tracing::warn!("📁 Permission denied, skipping: {}", err);
+                    }
+                    continue;
+                }
+            };
+
+            // Skip directories already reached through another symlink
+            if follow_symlinks && entry.file_type().is_dir() {
+                if let Some(id) = Self::dir_identity(entry.path()) {
+                    if !visited_dirs.insert(id) {
+                        walker.skip_current_dir();
+                        continue;
+                    }
+                }
+            }
+
             let path = entry.path();
             if is_audio_file(path) {
-                if let Some(path_str) = path.to_str() {
-                    audio_paths.push(path_str.to_string());
-                    file_count += 1;
+                if let Some(max_bytes) = max_file_bytes {
+                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    if size > max_bytes {
+                        Self::emit_file_skipped(control.app_handle, path, size, max_bytes);
+                        continue;
+                    }
+                }
+
+                // Limit number of files processed
+                if audio_paths.len() >= limit {
+                    tracing::warn!("📁 Reached maximum file limit: {}", MAX_FILES_PER_SCAN);
+                    return Err(FileError::ScanLimitExceeded(MAX_FILES_PER_SCAN).into());
                 }
+                // Stored paths are canonical so the same file always has the same path
+                let canonical = canonical_path(path).unwrap_or_else(|_| path.to_path_buf());
+                // Non-Unicode paths are listed in their `native-path:` form
+                if canonical.to_str().is_none() {
+                    tracing::info!("📁 Listing {} by its native path", canonical.display());
+                }
+                audio_paths.push(path_to_string(&canonical));
             }
         }
 
-        // Process files in parallel using rayon with limited threads
+        Ok(audio_paths)
+    }
+
+    /// Extracts metadata for scanned files in parallel, emitting progress for `root`
+    ///
+    /// Files whose metadata can't be read are left out.
+    fn extract_metadata_parallel(
+        audio_paths: &[String],
+        root: &str,
+        control: &ScanControl,
+    ) -> Result<Vec<MusicFile>, AppError> {
+        // Process files in parallel using rayon, one thread per core up to the cap
+        let num_threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_SCAN_THREADS);
         let thread_pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(MAX_SCAN_THREADS)
+            .num_threads(num_threads)
             .build()
             .map_err(|e| AppError::Concurrency(format!("Failed to create thread pool: {}", e)))?;
             
@@ -87,18 +463,28 @@ impl FileService {
             audio_paths
                 .par_iter()
                 .filter_map(|path| {
+                    // Once cancelled, the remaining files are skipped without parsing
+                    if control.is_cancelled() {
+                        return None;
+                    }
+
                     let result = Self::get_audio_metadata(path);
                     let current = processed_count.fetch_add(1, Ordering::SeqCst) + 1;
+                    control.processed.fetch_add(1, Ordering::SeqCst);
                     
-                    // Emit progress every 50 files
-                    if current % 50 == 0 {
-                        if let Some(app) = app_handle {
+                    // Emit progress periodically and once the last file is parsed
+                    if current.is_multiple_of(SCAN_PROGRESS_INTERVAL)
+                        || current == audio_paths.len()
+                    {
+                        if let Some(app) = control.app_handle {
                             let _ = app.emit(
-                                "library-scan-progress",
+                                events::LIBRARY_SCAN_PROGRESS,
                                 serde_json::json!({
+                                    "scanId": control.id,
                                     "current": current,
                                     "total": audio_paths.len(),
-                                    "path": path
+                                    "path": path,
+                                    "root": root
                                 }),
                             );
                         }
@@ -109,16 +495,336 @@ impl FileService {
                 .collect()
         });
 
-        // Emit completion event
+        control.check()?;
+        Ok(music_files)
+    }
+
+    /// Emits the scan start event for a folder
+    fn emit_scan_start(control: &ScanControl, folder_path: &str) {
+        if let Some(app) = control.app_handle {
+            let _ = app.emit(
+                events::LIBRARY_SCAN_START,
+                serde_json::json!({ "scanId": control.id, "path": folder_path }),
+            );
+        }
+    }
+
+    /// Reports an audio file left out for exceeding the size limit
+    fn emit_file_skipped(app_handle: Option<&AppHandle>, path: &Path, size: u64, max_bytes: u64) {
+        tracing::warn!(
+            "📁 Skipping {} ({} bytes, limit {})",
+            path.display(),
+            size,
+            max_bytes
+        );
         if let Some(app) = app_handle {
             let _ = app.emit(
-                "library-scan-complete",
-                serde_json::json!({ "total": music_files.len() }),
+                events::LIBRARY_SCAN_SKIPPED,
+                serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "reason": "tooLarge",
+                    "size": size,
+                    "maxBytes": max_bytes
+                }),
             );
         }
+    }
 
-        tracing::info!("📁 Scan completed: found {} audio files", music_files.len());
-        Ok(music_files)
+    /// Emits the scan completion event
+    fn emit_scan_complete(control: &ScanControl, total: usize) {
+        if let Some(app) = control.app_handle {
+            let _ = app.emit(
+                events::LIBRARY_SCAN_COMPLETE,
+                serde_json::json!({ "scanId": control.id, "total": total }),
+            );
+        }
+    }
+
+    /// Identifies a directory independently of the path used to reach it
+    #[cfg(unix)]
+    fn dir_identity(path: &Path) -> Option<DirIdentity> {
+        use std::os::unix::fs::MetadataExt;
+
+        std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+    }
+
+    /// Identifies a directory independently of the path used to reach it
+    #[cfg(not(unix))]
+    fn dir_identity(path: &Path) -> Option<DirIdentity> {
+        path.canonicalize().ok()
+    }
+
+    /// Extracts metadata for many files concurrently, preserving input order
+    ///
+    /// Each path is validated on its own, so one bad path only fails its own entry.
+    #[instrument(skip_all, fields(file_count = paths.len()))]
+    pub async fn get_audio_metadata_batch(paths: Vec<String>) -> Vec<Result<MusicFile, AppError>> {
+        use futures::stream::{self, StreamExt};
+
+        let mut results: Vec<(usize, Result<MusicFile, AppError>)> =
+            stream::iter(paths.into_iter().enumerate())
+                .map(|(index, path)| async move {
+                    let result =
+                        tokio::task::spawn_blocking(move || Self::get_audio_metadata(&path))
+                            .await
+                            .map_err(|e| AppError::Concurrency(format!("Task join error: {}", e)))
+                            .and_then(|r| r);
+                    (index, result)
+                })
+                .buffer_unordered(MAX_METADATA_BATCH_CONCURRENCY)
+                .collect()
+                .await;
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Filters an already-scanned library with a case- and accent-insensitive substring match
+    ///
+    /// Matches title, artist, album and genre unless `field` restricts it to one of them.
+    /// An empty query returns every track.
+    pub fn filter_tracks(
+        files: &[MusicFile],
+        query: &str,
+        field: Option<SearchField>,
+    ) -> Vec<MusicFile> {
+        let needle = normalize_for_search(query.trim());
+        if needle.is_empty() {
+            return files.to_vec();
+        }
+
+        files
+            .iter()
+            .filter(|file| {
+                let value = match field {
+                    Some(SearchField::Title) => &file.title,
+                    Some(SearchField::Artist) => &file.artist,
+                    Some(SearchField::Album) => &file.album,
+                    Some(SearchField::Genre) => &file.genre,
+                    None => return file.search_blob().contains(&needle),
+                };
+                value
+                    .as_deref()
+                    .is_some_and(|value| normalize_for_search(value).contains(&needle))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Sorts tracks by the given key
+    ///
+    /// Text keys compare case- and accent-insensitively. Tracks missing the key
+    /// always sort last, whatever the direction.
+    pub fn sort_tracks(
+        mut files: Vec<MusicFile>,
+        sort_by: SortKey,
+        ascending: bool,
+    ) -> Vec<MusicFile> {
+        fn by_option<T: Ord>(a: Option<T>, b: Option<T>, ascending: bool) -> std::cmp::Ordering {
+            use std::cmp::Ordering;
+
+            match (a, b) {
+                (Some(a), Some(b)) if ascending => a.cmp(&b),
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+
+        let text = |value: &Option<String>| value.as_deref().map(normalize_for_search);
+
+        files.sort_by(|a, b| match sort_by {
+            SortKey::Title => by_option(text(&a.title), text(&b.title), ascending),
+            SortKey::Artist => by_option(text(&a.artist), text(&b.artist), ascending),
+            SortKey::Album => by_option(text(&a.album), text(&b.album), ascending),
+            SortKey::Year => by_option(a.year, b.year, ascending),
+            SortKey::Duration => by_option(a.duration, b.duration, ascending),
+            SortKey::TrackNumber => by_option(a.track_number, b.track_number, ascending),
+        });
+
+        files
+    }
+
+    /// Counts tracks per genre
+    ///
+    /// Multi-genre tags like "Rock; Pop" or "Rock/Pop" count towards each genre once.
+    /// Genres are merged case- and accent-insensitively under the first spelling seen;
+    /// tracks without a genre are counted as [`UNKNOWN_GENRE`].
+    pub fn group_by_genre(files: &[MusicFile]) -> BTreeMap<String, usize> {
+        let mut groups: HashMap<String, (String, usize)> = HashMap::new();
+
+        for file in files {
+            let mut seen = HashSet::new();
+            for genre in Self::split_genres(file.genre.as_deref()) {
+                let key = normalize_for_search(&genre);
+                if seen.insert(key.clone()) {
+                    groups.entry(key).or_insert((genre, 0)).1 += 1;
+                }
+            }
+        }
+
+        groups.into_values().collect()
+    }
+
+    /// Returns the tracks tagged with `genre`, compared case- and accent-insensitively
+    ///
+    /// Matches any genre of a multi-genre tag; [`UNKNOWN_GENRE`] matches tracks
+    /// without a genre.
+    pub fn filter_by_genre(files: &[MusicFile], genre: &str) -> Vec<MusicFile> {
+        let needle = normalize_for_search(genre.trim());
+
+        files
+            .iter()
+            .filter(|file| {
+                Self::split_genres(file.genre.as_deref())
+                    .iter()
+                    .any(|g| normalize_for_search(g) == needle)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Counts tracks per release decade (1990 covers 1990-1999)
+    ///
+    /// Years before 1900 or after next year are treated as tag noise and counted
+    /// with the tracks that have no year.
+    pub fn year_histogram(files: &[MusicFile]) -> YearHistogram {
+        const MIN_PLAUSIBLE_YEAR: i32 = 1900;
+        const SECS_PER_YEAR: u64 = 31_556_952;
+
+        let current_year = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| 1970 + (d.as_secs() / SECS_PER_YEAR) as i32)
+            .unwrap_or(1970);
+        let plausible = MIN_PLAUSIBLE_YEAR..=current_year + 1;
+
+        let mut decades: BTreeMap<i32, u32> = BTreeMap::new();
+        let mut unknown = 0;
+        for year in files.iter().map(|file| file.year) {
+            match year.filter(|y| plausible.contains(y)) {
+                Some(year) => *decades.entry(year - year % 10).or_default() += 1,
+                None => unknown += 1,
+            }
+        }
+
+        YearHistogram {
+            decades: decades.into_iter().collect(),
+            unknown,
+        }
+    }
+
+    /// Finds the local copy of each Spotify track, in Spotify order
+    ///
+    /// Titles and artists are compared case-, accent- and punctuation-insensitively;
+    /// bracketed title suffixes such as "(Remastered 2011)" and "- Live" are ignored.
+    /// A candidate needs the same title, at least one shared artist and, when both
+    /// durations are known, a length within [`MATCH_DURATION_TOLERANCE_SECS`]. The
+    /// candidate with the closest duration wins.
+    pub fn match_spotify_tracks(
+        local: &[MusicFile],
+        spotify: &[SpotifyTrack],
+    ) -> Vec<Option<MusicFile>> {
+        let mut by_title: HashMap<String, Vec<(&MusicFile, Vec<String>)>> = HashMap::new();
+        for file in local {
+            let Some(title) = file.title.as_deref().map(Self::title_match_key) else {
+                continue;
+            };
+            let artists = if file.artists.is_empty() {
+                split_artists(file.artist.as_deref())
+            } else {
+                file.artists.clone()
+            };
+            let artists = artists.iter().map(|a| normalize_for_matching(a)).collect();
+            by_title.entry(title).or_default().push((file, artists));
+        }
+
+        spotify
+            .iter()
+            .map(|track| {
+                let artists: Vec<String> = track
+                    .artists
+                    .iter()
+                    .map(|a| normalize_for_matching(a))
+                    .collect();
+                let spotify_secs = track.duration_ms / 1000;
+
+                by_title
+                    .get(&Self::title_match_key(&track.name))?
+                    .iter()
+                    .filter(|(_, local_artists)| local_artists.iter().any(|a| artists.contains(a)))
+                    .filter_map(|(file, _)| {
+                        let difference = match file.duration {
+                            Some(secs) if spotify_secs > 0 => secs.abs_diff(spotify_secs),
+                            _ => 0,
+                        };
+                        (difference <= MATCH_DURATION_TOLERANCE_SECS).then_some((difference, *file))
+                    })
+                    .min_by_key(|(difference, _)| *difference)
+                    .map(|(_, file)| file.clone())
+            })
+            .collect()
+    }
+
+    /// Matches Spotify tracks against the library and splits them into found and missing
+    pub fn match_spotify_to_library(
+        local: &[MusicFile],
+        spotify: Vec<SpotifyTrack>,
+    ) -> LibraryMatch {
+        let matches = Self::match_spotify_tracks(local, &spotify);
+        let mut result = LibraryMatch {
+            matched: Vec::new(),
+            unmatched: Vec::new(),
+        };
+        for (track, found) in spotify.into_iter().zip(matches) {
+            match found {
+                Some(file) => result.matched.push(MatchedTrack {
+                    spotify: track,
+                    local: file,
+                }),
+                None => result.unmatched.push(track),
+            }
+        }
+        result
+    }
+
+    /// Title without version suffixes, normalized for matching
+    fn title_match_key(title: &str) -> String {
+        let mut base = String::with_capacity(title.len());
+        let mut depth = 0usize;
+        for c in title.chars() {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                _ if depth == 0 => base.push(c),
+                _ => {}
+            }
+        }
+        let base = base.split(" - ").next().unwrap_or_default();
+        let key = normalize_for_matching(base);
+        // A title that is only a bracketed part keeps it
+        if key.is_empty() {
+            normalize_for_matching(title)
+        } else {
+            key
+        }
+    }
+
+    /// Splits a genre tag on `;`, `,` and `/`, or yields [`UNKNOWN_GENRE`] when empty
+    fn split_genres(genre: Option<&str>) -> Vec<String> {
+        let genres: Vec<String> = genre
+            .unwrap_or_default()
+            .split([';', ',', '/'])
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        if genres.is_empty() {
+            vec![UNKNOWN_GENRE.to_string()]
+        } else {
+            genres
+        }
     }
 
     /// Extracts audio metadata from a file using the audiotags crate
@@ -135,8 +841,212 @@ impl FileService {
             return Err(FileError::UnsupportedFormat(ext.to_string()).into());
         }
 
-        Self::extract_metadata_from_tag(&validated_path, file_path)
-            .or_else(|_| Self::create_fallback_metadata(&validated_path, file_path))
+        // The returned `path` is the canonical one, whatever form the caller used
+        let canonical = path_to_string(&validated_path);
+        Self::extract_metadata_from_tag(&validated_path, &canonical)
+            .or_else(|_| Self::create_fallback_metadata(&validated_path, &canonical))
+    }
+
+    /// Checks that a file's audio stream parses, reading its properties
+    ///
+    /// Catches truncated or mislabelled files that [`Self::get_audio_metadata`]
+    /// would still describe from the file name alone.
+    pub fn probe_audio(path: &Path) -> Result<(), AppError> {
+        Probe::open(path)
+            .and_then(|probe| probe.read())
+            .map(|_| ())
+            .map_err(|e| FileError::Decode(format!("{}: {}", path.display(), e)).into())
+    }
+
+    /// Reads a track's lyrics, preferring a sibling `.lrc` file over embedded lyrics
+    ///
+    /// When the tags hold several lyrics frames (one per language) the first is
+    /// used. Returns `None` when the track has no lyrics.
+    #[instrument(skip_all, fields(file_path = %file_path))]
+    pub fn get_lyrics(file_path: &str) -> Result<Option<Lyrics>, AppError> {
+        let validated_path = validate_file(file_path)?;
+        if !is_audio_file(&validated_path) {
+            let ext = validated_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("unknown");
+            return Err(FileError::UnsupportedFormat(ext.to_string()).into());
+        }
+
+        if let Some(text) = Self::read_lrc_sidecar(&validated_path)? {
+            return Ok(Some(Lyrics {
+                text,
+                source: LyricsSource::Lrc,
+            }));
+        }
+
+        let embedded = Self::read_embedded_lyrics(&validated_path);
+        Ok(embedded.map(|text| Lyrics {
+            text,
+            source: LyricsSource::Embedded,
+        }))
+    }
+
+    /// Reads `<name>.lrc` (or `.LRC`) next to the audio file, if there is one
+    fn read_lrc_sidecar(audio_path: &Path) -> Result<Option<String>, AppError> {
+        let Some(lrc_path) = ["lrc", "LRC"]
+            .iter()
+            .map(|extension| audio_path.with_extension(extension))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        // Older .lrc files are often not UTF-8
+        let bytes = std::fs::read(&lrc_path)?;
+        let text = String::from_utf8_lossy(&bytes);
+        let text = text.trim_start_matches('\u{feff}').trim();
+        Ok((!text.is_empty()).then(|| text.to_string()))
+    }
+
+    /// Reads the first lyrics frame from the file's tags
+    fn read_embedded_lyrics(path: &Path) -> Option<String> {
+        let tagged = Probe::open(path)
+            .and_then(|probe| {
+                probe
+                    .options(ParseOptions::new().read_properties(false))
+                    .read()
+            })
+            .map_err(|e| tracing::debug!("📁 Failed to read lyrics for {}: {}", path.display(), e))
+            .ok()?;
+
+        tagged
+            .tags()
+            .iter()
+            .find_map(|tag| tag.get_string(&ItemKey::Lyrics))
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(ToString::to_string)
+    }
+
+    /// Imports an `.m3u`, `.m3u8` or `.pls` playlist
+    ///
+    /// Relative entries are resolved against the playlist's folder and `file://` URLs
+    /// are accepted; other URLs (streams) and entries that don't point to an audio
+    /// file on disk are reported in `missing`.
+    #[instrument(skip_all, fields(playlist_path = %playlist_path))]
+    pub fn import_playlist(
+        playlist_path: &str,
+        roots: &[PathBuf],
+    ) -> Result<ImportedPlaylist, AppError> {
+        let validated_path = validate_file(playlist_path)?;
+        let ext = validated_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        if !PLAYLIST_EXTENSIONS.contains(&ext.as_str()) {
+            return Err(FileError::UnsupportedFormat(ext).into());
+        }
+
+        // Older M3U files are often Latin-1; lossy decoding still keeps ASCII paths
+        let bytes = std::fs::read(&validated_path)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let entries = if ext == "pls" {
+            parse_pls(&content)
+        } else {
+            parse_m3u(&content)
+        };
+        if entries.len() > MAX_FILES_PER_SCAN {
+            return Err(FileError::ScanLimitExceeded(MAX_FILES_PER_SCAN).into());
+        }
+
+        let base_dir = validated_path.parent().unwrap_or(Path::new(""));
+        let results: Vec<Result<MusicFile, String>> = entries
+            .into_par_iter()
+            .map(|entry| {
+                Self::resolve_playlist_entry(base_dir, &entry, roots)
+                    .and_then(|path| Self::get_audio_metadata(&path).ok())
+                    .ok_or(entry)
+            })
+            .collect();
+
+        let mut playlist = ImportedPlaylist {
+            tracks: Vec::new(),
+            missing: Vec::new(),
+        };
+        for result in results {
+            match result {
+                Ok(track) => playlist.tracks.push(track),
+                Err(entry) => playlist.missing.push(entry),
+            }
+        }
+
+        tracing::info!(
+            "📁 Imported playlist {}: {} tracks, {} missing",
+            playlist_path,
+            playlist.tracks.len(),
+            playlist.missing.len()
+        );
+        Ok(playlist)
+    }
+
+    /// Saves the given local tracks, in order, as `<name>.m3u8` in `destination_dir`
+    ///
+    /// Every path must be an existing audio file inside `roots`; an existing
+    /// playlist with the same name is replaced. Returns the written playlist path.
+    #[instrument(skip_all, fields(name = %name, tracks = file_paths.len()))]
+    pub fn create_playlist(
+        name: &str,
+        destination_dir: &str,
+        file_paths: &[String],
+        roots: &[PathBuf],
+    ) -> Result<String, AppError> {
+        if file_paths.is_empty() {
+            return Err(AppError::Validation("Playlist has no tracks".into()));
+        }
+        if file_paths.len() > MAX_FILES_PER_SCAN {
+            return Err(FileError::ScanLimitExceeded(MAX_FILES_PER_SCAN).into());
+        }
+
+        let name = validate_playlist_name(name)?;
+        let destination = validate_directory(destination_dir)?;
+
+        let tracks = file_paths
+            .par_iter()
+            .map(|file_path| {
+                validate_within_roots(file_path, roots)?;
+                Self::get_audio_metadata(file_path)
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        let playlist_path = destination.join(format!("{}.m3u8", name));
+        export_m3u(&playlist_path, &tracks)?;
+
+        tracing::info!(
+            "📁 Saved playlist {} with {} tracks",
+            playlist_path.display(),
+            tracks.len()
+        );
+        Ok(playlist_path.to_string_lossy().into_owned())
+    }
+
+    /// Resolves a playlist entry to the canonical path of an existing audio file
+    ///
+    /// Entries resolving outside `roots` are treated as missing and never read.
+    fn resolve_playlist_entry(base_dir: &Path, entry: &str, roots: &[PathBuf]) -> Option<String> {
+        // `file://` URLs are percent-encoded; other schemes aren't local files
+        let local = if entry.contains("://") {
+            Url::parse(entry).ok()?.to_file_path().ok()?
+        } else {
+            PathBuf::from(entry)
+        };
+
+        // Canonicalizing resolves the `..` segments relative playlists rely on
+        let resolved = canonical_path(&base_dir.join(local)).ok()?;
+        if !roots.iter().any(|root| resolved.starts_with(root)) {
+            tracing::warn!("📁 Playlist entry outside allowed roots: {}", entry);
+            return None;
+        }
+        if !resolved.is_file() || !is_audio_file(&resolved) {
+            return None;
+        }
+        Some(path_to_string(&resolved))
     }
 
     /// Extracts metadata from audio tag
@@ -147,7 +1057,7 @@ impl FileService {
         })?;
 
         // Extract album art if available
-        let album_art = Self::extract_album_art(&tag);
+        let album_art = Self::extract_album_art(tag.as_ref());
 
         // Get title from tag, fallback to filename if empty or None
         let title = tag
@@ -156,29 +1066,106 @@ impl FileService {
             .map(ToString::to_string)
             .or_else(|| {
                 path.file_stem()
-                    .and_then(|s| s.to_str())
-                    .map(|s| Self::clean_filename_for_title(s))
+                    .map(|s| Self::clean_filename_for_title(&s.to_string_lossy()))
             });
 
-        // Get artist from tag, try to parse from filename if not available
-        let artist = tag
-            .artist()
-            .filter(|a| !a.trim().is_empty())
-            .map(ToString::to_string)
-            .or_else(|| Self::extract_artist_from_filename(path));
+        let tagged = Self::read_lofty_tags(path);
+        let artist_frames = tagged
+            .as_ref()
+            .map(Self::read_artist_frames)
+            .unwrap_or_default();
+
+        // Get artist from tag, try to parse from filename if not available.
+        // audiotags only sees the first artist frame, so prefer all of lofty's.
+        let artist = Self::join_artist_frames(&artist_frames)
+            .or_else(|| {
+                tag.artist()
+                    .filter(|a| !a.trim().is_empty())
+                    .map(ToString::to_string)
+            })
+            .or_else(|| Self::extract_artist_from_filename(path));
+        let artists = if artist_frames.is_empty() {
+            split_artists(artist.as_deref())
+        } else {
+            split_artists(artist_frames.iter().map(String::as_str))
+        };
+
+        let (replaygain_track_gain, replaygain_track_peak) = tagged
+            .as_ref()
+            .map(Self::read_replaygain)
+            .unwrap_or_default();
 
         Ok(MusicFile {
             path: file_path.to_string(),
             title,
             artist,
+            artists,
             album: tag.album_title().map(ToString::to_string),
             duration: tag.duration().map(|d| d as u32),
             year: tag.year(),
             genre: tag.genre().map(ToString::to_string),
+            track_number: tag.track_number(),
             album_art,
+            replaygain_track_gain,
+            replaygain_track_peak,
         })
     }
 
+    /// Reads the tags (not the audio properties) with lofty, for what audiotags doesn't expose
+    fn read_lofty_tags(path: &Path) -> Option<TaggedFile> {
+        Probe::open(path)
+            .and_then(|probe| {
+                probe
+                    .options(ParseOptions::new().read_properties(false))
+                    .read()
+            })
+            .map_err(|e| tracing::debug!("📁 Failed to read tags for {}: {}", path.display(), e))
+            .ok()
+    }
+
+    /// Reads every artist frame of the first tag that has one
+    fn read_artist_frames(tagged: &TaggedFile) -> Vec<String> {
+        tagged
+            .tags()
+            .iter()
+            .map(|tag| {
+                tag.get_strings(&ItemKey::TrackArtist)
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .find(|frames| !frames.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Joins artist frames (and NUL-separated values) into one display string
+    fn join_artist_frames(frames: &[String]) -> Option<String> {
+        let names: Vec<&str> = frames
+            .iter()
+            .flat_map(|frame| frame.split('\0'))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect();
+        (!names.is_empty()).then(|| names.join(", "))
+    }
+
+    /// Reads ReplayGain track gain and peak, which audiotags doesn't expose
+    ///
+    /// A missing or unparsable value yields `None`.
+    fn read_replaygain(tagged: &TaggedFile) -> (Option<f32>, Option<f32>) {
+        let read = |key: &ItemKey| {
+            tagged
+                .tags()
+                .iter()
+                .find_map(|tag| tag.get_string(key))
+                .and_then(parse_replaygain_value)
+        };
+
+        (
+            read(&ItemKey::ReplayGainTrackGain),
+            read(&ItemKey::ReplayGainTrackPeak),
+        )
+    }
+
     /// Cleans a filename to use as title (removes common patterns)
     fn clean_filename_for_title(filename: &str) -> String {
         let cleaned = filename
@@ -196,7 +1183,7 @@ impl FileService {
 
     /// Tries to extract artist from filename patterns like "Artist - Title"
     fn extract_artist_from_filename(path: &Path) -> Option<String> {
-        let filename = path.file_stem()?.to_str()?;
+        let filename = path.file_stem()?.to_string_lossy();
         
         // Try common patterns: "Artist - Title", "Artist – Title"
         for separator in [" - ", " – ", " _ "] {
@@ -212,21 +1199,356 @@ impl FileService {
         None
     }
 
+    /// Reads a whole audio file, for paths the asset protocol can't serve
+    ///
+    /// The webview plays local files through Tauri's asset protocol, which only
+    /// handles Unicode paths; files listed in the `native-path:` form are played
+    /// from these bytes instead.
+    #[instrument(skip_all, fields(file_path = %file_path))]
+    pub fn read_audio_bytes(file_path: &str) -> Result<Vec<u8>, AppError> {
+        let validated_path = validate_file(file_path)?;
+        if !is_audio_file(&validated_path) {
+            let ext = validated_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("unknown");
+            return Err(FileError::UnsupportedFormat(ext.to_string()).into());
+        }
+
+        Ok(std::fs::read(&validated_path)?)
+    }
+
+    /// Reads the embedded album art as raw image bytes plus its mime type
+    ///
+    /// Avoids the base64 data URL `MusicFile::album_art` carries, which inflates
+    /// large covers by a third. Fails with `NoAlbumArt` when the file has none.
+    #[instrument(skip_all, fields(file_path = %file_path))]
+    pub fn read_album_art_bytes(file_path: &str) -> Result<(Vec<u8>, &'static str), AppError> {
+        let validated_path = validate_file(file_path)?;
+        if !is_audio_file(&validated_path) {
+            let ext = validated_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("unknown");
+            return Err(FileError::UnsupportedFormat(ext.to_string()).into());
+        }
+
+        let tag = audiotags::Tag::new()
+            .read_from_path(&validated_path)
+            .map_err(|e| FileError::MetadataRead(e.to_string()))?;
+        let picture = tag
+            .album_cover()
+            .ok_or_else(|| FileError::NoAlbumArt(file_path.to_string()))?;
+
+        Ok((
+            picture.data.to_vec(),
+            Self::picture_mime_type(picture.mime_type),
+        ))
+    }
+
+    /// Shows a file in the system file manager, selecting it where supported
+    ///
+    /// Explorer and Finder open the containing folder with the file selected; on
+    /// Linux `xdg-open` opens the parent directory.
+    #[instrument(skip_all, fields(path = %path))]
+    pub fn reveal_in_file_manager(path: &str) -> Result<(), AppError> {
+        let path = validate_path(path)?;
+        let (program, args) = Self::reveal_command(&path);
+        let mut child = std::process::Command::new(program)
+            .args(&args)
+            .spawn()
+            .map_err(|e| FileError::RevealFailed(format!("{}: {}", program, e)))?;
+        // Reap the process so it doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
+
+        tracing::info!("📂 Revealed {} in file manager", path.display());
+        Ok(())
+    }
+
+    /// Program and arguments that reveal `path` in the file manager
+    #[cfg(windows)]
+    fn reveal_command(path: &Path) -> (&'static str, Vec<OsString>) {
+        // Explorer expects the switch and the path as a single argument
+        let mut select = OsString::from("/select,");
+        select.push(path);
+        ("explorer", vec![select])
+    }
+
+    /// Program and arguments that reveal `path` in the file manager
+    #[cfg(target_os = "macos")]
+    fn reveal_command(path: &Path) -> (&'static str, Vec<OsString>) {
+        ("open", vec!["-R".into(), path.into()])
+    }
+
+    /// Program and arguments that reveal `path` in the file manager
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn reveal_command(path: &Path) -> (&'static str, Vec<OsString>) {
+        let folder = if path.is_dir() {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        ("xdg-open", vec![folder.into()])
+    }
+
+    /// Deletes an audio file, returning the bytes freed
+    ///
+    /// With `to_trash` the file goes to the OS trash and can be restored; otherwise
+    /// it is removed permanently. Callers confine `path` to the allowed roots.
+    #[instrument(skip_all, fields(path = %path, to_trash))]
+    pub fn delete_track(path: &str, to_trash: bool) -> Result<u64, AppError> {
+        let file = validate_file(path)?;
+        if !is_audio_file(&file) {
+            return Err(FileError::UnsupportedFormat(path.to_string()).into());
+        }
+
+        let size = std::fs::metadata(&file)?.len();
+        if to_trash {
+            trash::delete(&file).map_err(|e| FileError::Trash(e.to_string()))?;
+            tracing::info!("🗑️ Moved {} to trash ({} bytes)", file.display(), size);
+        } else {
+            std::fs::remove_file(&file)?;
+            tracing::info!("🗑️ Deleted {} ({} bytes)", file.display(), size);
+        }
+
+        Ok(size)
+    }
+
+    /// Writes a file's embedded album art to disk, e.g. as `folder.jpg`
+    ///
+    /// The extension of `output_path` is replaced with the one matching the image
+    /// type. An existing file is left untouched unless `overwrite` is set, so calling
+    /// this for every track of an album writes the cover once per directory.
+    /// Returns the path of the cover file.
+    #[instrument(skip_all, fields(audio_path = %audio_path, output_path = %output_path))]
+    pub fn save_album_art(
+        audio_path: &str,
+        output_path: &str,
+        overwrite: bool,
+    ) -> Result<String, AppError> {
+        let output = validate_output_path(output_path)?;
+        let (bytes, mime_type) = Self::read_album_art_bytes(audio_path)?;
+
+        let extension = match mime_type {
+            "image/png" => "png",
+            "image/bmp" => "bmp",
+            "image/gif" => "gif",
+            _ => "jpg",
+        };
+        let output = output.with_extension(extension);
+
+        if output.exists() && !overwrite {
+            tracing::debug!("🖼️ Cover already exists, skipping: {}", output.display());
+        } else {
+            std::fs::write(&output, &bytes)?;
+            tracing::info!("🖼️ Saved cover to {}", output.display());
+        }
+
+        Ok(output.to_string_lossy().into_owned())
+    }
+
+    /// Applies the enabled `options` rules to each file's tags
+    ///
+    /// Only changed fields are written, and nothing is written in a dry run.
+    /// Results keep the order of `paths` and each file fails on its own. Emits
+    /// `tag-normalize-progress` after every file.
+    #[instrument(skip_all, fields(file_count = paths.len()))]
+    pub fn normalize_tags(
+        paths: &[String],
+        options: TagNormalizeOptions,
+        app_handle: Option<&AppHandle>,
+    ) -> Vec<Result<TagNormalization, AppError>> {
+        paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let result = Self::normalize_file_tags(path, options);
+                if let Err(e) = &result {
+                    tracing::warn!("🏷️ Could not normalize tags of {}: {}", path, e);
+                }
+                if let Some(app) = app_handle {
+                    let _ = app.emit(
+                        events::TAG_NORMALIZE_PROGRESS,
+                        TagNormalizeProgress {
+                            current: index + 1,
+                            total: paths.len(),
+                            path: path.clone(),
+                        },
+                    );
+                }
+                result
+            })
+            .collect()
+    }
+
+    /// Normalizes one file's tags, writing them back unless it's a dry run
+    fn normalize_file_tags(
+        path: &str,
+        options: TagNormalizeOptions,
+    ) -> Result<TagNormalization, AppError> {
+        let validated_path = validate_file(path)?;
+        if !is_audio_file(&validated_path) {
+            let ext = validated_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("unknown");
+            return Err(FileError::UnsupportedFormat(ext.to_string()).into());
+        }
+        let path_str = validated_path
+            .to_str()
+            .ok_or_else(|| FileError::InvalidPath(path.to_string()))?;
+
+        let mut tag = audiotags::Tag::new()
+            .read_from_path(path_str)
+            .map_err(|e| FileError::MetadataRead(e.to_string()))?;
+
+        let mut changes = Vec::new();
+        for (field, get, set, strip_remaster, move_article) in NORMALIZED_TAG_FIELDS {
+            let Some(before) = get(tag.as_ref()).map(ToString::to_string) else {
+                continue;
+            };
+            let after = Self::normalize_tag_value(&before, options, strip_remaster, move_article);
+            if after.is_empty() || after == before {
+                continue;
+            }
+            if !options.dry_run {
+                set(tag.as_mut(), &after);
+            }
+            changes.push(TagChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+
+        if !changes.is_empty() && !options.dry_run {
+            tag.write_to_path(path_str)
+                .map_err(|e| FileError::TagWrite(format!("{}: {}", path, e)))?;
+            tracing::info!("🏷️ Normalized {} tag(s) of {}", changes.len(), path);
+        }
+
+        Ok(TagNormalization {
+            path: path.to_string(),
+            changes,
+        })
+    }
+
+    /// Applies the enabled rules to one tag value
+    ///
+    /// Title-casing runs before the article is moved so "the beatles" ends up as
+    /// "Beatles, The".
+    fn normalize_tag_value(
+        value: &str,
+        options: TagNormalizeOptions,
+        strip_remaster: bool,
+        move_article: bool,
+    ) -> String {
+        let mut value = value.to_string();
+        if options.trim_whitespace {
+            value = collapse_whitespace(&value);
+        }
+        if options.strip_remaster_suffix && strip_remaster {
+            value = strip_remaster_suffix(&value).to_string();
+        }
+        if options.title_case {
+            value = title_case(&value);
+        }
+        if options.move_leading_article && move_article {
+            value = move_leading_article(&value);
+        }
+        value
+    }
+
+    /// Reads a local image file as a base64 data URL
+    ///
+    /// The type comes from the file's magic bytes, not its extension; anything but
+    /// JPEG, PNG, GIF, WebP or BMP is rejected, as are files over 10 MB or that
+    /// don't decode. Images with a side over [`MAX_IMAGE_DIMENSION`] pixels are
+    /// scaled down and re-encoded (JPEG stays JPEG, the rest become PNG); smaller
+    /// ones are returned as they are.
+    #[instrument(skip_all, fields(path = %path))]
+    pub fn read_image_as_data_url(path: &str) -> Result<String, AppError> {
+        let validated_path = validate_file(path)?;
+
+        let size = std::fs::metadata(&validated_path)?.len();
+        if size > MAX_IMAGE_FILE_BYTES {
+            return Err(AppError::Validation(format!(
+                "Image is larger than {} MB: {}",
+                MAX_IMAGE_FILE_BYTES / (1024 * 1024),
+                path
+            )));
+        }
+
+        let bytes = std::fs::read(&validated_path)?;
+        let mime_type =
+            Self::image_mime_type(&bytes).ok_or_else(|| FileError::NotImage(path.to_string()))?;
+        let (mime_type, bytes) = Self::fit_image(&bytes, mime_type)
+            .map_err(|e| FileError::NotImage(format!("{}: {}", path, e)))?;
+
+        use base64::Engine;
+        let base64_data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        Ok(format!("data:{};base64,{}", mime_type, base64_data))
+    }
+
+    /// Scales an image down to [`MAX_IMAGE_DIMENSION`], keeping its aspect ratio
+    ///
+    /// Returns the bytes untouched when the image is small enough.
+    fn fit_image(
+        bytes: &[u8],
+        mime_type: &'static str,
+    ) -> Result<(&'static str, Vec<u8>), image::ImageError> {
+        let decoded = image::load_from_memory(bytes)?;
+        if decoded.width() <= MAX_IMAGE_DIMENSION && decoded.height() <= MAX_IMAGE_DIMENSION {
+            return Ok((mime_type, bytes.to_vec()));
+        }
+
+        let resized = decoded.resize(
+            MAX_IMAGE_DIMENSION,
+            MAX_IMAGE_DIMENSION,
+            image::imageops::FilterType::Lanczos3,
+        );
+        let (mime_type, format) = match mime_type {
+            "image/jpeg" => ("image/jpeg", image::ImageFormat::Jpeg),
+            _ => ("image/png", image::ImageFormat::Png),
+        };
+
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        resized.write_to(&mut encoded, format)?;
+        Ok((mime_type, encoded.into_inner()))
+    }
+
+    /// Detects an image's mime type from its first bytes
+    fn image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+        match bytes {
+            [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+            [b'B', b'M', ..] => Some("image/bmp"),
+            _ => None,
+        }
+    }
+
+    /// Maps a tag picture type to its mime type
+    fn picture_mime_type(mime_type: audiotags::MimeType) -> &'static str {
+        match mime_type {
+            audiotags::MimeType::Jpeg => "image/jpeg",
+            audiotags::MimeType::Png => "image/png",
+            audiotags::MimeType::Bmp => "image/bmp",
+            audiotags::MimeType::Gif => "image/gif",
+            _ => "image/jpeg", // fallback
+        }
+    }
+
     /// Extracts album art from audio tag and converts to base64 data URL
-    fn extract_album_art(tag: &Box<dyn audiotags::AudioTag + Send + Sync>) -> Option<String> {
+    fn extract_album_art(tag: &(dyn audiotags::AudioTag + Send + Sync)) -> Option<String> {
         // Try to get album cover
         if let Some(picture) = tag.album_cover() {
             // Convert image data to base64 data URL
-            let mime_type = match picture.mime_type {
-                audiotags::MimeType::Jpeg => "image/jpeg",
-                audiotags::MimeType::Png => "image/png",
-                audiotags::MimeType::Bmp => "image/bmp",
-                audiotags::MimeType::Gif => "image/gif",
-                _ => "image/jpeg", // fallback
-            };
+            let mime_type = Self::picture_mime_type(picture.mime_type);
 
             use base64::Engine;
-            let base64_data = base64::engine::general_purpose::STANDARD.encode(&picture.data);
+            let base64_data = base64::engine::general_purpose::STANDARD.encode(picture.data);
             Some(format!("data:{};base64,{}", mime_type, base64_data))
         } else {
             None
@@ -237,20 +1559,1240 @@ impl FileService {
     fn create_fallback_metadata(path: &Path, file_path: &str) -> Result<MusicFile, AppError> {
         let title = path
             .file_stem()
-            .and_then(|s| s.to_str())
-            .map(|s| Self::clean_filename_for_title(s));
+            .map(|s| Self::clean_filename_for_title(&s.to_string_lossy()));
 
         let artist = Self::extract_artist_from_filename(path);
+        let artists = split_artists(artist.as_deref());
 
         Ok(MusicFile {
             path: file_path.to_string(),
             title,
             artist,
+            artists,
             album: None,
             duration: None,
             year: None,
             genre: None,
+            track_number: None,
             album_art: None,
+            replaygain_track_gain: None,
+            replaygain_track_peak: None,
         })
     }
+
+    /// Computes `(min, max)` amplitude pairs for drawing a waveform overview
+    ///
+    /// Channels are mixed down to mono. `bucket_count` is clamped to 1..=4096; short
+    /// files may yield fewer buckets. Results are cached per path and modification time.
+    #[instrument(skip_all, fields(path = %file_path, bucket_count))]
+    pub fn compute_waveform_peaks(
+        file_path: &str,
+        bucket_count: usize,
+    ) -> Result<Vec<(f32, f32)>, AppError> {
+        let path = validate_file(file_path)?;
+        let bucket_count = bucket_count.clamp(1, MAX_WAVEFORM_BUCKETS);
+        let modified = std::fs::metadata(&path)?.modified()?;
+        let key = (path, modified, bucket_count);
+
+        if let Some(peaks) = Self::waveform_cache()?.get(&key) {
+            return Ok(peaks.clone());
+        }
+
+        let chunks = Self::decode_chunk_peaks(&key.0)?;
+        let peaks = Self::downsample_peaks(&chunks, bucket_count);
+
+        let mut cache = Self::waveform_cache()?;
+        if cache.len() >= WAVEFORM_CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(key, peaks.clone());
+
+        Ok(peaks)
+    }
+
+    /// Locks the waveform cache
+    fn waveform_cache() -> Result<MutexGuard<'static, WaveformCache>, AppError> {
+        WAVEFORM_CACHE
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("Waveform cache mutex poisoned: {}", e)))
+    }
+
+    /// Decodes the file's default track into one mono min/max pair per
+    /// `WAVEFORM_CHUNK_FRAMES` frames
+    fn decode_chunk_peaks(path: &Path) -> Result<Vec<(f32, f32)>, AppError> {
+        let decode_error =
+            |e: SymphoniaError| FileError::Decode(format!("{}: {}", path.display(), e));
+
+        let source =
+            MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+
+        let mut format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                source,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(decode_error)?
+            .format;
+        let track = format
+            .default_track()
+            .ok_or_else(|| FileError::Decode(format!("{}: no audio track", path.display())))?;
+        let track_id = track.id;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(decode_error)?;
+
+        let mut chunks = Vec::new();
+        let (mut low, mut high, mut frames) = (0.0f32, 0.0f32, 0usize);
+
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(SymphoniaError::ResetRequired) => break,
+                Err(e) => return Err(decode_error(e).into()),
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupt packet only costs a gap in the waveform
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(e) => return Err(decode_error(e).into()),
+            };
+
+            let spec = *decoded.spec();
+            let channels = spec.channels.count().max(1);
+            let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            samples.copy_interleaved_ref(decoded);
+
+            for frame in samples.samples().chunks(channels) {
+                let value = frame.iter().sum::<f32>() / channels as f32;
+                low = low.min(value);
+                high = high.max(value);
+                frames += 1;
+
+                if frames == WAVEFORM_CHUNK_FRAMES {
+                    chunks.push((low, high));
+                    (low, high, frames) = (0.0, 0.0, 0);
+                }
+            }
+        }
+
+        if frames > 0 {
+            chunks.push((low, high));
+        }
+
+        Ok(chunks)
+    }
+
+    /// Merges chunk peaks into `bucket_count` evenly sized buckets
+    fn downsample_peaks(chunks: &[(f32, f32)], bucket_count: usize) -> Vec<(f32, f32)> {
+        if chunks.len() <= bucket_count {
+            return chunks.to_vec();
+        }
+
+        (0..bucket_count)
+            .map(|i| {
+                let start = i * chunks.len() / bucket_count;
+                let end = ((i + 1) * chunks.len() / bucket_count).max(start + 1);
+                chunks[start..end]
+                    .iter()
+                    .fold((0.0f32, 0.0f32), |(low, high), &(min, max)| {
+                        (low.min(min), high.max(max))
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    use crate::test_support::write_silent_mp3;
+
+    #[test]
+    fn playlist_entries_outside_roots_are_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&library).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(library.join("inside.mp3"), b"").unwrap();
+        fs::write(outside.join("secret.mp3"), b"").unwrap();
+        let playlist = library.join("mix.m3u");
+        fs::write(&playlist, "#EXTM3U\ninside.mp3\n../outside/secret.mp3\n").unwrap();
+        let roots = vec![canonical_path(&library).unwrap()];
+
+        let imported = FileService::import_playlist(&playlist.to_string_lossy(), &roots).unwrap();
+
+        assert_eq!(imported.tracks.len(), 1);
+        assert!(imported.tracks[0].path.ends_with("inside.mp3"));
+        assert_eq!(imported.missing, vec!["../outside/secret.mp3".to_string()]);
+    }
+
+    #[test]
+    fn create_playlist_preserves_track_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = ["charlie.mp3", "alpha.mp3", "bravo.mp3"];
+        for name in names {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        let roots = vec![canonical_path(dir.path()).unwrap()];
+        let paths: Vec<String> = names
+            .iter()
+            .map(|name| dir.path().join(name).to_string_lossy().into_owned())
+            .collect();
+
+        let written =
+            FileService::create_playlist("mix", &dir.path().to_string_lossy(), &paths, &roots)
+                .unwrap();
+
+        let content = fs::read_to_string(written).unwrap();
+        let entries: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert_eq!(entries.len(), names.len());
+        for (entry, name) in entries.iter().zip(names) {
+            assert!(entry.ends_with(name), "{} should end with {}", entry, name);
+        }
+    }
+
+    #[test]
+    fn create_playlist_rejects_tracks_outside_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&library).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(library.join("inside.mp3"), b"").unwrap();
+        fs::write(outside.join("secret.mp3"), b"").unwrap();
+        let roots = vec![canonical_path(&library).unwrap()];
+        let paths = vec![
+            library.join("inside.mp3").to_string_lossy().into_owned(),
+            outside.join("secret.mp3").to_string_lossy().into_owned(),
+        ];
+
+        let result =
+            FileService::create_playlist("mix", &library.to_string_lossy(), &paths, &roots);
+
+        assert!(result.is_err());
+        assert!(!library.join("mix.m3u8").exists());
+    }
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        image::RgbaImage::from_pixel(width, height, image::Rgba([200, 30, 30, 255]))
+            .save_with_format(path, image::ImageFormat::Png)
+            .unwrap();
+    }
+
+    fn decode_data_url(data_url: &str) -> (String, image::DynamicImage) {
+        use base64::Engine;
+        let (header, data) = data_url.split_once(";base64,").unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .unwrap();
+        let mime_type = header.trim_start_matches("data:").to_string();
+        (mime_type, image::load_from_memory(&bytes).unwrap())
+    }
+
+    #[test]
+    fn reads_a_small_image_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let cover = dir.path().join("cover.png");
+        write_png(&cover, 40, 30);
+
+        let data_url = FileService::read_image_as_data_url(&cover.to_string_lossy()).unwrap();
+
+        let (mime_type, image) = decode_data_url(&data_url);
+        assert_eq!(mime_type, "image/png");
+        assert_eq!((image.width(), image.height()), (40, 30));
+    }
+
+    #[test]
+    fn scales_large_images_down() {
+        let dir = tempfile::tempdir().unwrap();
+        // The extension doesn't matter, the magic bytes do
+        let cover = dir.path().join("cover.dat");
+        write_png(&cover, 2048, 1024);
+
+        let data_url = FileService::read_image_as_data_url(&cover.to_string_lossy()).unwrap();
+
+        let (mime_type, image) = decode_data_url(&data_url);
+        assert_eq!(mime_type, "image/png");
+        assert_eq!((image.width(), image.height()), (MAX_IMAGE_DIMENSION, 512));
+    }
+
+    #[test]
+    fn rejects_text_and_corrupt_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.png");
+        fs::write(&notes, "just some text").unwrap();
+        let truncated = dir.path().join("truncated.png");
+        fs::write(&truncated, b"\x89PNG\r\n\x1a\n not really").unwrap();
+
+        for path in [notes, truncated] {
+            assert!(matches!(
+                FileService::read_image_as_data_url(&path.to_string_lossy()),
+                Err(AppError::File(FileError::NotImage(_)))
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lists_and_reads_non_unicode_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        use crate::utils::{path_from_string, NATIVE_PATH_PREFIX};
+
+        let dir = tempfile::tempdir().unwrap();
+        // Latin-1 "café.mp3", as old rips often have
+        let file = dir.path().join(OsStr::from_bytes(b"caf\xe9.mp3"));
+        if fs::write(&file, b"not really audio").is_err() {
+            // Some filesystems only allow UTF-8 names
+            return;
+        }
+
+        let tracks =
+            FileService::scan_music_folder(&dir.path().to_string_lossy(), false, None, None)
+                .unwrap();
+
+        assert_eq!(tracks.len(), 1);
+        let path = &tracks[0].path;
+        assert!(path.starts_with(NATIVE_PATH_PREFIX), "{}", path);
+        assert_eq!(
+            path_from_string(path).unwrap(),
+            canonical_path(&file).unwrap()
+        );
+        assert_eq!(FileService::get_audio_metadata(path).unwrap().path, *path);
+        assert_eq!(
+            FileService::read_audio_bytes(path).unwrap(),
+            b"not really audio"
+        );
+    }
+
+    fn tagged(title: &str, artist: &str, album: &str, genre: &str) -> MusicFile {
+        let mut file = MusicFile::new(format!("/music/{}.mp3", title), Some(title.to_string()));
+        file.artist = Some(artist.to_string());
+        file.album = Some(album.to_string());
+        file.genre = Some(genre.to_string());
+        file
+    }
+
+    fn titles(files: &[MusicFile]) -> Vec<&str> {
+        files.iter().filter_map(|f| f.title.as_deref()).collect()
+    }
+
+    #[test]
+    fn filter_tracks_folds_accents_and_searches_every_field() {
+        let library = [
+            tagged("Jóga", "Björk", "Homogenic", "Electronic"),
+            tagged("Yellow", "Coldplay", "Parachutes", "Rock"),
+            tagged("Clocks", "Coldplay", "A Rush of Blood", "Électro Rock"),
+        ];
+
+        let filter = |query, field| FileService::filter_tracks(&library, query, field);
+
+        assert_eq!(titles(&filter("BJORK", None)), ["Jóga"]);
+        assert_eq!(titles(&filter("joga", None)), ["Jóga"]);
+        // Artist, album and genre all match without a field
+        assert_eq!(titles(&filter("coldplay", None)), ["Yellow", "Clocks"]);
+        assert_eq!(titles(&filter("parachutes", None)), ["Yellow"]);
+        assert_eq!(titles(&filter("electro", None)), ["Jóga", "Clocks"]);
+        // A field restricts the match
+        assert_eq!(
+            titles(&filter("rock", Some(SearchField::Genre))),
+            ["Yellow", "Clocks"]
+        );
+        assert!(filter("rock", Some(SearchField::Title)).is_empty());
+        assert_eq!(filter("  ", None).len(), library.len());
+    }
+
+    #[test]
+    fn sort_tracks_puts_missing_values_last_for_every_key() {
+        let mut full = tagged("Álbum", "Ábba", "Ágora", "Pop");
+        full.year = Some(1999);
+        full.duration = Some(200);
+        full.track_number = Some(2);
+        let mut other = tagged("beta", "beta", "beta", "Pop");
+        other.year = Some(1980);
+        other.duration = Some(100);
+        other.track_number = Some(1);
+        let empty = MusicFile::new("/music/empty.mp3".to_string(), None);
+        let library = vec![empty, full, other];
+
+        let keys = [
+            SortKey::Title,
+            SortKey::Artist,
+            SortKey::Album,
+            SortKey::Year,
+            SortKey::Duration,
+            SortKey::TrackNumber,
+        ];
+        for key in keys {
+            let paths = |ascending| {
+                FileService::sort_tracks(library.clone(), key, ascending)
+                    .into_iter()
+                    .map(|f| f.path)
+                    .collect::<Vec<_>>()
+            };
+            // Accents are ignored, so "Á..." sorts before "beta"
+            let (first, second) = match key {
+                SortKey::Title | SortKey::Artist | SortKey::Album => ("Álbum", "beta"),
+                _ => ("beta", "Álbum"),
+            };
+            let path = |title| format!("/music/{}.mp3", title);
+
+            assert_eq!(
+                paths(true),
+                [path(first), path(second), path("empty")],
+                "{:?}",
+                key
+            );
+            assert_eq!(
+                paths(false),
+                [path(second), path(first), path("empty")],
+                "{:?}",
+                key
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn reveal_selects_the_file_in_explorer() {
+        let path = Path::new(r"C:\Music\My Song.mp3");
+        let (program, args) = FileService::reveal_command(path);
+        assert_eq!(program, "explorer");
+        assert_eq!(args, [OsString::from(r"/select,C:\Music\My Song.mp3")]);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn reveal_selects_the_file_in_finder() {
+        let path = Path::new("/Music/My Song.mp3");
+        let (program, args) = FileService::reveal_command(path);
+        assert_eq!(program, "open");
+        assert_eq!(args, [OsString::from("-R"), OsString::from(path)]);
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    #[test]
+    fn reveal_opens_the_parent_folder_with_xdg_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("My Song.mp3");
+        fs::write(&file, b"").unwrap();
+
+        let (program, args) = FileService::reveal_command(&file);
+        assert_eq!(program, "xdg-open");
+        assert_eq!(args, [OsString::from(dir.path())]);
+        // A folder is opened itself
+        let (_, args) = FileService::reveal_command(dir.path());
+        assert_eq!(args, [OsString::from(dir.path())]);
+    }
+
+    /// A scan control without an app, registered in `state`
+    fn scan_control(state: &ScanState) -> ScanControl<'static> {
+        let (id, cancelled) = state.begin();
+        ScanControl {
+            app_handle: None,
+            id,
+            cancelled,
+            processed: AtomicUsize::new(0),
+        }
+    }
+
+    #[test]
+    fn cancelling_stops_a_scan_partway() {
+        let dir = tempfile::tempdir().unwrap();
+        let total = 2000;
+        for i in 0..total {
+            fs::write(dir.path().join(format!("{:04} - Song.mp3", i)), b"").unwrap();
+        }
+
+        let state = ScanState::default();
+        let control = scan_control(&state);
+        let paths =
+            FileService::collect_audio_paths(dir.path(), false, total, None, &control).unwrap();
+        assert_eq!(paths.len(), total);
+
+        // Cancel as soon as the first file is parsed
+        let result = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                while control.processed.load(Ordering::SeqCst) == 0 {
+                    std::hint::spin_loop();
+                }
+                assert!(state.cancel(control.id));
+            });
+            FileService::extract_metadata_parallel(&paths, "", &control)
+        });
+        assert!(matches!(
+            result,
+            Err(AppError::File(FileError::ScanCancelled))
+        ));
+        let processed = control.processed.load(Ordering::SeqCst);
+        assert!(processed > 0 && processed < total, "{}", processed);
+
+        // The walk stops too
+        assert!(matches!(
+            FileService::collect_audio_paths(dir.path(), false, total, None, &control),
+            Err(AppError::File(FileError::ScanCancelled))
+        ));
+    }
+
+    #[test]
+    fn cancelling_a_scan_leaves_the_others_running() {
+        let state = ScanState::default();
+        let first = scan_control(&state);
+        let second = scan_control(&state);
+
+        assert!(state.cancel(first.id));
+        // A scan starting after the cancel doesn't clear it
+        let third = scan_control(&state);
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+        assert!(!third.is_cancelled());
+
+        // A finished scan can't be cancelled anymore
+        state.finish(second.id);
+        assert!(!state.cancel(second.id));
+        assert!(!second.is_cancelled());
+
+        state.cancel_all();
+        assert!(third.is_cancelled());
+    }
+
+    #[test]
+    fn deleted_tracks_are_removed_or_trashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let removed = dir.path().join("removed.mp3");
+        let trashed = dir.path().join("trashed.mp3");
+        fs::write(&removed, [0u8; 1234]).unwrap();
+        fs::write(&trashed, [0u8; 42]).unwrap();
+
+        let freed = FileService::delete_track(&removed.to_string_lossy(), false).unwrap();
+        assert_eq!(freed, 1234);
+        assert!(!removed.exists());
+
+        // The trash can lives under HOME, which other tests change
+        let _env = crate::utils::path::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match FileService::delete_track(&trashed.to_string_lossy(), true) {
+            Ok(freed) => {
+                assert_eq!(freed, 42);
+                assert!(!trashed.exists());
+                // Unlike a permanent delete, the file can be restored
+                #[cfg(not(target_os = "macos"))]
+                {
+                    let parent = canonical_path(dir.path()).unwrap();
+                    let items: Vec<_> = trash::os_limited::list()
+                        .unwrap()
+                        .into_iter()
+                        .filter(|item| item.original_parent == parent)
+                        .collect();
+                    assert_eq!(items.len(), 1);
+                    trash::os_limited::restore_all(items).unwrap();
+                    assert_eq!(fs::read(&trashed).unwrap().len(), 42);
+                }
+            }
+            // No trash can on this machine: the file must be left alone
+            Err(AppError::File(FileError::Trash(_))) => assert!(trashed.exists()),
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn only_existing_audio_files_can_be_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, "keep me").unwrap();
+
+        for to_trash in [false, true] {
+            assert!(matches!(
+                FileService::delete_track(&notes.to_string_lossy(), to_trash),
+                Err(AppError::File(FileError::UnsupportedFormat(_)))
+            ));
+            let missing = dir.path().join("missing.mp3");
+            assert!(FileService::delete_track(&missing.to_string_lossy(), to_trash).is_err());
+        }
+        assert!(notes.exists());
+    }
+
+    fn scanned_names(folder: &Path, follow_symlinks: bool) -> Vec<String> {
+        let mut names: Vec<String> =
+            FileService::scan_music_folder(&folder.to_string_lossy(), follow_symlinks, None, None)
+                .unwrap()
+                .iter()
+                .map(|file| {
+                    Path::new(&file.path)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect();
+        names.sort();
+        names
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_followed_only_on_request_and_loops_end() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let elsewhere = dir.path().join("elsewhere");
+        fs::create_dir_all(library.join("album")).unwrap();
+        fs::create_dir_all(&elsewhere).unwrap();
+        fs::write(library.join("album").join("own.mp3"), b"").unwrap();
+        fs::write(elsewhere.join("linked.mp3"), b"").unwrap();
+        // Two links to the same folder, and a link back to the library
+        symlink(&elsewhere, library.join("link")).unwrap();
+        symlink(&elsewhere, library.join("album").join("again")).unwrap();
+        symlink(&library, library.join("album").join("loop")).unwrap();
+
+        assert_eq!(scanned_names(&library, false), ["own.mp3"]);
+        assert_eq!(scanned_names(&library, true), ["linked.mp3", "own.mp3"]);
+    }
+
+    #[tokio::test]
+    async fn metadata_batch_fails_bad_entries_on_their_own() {
+        let dir = tempfile::tempdir().unwrap();
+        let song = dir.path().join("Artist - Song.mp3");
+        let other = dir.path().join("Other.flac");
+        let notes = dir.path().join("notes.txt");
+        for path in [&song, &other, &notes] {
+            fs::write(path, b"").unwrap();
+        }
+        let path = |path: &Path| path.to_string_lossy().into_owned();
+
+        let results = FileService::get_audio_metadata_batch(vec![
+            path(&song),
+            path(&dir.path().join("missing.mp3")),
+            path(&notes),
+            path(
+                &dir.path()
+                    .join("..")
+                    .join(dir.path().file_name().unwrap())
+                    .join("Other.flac"),
+            ),
+            path(&other),
+        ])
+        .await;
+
+        assert_eq!(results.len(), 5);
+        let song = results[0].as_ref().unwrap();
+        assert_eq!(song.title.as_deref(), Some("Artist - Song"));
+        assert_eq!(song.artist.as_deref(), Some("Artist"));
+        assert!(results[1].is_err());
+        assert!(matches!(
+            results[2],
+            Err(AppError::File(FileError::UnsupportedFormat(_)))
+        ));
+        assert!(matches!(
+            results[3],
+            Err(AppError::File(FileError::PathTraversal(_)))
+        ));
+        assert_eq!(results[4].as_ref().unwrap().title.as_deref(), Some("Other"));
+    }
+
+    #[test]
+    fn multi_folder_scan_merges_folders_and_reports_bad_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let rock = dir.path().join("rock");
+        let jazz = dir.path().join("jazz");
+        fs::create_dir_all(rock.join("nested")).unwrap();
+        fs::create_dir_all(&jazz).unwrap();
+        fs::write(rock.join("one.mp3"), b"").unwrap();
+        fs::write(rock.join("nested").join("two.mp3"), b"").unwrap();
+        fs::write(jazz.join("three.flac"), b"").unwrap();
+        let missing = dir.path().join("missing").to_string_lossy().into_owned();
+        let folders = vec![
+            rock.to_string_lossy().into_owned(),
+            missing.clone(),
+            jazz.to_string_lossy().into_owned(),
+            // Files reached through another folder are kept once
+            rock.join("nested").to_string_lossy().into_owned(),
+        ];
+
+        let (files, failed) = FileService::scan_music_folders(&folders, false, None).unwrap();
+
+        let mut names: Vec<_> = files
+            .iter()
+            .map(|file| file.title.clone().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["one", "three", "two"]);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, missing);
+        assert!(matches!(
+            failed[0].1,
+            AppError::File(FileError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn imports_m3u_and_pls_playlists() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = canonical_path(dir.path()).unwrap();
+        fs::create_dir_all(library.join("Album")).unwrap();
+        for name in ["Album/01 - First.mp3", "Second.flac", "Third.ogg"] {
+            fs::write(library.join(name), b"").unwrap();
+        }
+        let absolute = library.join("Second.flac").to_string_lossy().into_owned();
+        let url = format!("file://{}", library.join("Third.ogg").to_string_lossy());
+        let roots = vec![library.clone()];
+
+        let m3u = library.join("mix.m3u8");
+        fs::write(
+            &m3u,
+            format!(
+                "\u{feff}#EXTM3U\n#EXTINF:180,First\nAlbum/01 - First.mp3\n\n{}\n{}\n\
+                 http://radio.example/stream\nGone.mp3\n",
+                absolute, url
+            ),
+        )
+        .unwrap();
+        let imported = FileService::import_playlist(&m3u.to_string_lossy(), &roots).unwrap();
+        let titles: Vec<_> = imported
+            .tracks
+            .iter()
+            .map(|track| track.title.as_deref().unwrap())
+            .collect();
+        assert_eq!(titles, ["First", "Second", "Third"]);
+        assert_eq!(imported.tracks[1].path, absolute);
+        assert_eq!(
+            imported.missing,
+            ["http://radio.example/stream", "Gone.mp3"]
+        );
+
+        // PLS entries follow their number, not their line
+        let pls = library.join("mix.pls");
+        fs::write(
+            &pls,
+            "[playlist]\nFile2=Second.flac\nTitle2=Second\nFile1=Album/01 - First.mp3\n\
+             NumberOfEntries=2\nVersion=2\n",
+        )
+        .unwrap();
+        let imported = FileService::import_playlist(&pls.to_string_lossy(), &roots).unwrap();
+        let titles: Vec<_> = imported
+            .tracks
+            .iter()
+            .map(|track| track.title.as_deref().unwrap())
+            .collect();
+        assert_eq!(titles, ["First", "Second"]);
+        assert!(imported.missing.is_empty());
+    }
+
+    /// Writes a silent MP3 carrying an ID3v2 tag filled in by `edit`
+    fn write_tagged_mp3(path: &Path, edit: impl FnOnce(&mut lofty::tag::Tag)) {
+        use lofty::config::WriteOptions;
+        use lofty::tag::{Tag, TagExt, TagType};
+
+        write_silent_mp3(path);
+        let mut tag = Tag::new(TagType::Id3v2);
+        edit(&mut tag);
+        tag.save_to_path(path, WriteOptions::default()).unwrap();
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::from_pixel(width, height, image::Rgba([30, 30, 200, 255]))
+            .write_to(&mut bytes, image::ImageFormat::Png)
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    fn with_cover(cover: &[u8]) -> impl FnOnce(&mut lofty::tag::Tag) + '_ {
+        use lofty::picture::{MimeType, Picture, PictureType};
+
+        move |tag| {
+            tag.push_picture(Picture::new_unchecked(
+                PictureType::CoverFront,
+                Some(MimeType::Png),
+                None,
+                cover.to_vec(),
+            ))
+        }
+    }
+
+    #[test]
+    fn album_art_bytes_are_the_embedded_picture() {
+        let dir = tempfile::tempdir().unwrap();
+        let cover = png_bytes(8, 8);
+        let with_art = dir.path().join("with art.mp3");
+        let without_art = dir.path().join("without art.mp3");
+        write_tagged_mp3(&with_art, with_cover(&cover));
+        write_tagged_mp3(&without_art, |tag| {
+            lofty::tag::Accessor::set_title(tag, "Bare".to_string())
+        });
+
+        let (bytes, mime_type) =
+            FileService::read_album_art_bytes(&with_art.to_string_lossy()).unwrap();
+        assert_eq!(bytes, cover);
+        assert_eq!(mime_type, "image/png");
+        assert!(matches!(
+            FileService::read_album_art_bytes(&without_art.to_string_lossy()),
+            Err(AppError::File(FileError::NoAlbumArt(_)))
+        ));
+    }
+
+    #[test]
+    fn saved_album_art_is_the_embedded_cover() {
+        let dir = tempfile::tempdir().unwrap();
+        let cover = png_bytes(8, 8);
+        let track = dir.path().join("track.mp3");
+        write_tagged_mp3(&track, with_cover(&cover));
+        let track = track.to_string_lossy();
+        let requested = dir.path().join("folder.jpg");
+
+        // The extension follows the image type
+        let saved =
+            FileService::save_album_art(&track, &requested.to_string_lossy(), false).unwrap();
+        assert_eq!(saved, dir.path().join("folder.png").to_string_lossy());
+        assert_eq!(fs::read(&saved).unwrap(), cover);
+
+        // An existing cover is only replaced with `overwrite`
+        fs::write(&saved, b"older cover").unwrap();
+        FileService::save_album_art(&track, &requested.to_string_lossy(), false).unwrap();
+        assert_eq!(fs::read(&saved).unwrap(), b"older cover");
+        FileService::save_album_art(&track, &requested.to_string_lossy(), true).unwrap();
+        assert_eq!(fs::read(&saved).unwrap(), cover);
+    }
+
+    #[test]
+    fn multi_genre_tags_count_towards_each_genre() {
+        let mut untagged = tagged("Silence", "Nobody", "None", "");
+        untagged.genre = None;
+        let library = [
+            tagged("One", "A", "X", "Rock; Pop"),
+            tagged("Two", "A", "X", "rock/Électro, ROCK"),
+            tagged("Three", "A", "X", "electro"),
+            tagged("Four", "A", "X", " ; / "),
+            untagged,
+        ];
+
+        let groups = FileService::group_by_genre(&library);
+        let expected: BTreeMap<String, usize> = [
+            ("Rock".to_string(), 2),
+            ("Pop".to_string(), 1),
+            ("Électro".to_string(), 2),
+            (UNKNOWN_GENRE.to_string(), 2),
+        ]
+        .into();
+        assert_eq!(groups, expected);
+
+        assert_eq!(
+            titles(&FileService::filter_by_genre(&library, " ELECTRO ")),
+            ["Two", "Three"]
+        );
+        assert_eq!(
+            titles(&FileService::filter_by_genre(&library, UNKNOWN_GENRE)),
+            ["Four", "Silence"]
+        );
+        assert!(FileService::filter_by_genre(&library, "Jazz").is_empty());
+    }
+
+    #[test]
+    fn year_histogram_counts_decades_and_implausible_years() {
+        let library: Vec<MusicFile> = [
+            Some(1999),
+            Some(1990),
+            Some(2001),
+            Some(1969),
+            None,
+            Some(0),
+            Some(1899),
+            Some(3000),
+        ]
+        .into_iter()
+        .map(|year| {
+            let mut file = tagged("Song", "Artist", "Album", "Pop");
+            file.year = year;
+            file
+        })
+        .collect();
+
+        let histogram = FileService::year_histogram(&library);
+        assert_eq!(histogram.decades, [(1960, 1), (1990, 2), (2000, 1)]);
+        assert_eq!(histogram.unknown, 4);
+        assert!(FileService::year_histogram(&[]).decades.is_empty());
+    }
+
+    #[test]
+    fn lrc_sidecar_wins_over_embedded_lyrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let track = dir.path().join("track.mp3");
+        write_tagged_mp3(&track, |tag| {
+            tag.insert_text(lofty::tag::ItemKey::Lyrics, "Embedded words".to_string());
+        });
+        let lyrics = || {
+            FileService::get_lyrics(&track.to_string_lossy())
+                .unwrap()
+                .unwrap()
+        };
+
+        let embedded = lyrics();
+        assert_eq!(embedded.text, "Embedded words");
+        assert!(matches!(embedded.source, LyricsSource::Embedded));
+
+        // A BOM and Latin-1 text, as older .lrc files often have
+        fs::write(
+            dir.path().join("track.lrc"),
+            b"\xef\xbb\xbf[00:01.00]Caf\xe9 words\n",
+        )
+        .unwrap();
+        let sidecar = lyrics();
+        assert_eq!(sidecar.text, "[00:01.00]Caf\u{fffd} words");
+        assert!(matches!(sidecar.source, LyricsSource::Lrc));
+
+        // No lyrics at all
+        let bare = dir.path().join("bare.mp3");
+        write_silent_mp3(&bare);
+        assert!(FileService::get_lyrics(&bare.to_string_lossy())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn multi_value_artist_tags_are_split() {
+        let dir = tempfile::tempdir().unwrap();
+        let track = dir.path().join("track.mp3");
+        // ID3v2.4 stores several artists as NUL-separated values of one frame
+        write_tagged_mp3(&track, |tag| {
+            tag.insert_text(
+                lofty::tag::ItemKey::TrackArtist,
+                "Simon & Garfunkel\0Paul Simon feat. Art Garfunkel".to_string(),
+            );
+        });
+
+        let file = FileService::get_audio_metadata(&track.to_string_lossy()).unwrap();
+        assert_eq!(
+            file.artist.as_deref(),
+            Some("Simon & Garfunkel, Paul Simon feat. Art Garfunkel")
+        );
+        assert_eq!(
+            file.artists,
+            ["Simon & Garfunkel", "Paul Simon", "Art Garfunkel"]
+        );
+    }
+
+    #[test]
+    fn files_over_the_size_limit_are_skipped_unread() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("small.mp3"), [0u8; 1024]).unwrap();
+        // Sparse, so it takes no real disk space
+        fs::File::create(dir.path().join("huge set.mp3"))
+            .unwrap()
+            .set_len(5 * 1024 * 1024 * 1024)
+            .unwrap();
+
+        let scanned =
+            FileService::scan_music_folder(&dir.path().to_string_lossy(), false, Some(1024), None)
+                .unwrap();
+        assert_eq!(titles(&scanned), ["small"]);
+    }
+
+    fn spotify_track(name: &str, artists: &[&str], duration_secs: u32) -> SpotifyTrack {
+        SpotifyTrack {
+            id: None,
+            name: name.to_string(),
+            artists: artists.iter().map(ToString::to_string).collect(),
+            album: "Album".to_string(),
+            album_image: None,
+            duration_ms: duration_secs * 1000,
+            popularity: None,
+            preview_url: None,
+            external_url: None,
+        }
+    }
+
+    fn local_track(title: &str, artist: &str, duration: Option<u32>) -> MusicFile {
+        let mut file = tagged(title, artist, "Album", "Rock");
+        file.path = format!("/music/{} - {:?}.mp3", title, duration);
+        file.duration = duration;
+        file
+    }
+
+    #[test]
+    fn spotify_tracks_match_near_identical_local_files() {
+        let library = [
+            local_track("Hey Jude (Remastered 2015)", "The Beatles", Some(431)),
+            local_track("Hey Jude", "The Beatles", Some(300)),
+            local_track("Let It Be - Live", "the beatles; Billy Preston", Some(245)),
+            local_track("Yesterday", "Paul McCartney", None),
+            local_track("Something", "The Beatles", Some(183)),
+        ];
+        let spotify = [
+            // The closest duration wins over an exact title
+            spotify_track("Hey Jude - Remastered", &["The Beatles"], 429),
+            spotify_track("Let It Be", &["Billy Preston"], 243),
+            // No local duration: title and artist are enough
+            spotify_track("yesterday!", &["Paul McCartney", "Someone"], 125),
+            // Too far off in length, wrong artist, and unknown
+            spotify_track("Something", &["The Beatles"], 190),
+            spotify_track("Hey Jude", &["Wilson Pickett"], 300),
+            spotify_track("Help!", &["The Beatles"], 140),
+        ];
+
+        let matched: Vec<_> = FileService::match_spotify_tracks(&library, &spotify)
+            .into_iter()
+            .map(|found| found.map(|file| file.path))
+            .collect();
+        assert_eq!(
+            matched,
+            [
+                Some(library[0].path.clone()),
+                Some(library[2].path.clone()),
+                Some(library[3].path.clone()),
+                None,
+                None,
+                None,
+            ]
+        );
+
+        let split = FileService::match_spotify_to_library(&library, spotify.to_vec());
+        assert_eq!(split.matched.len(), 3);
+        let unmatched: Vec<_> = split.unmatched.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(unmatched, ["Something", "Hey Jude", "Help!"]);
+    }
+
+    #[test]
+    fn replaygain_is_read_from_the_tags() {
+        use lofty::tag::ItemKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let gained = dir.path().join("gained.mp3");
+        write_tagged_mp3(&gained, |tag| {
+            tag.insert_text(ItemKey::ReplayGainTrackGain, "-7.25 dB".to_string());
+            tag.insert_text(ItemKey::ReplayGainTrackPeak, "0.988553".to_string());
+        });
+        let broken = dir.path().join("broken.mp3");
+        write_tagged_mp3(&broken, |tag| {
+            tag.insert_text(ItemKey::ReplayGainTrackGain, "loud".to_string());
+        });
+
+        let file = FileService::get_audio_metadata(&gained.to_string_lossy()).unwrap();
+        assert_eq!(file.replaygain_track_gain, Some(-7.25));
+        assert_eq!(file.replaygain_track_peak, Some(0.988553));
+        let file = FileService::get_audio_metadata(&broken.to_string_lossy()).unwrap();
+        assert_eq!(file.replaygain_track_gain, None);
+        assert_eq!(file.replaygain_track_peak, None);
+    }
+
+    /// Writes a mono 16-bit WAV: `tone_secs` of a 440 Hz sine at `amplitude`,
+    /// then `silence_secs` of silence
+    fn write_tone_wav(path: &Path, amplitude: f32, tone_secs: f32, silence_secs: f32) {
+        // A second is exactly 32 waveform chunks
+        const SAMPLE_RATE: u32 = 8192;
+        let tone = (tone_secs * SAMPLE_RATE as f32) as usize;
+        let silence = (silence_secs * SAMPLE_RATE as f32) as usize;
+        let samples: Vec<i16> = (0..tone)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let value = amplitude * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+                (value * i16::MAX as f32) as i16
+            })
+            .chain(std::iter::repeat_n(0, silence))
+            .collect();
+
+        let data_len = (samples.len() * 2) as u32;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        fs::write(path, wav).unwrap();
+    }
+
+    #[test]
+    fn waveform_of_a_tone_then_silence() {
+        let dir = tempfile::tempdir().unwrap();
+        let tone = dir.path().join("tone.wav");
+        write_tone_wav(&tone, 0.5, 1.0, 1.0);
+        let tone = tone.to_string_lossy();
+
+        let peaks = FileService::compute_waveform_peaks(&tone, 4).unwrap();
+        assert_eq!(peaks.len(), 4);
+        for &(low, high) in &peaks[..2] {
+            assert!((low + 0.5).abs() < 0.01, "{}", low);
+            assert!((high - 0.5).abs() < 0.01, "{}", high);
+        }
+        for &(low, high) in &peaks[2..] {
+            assert!(low.abs() < 0.001 && high.abs() < 0.001);
+        }
+
+        // Served from the cache, and bucket counts are capped
+        assert_eq!(
+            FileService::compute_waveform_peaks(&tone, 4).unwrap(),
+            peaks
+        );
+        assert_eq!(
+            FileService::compute_waveform_peaks(&tone, 0).unwrap().len(),
+            1
+        );
+        assert!(
+            FileService::compute_waveform_peaks(&tone, usize::MAX)
+                .unwrap()
+                .len()
+                <= MAX_WAVEFORM_BUCKETS
+        );
+    }
+
+    /// Generates `count` tagged MP3s with cover art, 50 per album folder
+    fn write_fixture_library(root: &Path, count: usize) {
+        let cover = png_bytes(64, 64);
+        for i in 0..count {
+            let album = root.join(format!("Album {}", i / 50));
+            fs::create_dir_all(&album).unwrap();
+            write_tagged_mp3(&album.join(format!("{:02}.mp3", i % 50)), |tag| {
+                use lofty::tag::Accessor;
+
+                tag.set_title(format!("Track {}", i));
+                tag.set_artist("Fixture".to_string());
+                tag.set_album(format!("Album {}", i / 50));
+                with_cover(&cover)(tag);
+            });
+        }
+    }
+
+    /// Scans `root` in parallel and serially, returning both results and timings
+    fn scan_both_ways(
+        root: &Path,
+    ) -> (
+        Vec<MusicFile>,
+        Vec<MusicFile>,
+        std::time::Duration,
+        std::time::Duration,
+    ) {
+        let control = scan_control(&ScanState::default());
+        let paths =
+            FileService::collect_audio_paths(root, false, MAX_FILES_PER_SCAN, None, &control)
+                .unwrap();
+
+        let started = std::time::Instant::now();
+        let mut serial: Vec<MusicFile> = paths
+            .iter()
+            .map(|path| FileService::get_audio_metadata(path).unwrap())
+            .collect();
+        let serial_time = started.elapsed();
+        let started = std::time::Instant::now();
+        let mut parallel = FileService::extract_metadata_parallel(&paths, "", &control).unwrap();
+        let parallel_time = started.elapsed();
+
+        parallel.sort_by(|a, b| a.path.cmp(&b.path));
+        serial.sort_by(|a, b| a.path.cmp(&b.path));
+        (parallel, serial, parallel_time, serial_time)
+    }
+
+    #[test]
+    fn parallel_scan_parses_like_a_serial_one() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture_library(dir.path(), 120);
+
+        let (parallel, serial, _, _) = scan_both_ways(dir.path());
+        assert_eq!(parallel.len(), 120);
+        for (a, b) in parallel.iter().zip(&serial) {
+            assert_eq!(a.path, b.path);
+            assert_eq!(a.title, b.title);
+            assert_eq!(a.album, b.album);
+            assert!(a.album_art.is_some());
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark: cargo test scan_benchmark -- --ignored --nocapture"]
+    fn scan_benchmark() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture_library(dir.path(), 2000);
+
+        let (parallel, serial, parallel_time, serial_time) = scan_both_ways(dir.path());
+        assert_eq!(parallel.len(), serial.len());
+        println!(
+            "2000 files: parallel {:?}, serial {:?} ({:.1}x)",
+            parallel_time,
+            serial_time,
+            serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn playlist_file_urls_are_percent_decoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = canonical_path(dir.path()).unwrap();
+        fs::write(library.join("My Song.mp3"), b"").unwrap();
+        let roots = vec![library.clone()];
+
+        let url = format!("file://{}/My%20Song.mp3", library.to_string_lossy());
+        assert_eq!(
+            FileService::resolve_playlist_entry(&library, &url, &roots),
+            Some(path_to_string(&library.join("My Song.mp3")))
+        );
+        assert_eq!(
+            FileService::resolve_playlist_entry(&library, "http://x/My%20Song.mp3", &roots),
+            None
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn playlist_file_urls_keep_their_drive_letter() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = canonical_path(dir.path()).unwrap();
+        fs::write(library.join("song.mp3"), b"").unwrap();
+        let roots = vec![library.clone()];
+
+        // file:///C:/Users/.../song.mp3, not /C:/Users/...
+        let url = format!(
+            "file:///{}/song.mp3",
+            library.to_string_lossy().replace('\\', "/")
+        );
+        assert_eq!(
+            FileService::resolve_playlist_entry(Path::new("D:\\"), &url, &roots),
+            Some(path_to_string(&library.join("song.mp3")))
+        );
+    }
+
+    #[test]
+    fn overlapping_folders_count_towards_the_limit_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let unique = MAX_FILES_PER_SCAN / 2 + 1;
+        for i in 0..unique {
+            fs::write(dir.path().join(format!("{}.mp3", i)), b"").unwrap();
+        }
+        let folder = dir.path().to_string_lossy().into_owned();
+
+        // Listed twice, the folder's files add up to more than the limit
+        let (files, failed) =
+            FileService::scan_music_folders(&[folder.clone(), folder], false, None).unwrap();
+
+        assert_eq!(files.len(), unique);
+        assert!(failed.is_empty());
+    }
 }
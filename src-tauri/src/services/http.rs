@@ -0,0 +1,182 @@
+//! Shared HTTP client
+//!
+//! A single pooled `reqwest::Client` is built at startup and managed by Tauri, so
+//! requests to the same host (Last.fm, Spotify's image CDN) reuse connections
+//! instead of opening a new TLS session each time.
+
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::header::CONTENT_TYPE;
+use tracing::instrument;
+
+use crate::errors::AppError;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+const POOL_MAX_IDLE_PER_HOST: usize = 8;
+/// Largest remote image accepted (Spotify covers are a few hundred KB)
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Managed state holding the shared HTTP client
+pub struct HttpState {
+    client: reqwest::Client,
+}
+
+impl Default for HttpState {
+    fn default() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client }
+    }
+}
+
+impl HttpState {
+    /// Handle to the shared client; clones share the connection pool
+    pub fn client(&self) -> reqwest::Client {
+        self.client.clone()
+    }
+}
+
+/// Service for fetching remote resources
+pub struct HttpService;
+
+impl HttpService {
+    /// Downloads a remote image and returns it as a base64 data URL
+    #[instrument(skip_all, fields(url = %url))]
+    pub async fn fetch_image_as_data_url(
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<String, AppError> {
+        let (bytes, mime_type) = Self::fetch_image(client, url).await?;
+        let base64_data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        Ok(format!("data:{};base64,{}", mime_type, base64_data))
+    }
+
+    /// Downloads a remote image, returning its bytes and mime type
+    ///
+    /// Only http(s) URLs are fetched, and the response must be an image of at most
+    /// 10 MB.
+    pub async fn fetch_image(
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<(Vec<u8>, String), AppError> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| AppError::Validation(format!("Invalid image URL {}: {}", url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AppError::Validation(format!(
+                "Image URL must use http or https: {}",
+                url
+            )));
+        }
+
+        let response = client
+            .get(parsed)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::ExternalApi(format!("Failed to fetch image: {}", e)))?;
+
+        let mime_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !mime_type.starts_with("image/") {
+            return Err(AppError::ExternalApi(format!(
+                "URL did not return an image (content type '{}'): {}",
+                mime_type, url
+            )));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_IMAGE_BYTES as u64)
+        {
+            return Err(AppError::ExternalApi(format!(
+                "Image is too large: {}",
+                url
+            )));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Failed to read image: {}", e)))?;
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return Err(AppError::ExternalApi(format!(
+                "Image is too large: {}",
+                url
+            )));
+        }
+
+        tracing::debug!("🖼️ Fetched {} ({} bytes)", url, bytes.len());
+        Ok((bytes.to_vec(), mime_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\nfake image";
+
+    fn image_server() -> MockServer {
+        MockServer::start(|request| match request.url.as_str() {
+            "/cover.png" => MockResponse::new(200, PNG_BYTES)
+                .with_header("Content-Type", "image/png; charset=binary"),
+            "/page.html" => {
+                MockResponse::new(200, "<html></html>").with_header("Content-Type", "text/html")
+            }
+            _ => MockResponse::new(404, "not found"),
+        })
+    }
+
+    #[tokio::test]
+    async fn fetches_images_through_the_shared_client() {
+        let server = image_server();
+        let client = HttpState::default().client();
+
+        let url = format!("{}/cover.png", server.url());
+        let (bytes, mime_type) = HttpService::fetch_image(&client, &url).await.unwrap();
+        assert_eq!(bytes, PNG_BYTES);
+        assert_eq!(mime_type, "image/png");
+
+        let data_url = HttpService::fetch_image_as_data_url(&client, &url)
+            .await
+            .unwrap();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(PNG_BYTES);
+        assert_eq!(data_url, format!("data:image/png;base64,{}", encoded));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_images_missing_files_and_other_schemes() {
+        let server = image_server();
+        let client = HttpState::default().client();
+
+        for path in ["/page.html", "/missing.png"] {
+            let url = format!("{}{}", server.url(), path);
+            assert!(
+                matches!(
+                    HttpService::fetch_image(&client, &url).await,
+                    Err(AppError::ExternalApi(_))
+                ),
+                "{} should fail",
+                path
+            );
+        }
+        assert!(matches!(
+            HttpService::fetch_image(&client, "file:///etc/passwd").await,
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -1,71 +1,191 @@
 //! Last.fm API service with caching
 
 use crate::domain::lastfm::{
-    raw, EnrichedTrack, LastFmImage, ProcessedAlbumInfo, ProcessedArtistInfo, ProcessedTrackInfo,
+    raw, EnrichedTrack, ImageSize, LastFmCacheStats, LastFmImage, ProcessedAlbumInfo,
+    ProcessedArtistInfo, ProcessedTopTrack, ProcessedTrackInfo,
 };
 use crate::domain::music::MusicFile;
 use crate::errors::AppError;
+use crate::services::CoverArtService;
+use crate::utils::{get_app_data_dir, with_retries};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 
 const API_BASE_URL: &str = "https://ws.audioscrobbler.com/2.0/";
-const REQUEST_TIMEOUT_SECS: u64 = 30;
 const RATE_LIMIT_DELAY_MS: u64 = 100; // 10 requests per second max
+const MAX_TOP_TRACKS: u32 = 50;
+const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY_MS: u64 = 500;
+/// File name of the grey star Last.fm serves when it has no real image
+const PLACEHOLDER_IMAGE_HASH: &str = "2a96cbd8b46e442fc41c2b86b821562f";
+/// Last.fm error code for an unknown artist/track/album
+const ERROR_INVALID_PARAMETERS: i32 = 6;
+/// Seconds a cached response is used without asking Last.fm
+const CACHE_TTL_SECS: u64 = 1800;
+/// Seconds an expired entry with validators is kept for a conditional refresh
+const REVALIDATE_MAX_AGE_SECS: u64 = 7 * 24 * 3600;
+/// Cache file format; older files are ignored
+const CACHE_FILE_VERSION: u32 = 2;
+
+/// `ETag`/`Last-Modified` of a response, sent back to revalidate it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Cached value, when it was fetched (or last revalidated) and its validators
+type CacheEntry<T> = (T, u64, Validators);
+
+/// Result of looking up a cache entry
+enum CacheLookup<T> {
+    /// Within the TTL, use as-is
+    Fresh(T),
+    /// Expired but can be revalidated with a conditional request
+    Stale(T, Validators),
+    /// Missing, or expired without validators
+    Miss,
+}
+
+/// Response of a (possibly conditional) request
+enum Fetched<T> {
+    Modified(T, Validators),
+    /// 304: the cached value is still current
+    NotModified,
+}
 
 /// Estructura para persistir el cache en archivo JSON
 #[derive(Serialize, Deserialize)]
 struct CacheFile {
-    track_cache: HashMap<String, (ProcessedTrackInfo, u64)>,
-    artist_cache: HashMap<String, (ProcessedArtistInfo, u64)>,
-    album_cache: HashMap<String, (ProcessedAlbumInfo, u64)>,
+    track_cache: HashMap<String, CacheEntry<ProcessedTrackInfo>>,
+    artist_cache: HashMap<String, CacheEntry<ProcessedArtistInfo>>,
+    album_cache: HashMap<String, CacheEntry<ProcessedAlbumInfo>>,
+    #[serde(default)]
+    artist_top_cache: HashMap<String, CacheEntry<Vec<ProcessedTopTrack>>>,
     version: u32,
 }
 
 pub struct LastFmService {
     client: reqwest::Client,
     api_key: String,
+    /// Last.fm API endpoint
+    api_url: String,
+    /// File the cache is persisted to
+    cache_file: PathBuf,
     // Simple in-memory cache for now: key -> (json_metadata, timestamp)
     // We might want to cache specific processed types instead of raw json to save parsing,
     // but the Prompt suggested (serde_json::Value, u64).
     // Let's cache the Processed types directly for better perfs.
-    track_cache: RwLock<HashMap<String, (ProcessedTrackInfo, u64)>>,
-    artist_cache: RwLock<HashMap<String, (ProcessedArtistInfo, u64)>>,
-    album_cache: RwLock<HashMap<String, (ProcessedAlbumInfo, u64)>>,
+    track_cache: RwLock<HashMap<String, CacheEntry<ProcessedTrackInfo>>>,
+    artist_cache: RwLock<HashMap<String, CacheEntry<ProcessedArtistInfo>>>,
+    album_cache: RwLock<HashMap<String, CacheEntry<ProcessedAlbumInfo>>>,
+    artist_top_cache: RwLock<HashMap<String, CacheEntry<Vec<ProcessedTopTrack>>>>,
     last_request_time: RwLock<std::time::Instant>,
     cache_loaded: RwLock<bool>,
+    /// Total attempts per request, including the first one
+    retry_attempts: u32,
+    /// When set, only cached data is served and no request is made
+    offline: AtomicBool,
+    /// Whether Last.fm may correct misspelled artist, track and album names
+    autocorrect: AtomicBool,
+    /// Lookups served from the cache / needing a request
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
 }
 
 impl LastFmService {
-    pub fn new(api_key: String) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
-            .build()
-            .expect("Failed to create HTTP client");
-        
+    /// Creates the service on top of a shared HTTP client (see `HttpState`)
+    pub fn new(api_key: String, client: reqwest::Client) -> Self {
         Self {
             client,
             api_key,
+            api_url: API_BASE_URL.to_string(),
+            cache_file: get_app_data_dir().join("lastfm_cache.json"),
             track_cache: RwLock::new(HashMap::new()),
             artist_cache: RwLock::new(HashMap::new()),
             album_cache: RwLock::new(HashMap::new()),
+            artist_top_cache: RwLock::new(HashMap::new()),
             last_request_time: RwLock::new(std::time::Instant::now() - Duration::from_millis(RATE_LIMIT_DELAY_MS)),
             cache_loaded: RwLock::new(false),
+            retry_attempts: DEFAULT_RETRY_ATTEMPTS,
+            offline: AtomicBool::new(false),
+            autocorrect: AtomicBool::new(true),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
         }
     }
 
-    /// Obtiene la ruta del archivo de cache
-    fn get_cache_file_path() -> Result<PathBuf, AppError> {
-        let cache_dir = dirs::data_dir()
-            .or_else(|| std::env::temp_dir().parent().map(|p| p.to_path_buf()))
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("musicplayer");
-        
-        Ok(cache_dir.join("lastfm_cache.json"))
+    /// Whether an API key is configured (without one every request fails)
+    pub fn has_api_key(&self) -> bool {
+        !self.api_key.trim().is_empty()
+    }
+
+    /// Switches offline mode: cached values (even expired ones) are returned and
+    /// lookups that aren't cached fail with `Offline` instead of hitting the network
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+        tracing::info!("🎵 Last.fm offline mode: {}", offline);
+    }
+
+    fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// Turns Last.fm's name autocorrection on or off (on by default)
+    ///
+    /// Autocorrection finds the right entity for misspelled or differently written
+    /// names, but can also swap an intentional name (a side project, a band whose
+    /// name looks like a typo) for a more popular one. Off, only exact names match.
+    pub fn set_autocorrect(&self, autocorrect: bool) {
+        self.autocorrect.store(autocorrect, Ordering::Relaxed);
+        tracing::info!("🎵 Last.fm autocorrect: {}", autocorrect);
+    }
+
+    fn autocorrects(&self) -> bool {
+        self.autocorrect.load(Ordering::Relaxed)
+    }
+
+    /// Cache key suffix for the image size and the autocorrect setting
+    ///
+    /// Exact lookups are cached apart, since the same query can resolve to a
+    /// different entity with autocorrection on.
+    fn cache_key_suffix(&self, image_size: Option<ImageSize>) -> String {
+        let mut suffix = image_key_suffix(image_size);
+        if !self.autocorrects() {
+            suffix.push_str(":exact");
+        }
+        suffix
+    }
+
+    /// Sets how many times a request is attempted before giving up (at least once)
+    pub fn with_retry_attempts(mut self, attempts: u32) -> Self {
+        self.retry_attempts = attempts.max(1);
+        self
     }
 
     /// Carga el cache desde archivo si no está cargado aún (lazy loading)
@@ -83,17 +203,17 @@ impl LastFmService {
 
     /// Carga el cache desde archivo JSON
     async fn load_cache_from_file(&self) -> Result<(), AppError> {
-        let cache_file = Self::get_cache_file_path()?;
+        let cache_file = &self.cache_file;
         
         if !cache_file.exists() {
             return Ok(()); // No hay cache previo
         }
 
-        match fs::read_to_string(&cache_file) {
+        match fs::read_to_string(cache_file) {
             Ok(json) => {
                 match serde_json::from_str::<CacheFile>(&json) {
                     Ok(cache_data) => {
-                        if cache_data.version != 1 {
+                        if cache_data.version != CACHE_FILE_VERSION {
                             return Ok(()); // Versión incompatible
                         }
 
@@ -104,25 +224,32 @@ impl LastFmService {
                             .as_secs();
 
                         let mut track_cache = self.track_cache.write().await;
-                        for (key, (data, timestamp)) in cache_data.track_cache {
-                            if now - timestamp < 1800 { // 30 minutos
-                                track_cache.insert(key, (data, timestamp));
-                            }
-                        }
+                        track_cache.extend(cache_data.track_cache.into_iter().filter(
+                            |(_, (_, timestamp, validators))| {
+                                is_worth_keeping(now, *timestamp, validators)
+                            },
+                        ));
 
                         let mut artist_cache = self.artist_cache.write().await;
-                        for (key, (data, timestamp)) in cache_data.artist_cache {
-                            if now - timestamp < 1800 {
-                                artist_cache.insert(key, (data, timestamp));
-                            }
-                        }
+                        artist_cache.extend(cache_data.artist_cache.into_iter().filter(
+                            |(_, (_, timestamp, validators))| {
+                                is_worth_keeping(now, *timestamp, validators)
+                            },
+                        ));
 
                         let mut album_cache = self.album_cache.write().await;
-                        for (key, (data, timestamp)) in cache_data.album_cache {
-                            if now - timestamp < 1800 {
-                                album_cache.insert(key, (data, timestamp));
-                            }
-                        }
+                        album_cache.extend(cache_data.album_cache.into_iter().filter(
+                            |(_, (_, timestamp, validators))| {
+                                is_worth_keeping(now, *timestamp, validators)
+                            },
+                        ));
+
+                        let mut artist_top_cache = self.artist_top_cache.write().await;
+                        artist_top_cache.extend(cache_data.artist_top_cache.into_iter().filter(
+                            |(_, (_, timestamp, validators))| {
+                                is_worth_keeping(now, *timestamp, validators)
+                            },
+                        ));
                     }
                     Err(e) => {
                         eprintln!("Cache file corrupted, ignoring: {}", e);
@@ -139,15 +266,27 @@ impl LastFmService {
         Ok(())
     }
 
+    /// Writes the cache to disk, e.g. on exit
+    ///
+    /// Does nothing if the cache was never loaded, so the file on disk is not
+    /// replaced by an empty cache.
+    pub async fn flush_cache(&self) -> Result<(), AppError> {
+        if !*self.cache_loaded.read().await {
+            return Ok(());
+        }
+        self.save_cache_to_file().await
+    }
+
     /// Guarda el cache actual en archivo JSON
     async fn save_cache_to_file(&self) -> Result<(), AppError> {
-        let cache_file = Self::get_cache_file_path()?;
+        let cache_file = &self.cache_file;
         
         let cache_data = CacheFile {
             track_cache: self.track_cache.read().await.clone(),
             artist_cache: self.artist_cache.read().await.clone(),
             album_cache: self.album_cache.read().await.clone(),
-            version: 1,
+            artist_top_cache: self.artist_top_cache.read().await.clone(),
+            version: CACHE_FILE_VERSION,
         };
 
         let json = serde_json::to_string_pretty(&cache_data)
@@ -159,7 +298,7 @@ impl LastFmService {
                 .map_err(|e| AppError::ExternalApi(format!("Failed to create cache dir: {}", e)))?;
         }
 
-        fs::write(&cache_file, json)
+        fs::write(cache_file, json)
             .map_err(|e| AppError::ExternalApi(format!("Failed to write cache file: {}", e)))?;
 
         Ok(())
@@ -180,29 +319,145 @@ impl LastFmService {
         Ok(())
     }
 
+    /// Looks up a cache entry, telling fresh entries from ones worth revalidating
+    ///
+    /// Offline, every cached entry counts as fresh whatever its age.
+    async fn lookup<T: Clone>(
+        &self,
+        cache: &RwLock<HashMap<String, CacheEntry<T>>>,
+        key: &str,
+    ) -> CacheLookup<T> {
+        let offline = self.is_offline();
+        let cache = cache.read().await;
+        let lookup = match cache.get(key) {
+            Some((value, timestamp, _))
+                if offline || now_secs().saturating_sub(*timestamp) < CACHE_TTL_SECS =>
+            {
+                CacheLookup::Fresh(value.clone())
+            }
+            Some((value, _, validators)) if !validators.is_empty() => {
+                CacheLookup::Stale(value.clone(), validators.clone())
+            }
+            _ => CacheLookup::Miss,
+        };
+
+        let counter = match lookup {
+            CacheLookup::Fresh(_) => &self.cache_hits,
+            _ => &self.cache_misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        lookup
+    }
+
+    /// Entry counts of every cache plus the hit/miss counters
+    pub async fn cache_stats(&self) -> Result<LastFmCacheStats, AppError> {
+        self.ensure_cache_loaded().await?;
+        Ok(LastFmCacheStats {
+            track_entries: self.track_cache.read().await.len(),
+            artist_entries: self.artist_cache.read().await.len(),
+            album_entries: self.album_cache.read().await.len(),
+            artist_top_entries: self.artist_top_cache.read().await.len(),
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Empties every cache, on disk too, and resets the hit/miss counters
+    pub async fn clear_cache(&self) -> Result<(), AppError> {
+        // Mark the cache as loaded so a later lookup doesn't bring the old file back
+        *self.cache_loaded.write().await = true;
+        self.track_cache.write().await.clear();
+        self.artist_cache.write().await.clear();
+        self.album_cache.write().await.clear();
+        self.artist_top_cache.write().await.clear();
+        self.cache_hits.store(0, Ordering::Relaxed);
+        self.cache_misses.store(0, Ordering::Relaxed);
+
+        tracing::info!("🎵 Last.fm cache cleared");
+        self.save_cache_to_file().await
+    }
+
+    /// Handles a 304: restarts the entry's TTL and returns the cached value
+    async fn revalidated<T: Clone>(
+        cache: &RwLock<HashMap<String, CacheEntry<T>>>,
+        key: &str,
+        stale: Option<(T, Validators)>,
+    ) -> Result<T, AppError> {
+        let (value, _) = stale.ok_or_else(|| {
+            AppError::ExternalApi("Unexpected 304 for an unconditional request".into())
+        })?;
+        if let Some(entry) = cache.write().await.get_mut(key) {
+            entry.1 = now_secs();
+        }
+        tracing::debug!("🎵 Last.fm cache entry revalidated: {}", key);
+        Ok(value)
+    }
+
+    /// Sends one Last.fm request, failing with `ExternalApi` only when retrying may help
+    async fn send(
+        &self,
+        query: &[(&str, &str)],
+        validators: Option<&Validators>,
+    ) -> Result<reqwest::Response, AppError> {
+        self.enforce_rate_limit().await?;
+
+        let mut request = self.client.get(&self.api_url).query(query);
+        if let Some(validators) = validators {
+            if let Some(etag) = &validators.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() || e.is_request() {
+                AppError::ExternalApi(e.to_string())
+            } else {
+                AppError::Unknown(e.to_string())
+            }
+        })?;
+        if response.status().is_server_error() {
+            return Err(AppError::ExternalApi(format!(
+                "HTTP Error: {}",
+                response.status()
+            )));
+        }
+        Ok(response)
+    }
+
+    /// Fetches a Last.fm method, sending `validators` as conditional headers
+    ///
+    /// With validators, a 304 yields `Fetched::NotModified` so the cached value
+    /// can be reused without downloading or parsing the response again.
     async fn fetch<T: serde::de::DeserializeOwned>(
         &self,
         method: &str,
         params: &[(&str, &str)],
-    ) -> Result<T, AppError> {
-        self.enforce_rate_limit().await?;
-        
+        validators: Option<&Validators>,
+    ) -> Result<Fetched<T>, AppError> {
         let mut query = vec![
             ("method", method),
             ("api_key", &self.api_key),
             ("format", "json"),
-            ("autocorrect", "1"),
+            ("autocorrect", if self.autocorrects() { "1" } else { "0" }),
         ];
         query.extend_from_slice(params);
 
-        let response = self
-            .client
-            .get(API_BASE_URL)
-            .query(&query)
-            .send()
-            .await
-            .map_err(|e| AppError::ExternalApi(e.to_string()))?;
-
+        // Connection problems and 5xx come back as `ExternalApi` and are retried
+        // with backoff. Last.fm application errors are deterministic and are never retried.
+        let response = with_retries(
+            self.retry_attempts,
+            Duration::from_millis(RETRY_BASE_DELAY_MS),
+            |e| matches!(e, AppError::ExternalApi(_)),
+            || self.send(&query, validators),
+        )
+        .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED && validators.is_some() {
+            return Ok(Fetched::NotModified);
+        }
         if !response.status().is_success() {
             return Err(AppError::ExternalApi(format!(
                 "HTTP Error: {}",
@@ -210,6 +465,7 @@ impl LastFmService {
             )));
         }
 
+        let validators = Validators::from_headers(response.headers());
         let bytes = response
             .bytes()
             .await
@@ -223,44 +479,57 @@ impl LastFmService {
         }
 
         if let Ok(err) = serde_json::from_slice::<LastFmError>(&bytes) {
-            return Err(AppError::ExternalApi(format!(
-                "Last.fm Error {}: {}",
-                err.error, err.message
-            )));
+            return Err(AppError::LastFmApi {
+                code: err.error,
+                message: err.message,
+            });
         }
 
         serde_json::from_slice::<T>(&bytes)
+            .map(|value| Fetched::Modified(value, validators))
             .map_err(|e| AppError::ExternalApi(format!("Parse Error: {}", e)))
     }
 
+    /// Gets track info; `image_size` picks the album image size (largest by default)
     pub async fn get_track_info(
         &self,
         artist: &str,
         track: &str,
+        image_size: Option<ImageSize>,
     ) -> Result<ProcessedTrackInfo, AppError> {
         // Asegurar que el cache esté cargado
         self.ensure_cache_loaded().await?;
 
-        let cache_key = format!("track:{}:{}", artist.to_lowercase(), track.to_lowercase());
+        let cache_key = format!(
+            "track:{}:{}{}",
+            artist.to_lowercase(),
+            track.to_lowercase(),
+            self.cache_key_suffix(image_size)
+        );
+
+        let stale = match self.lookup(&self.track_cache, &cache_key).await {
+            CacheLookup::Fresh(info) => return Ok(info),
+            CacheLookup::Stale(info, validators) => Some((info, validators)),
+            CacheLookup::Miss => None,
+        };
+        if self.is_offline() {
+            return Err(AppError::Offline(cache_key));
+        }
 
+        let fetched_at = now_secs();
+        let (response, validators): (raw::TrackResponse, _) = match self
+            .fetch(
+                "track.getinfo",
+                &[("artist", artist), ("track", track)],
+                stale.as_ref().map(|(_, v)| v),
+            )
+            .await?
         {
-            let cache = self.track_cache.read().await;
-            if let Some((info, timestamp)) = cache.get(&cache_key) {
-                // TODO: Check TTL (30 mins = 1800s)
-                // For now, infinite cache or simpler check
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                if now - timestamp < 1800 {
-                    return Ok(info.clone());
-                }
+            Fetched::Modified(response, validators) => (response, validators),
+            Fetched::NotModified => {
+                return Self::revalidated(&self.track_cache, &cache_key, stale).await
             }
-        }
-
-        let response: raw::TrackResponse = self
-            .fetch("track.getinfo", &[("artist", artist), ("track", track)])
-            .await?;
+        };
 
         let t = response.track;
         let processed = ProcessedTrackInfo {
@@ -281,17 +550,11 @@ impl LastFmService {
             url: t.url,
             image: t
                 .album
-                .and_then(|a| get_best_image(&a.image.unwrap_or_default())),
+                .and_then(|a| get_best_image(&a.image.unwrap_or_default(), image_size)),
         };
 
-        {
-            let mut cache = self.track_cache.write().await;
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            cache.insert(cache_key, (processed.clone(), now));
-        }
+        self.store_track(cache_key, &processed, image_size, validators, fetched_at)
+            .await;
 
         // Guardar cache persistente
         if let Err(e) = self.save_cache_to_file().await {
@@ -301,31 +564,80 @@ impl LastFmService {
         Ok(processed)
     }
 
-    pub async fn get_artist_info(&self, artist: &str) -> Result<ProcessedArtistInfo, AppError> {
+    /// Caches a fetched track under the requested key and its autocorrected names
+    ///
+    /// The canonical spelling then hits too. An entry under that name stored since
+    /// `fetched_at` (by a concurrent lookup of another spelling) is kept.
+    async fn store_track(
+        &self,
+        cache_key: String,
+        processed: &ProcessedTrackInfo,
+        image_size: Option<ImageSize>,
+        validators: Validators,
+        fetched_at: u64,
+    ) {
+        let mut cache = self.track_cache.write().await;
+        let now = now_secs();
+
+        let corrected_key = format!(
+            "track:{}:{}{}",
+            processed.artist.to_lowercase(),
+            processed.name.to_lowercase(),
+            self.cache_key_suffix(image_size)
+        );
+        if corrected_key != cache_key {
+            let is_fresher = cache
+                .get(&corrected_key)
+                .is_some_and(|(_, timestamp, _)| *timestamp >= fetched_at);
+            if !is_fresher {
+                cache.insert(corrected_key, (processed.clone(), now, validators.clone()));
+            }
+        }
+
+        cache.insert(cache_key, (processed.clone(), now, validators));
+    }
+
+    /// Gets artist info; `image_size` picks the image size (largest by default)
+    pub async fn get_artist_info(
+        &self,
+        artist: &str,
+        image_size: Option<ImageSize>,
+    ) -> Result<ProcessedArtistInfo, AppError> {
         // Asegurar que el cache esté cargado
         self.ensure_cache_loaded().await?;
 
-        let cache_key = format!("artist:{}", artist.to_lowercase());
-        {
-            let cache = self.artist_cache.read().await;
-            if let Some((info, timestamp)) = cache.get(&cache_key) {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                if now - timestamp < 1800 {
-                    return Ok(info.clone());
-                }
-            }
+        let cache_key = format!(
+            "artist:{}{}",
+            artist.to_lowercase(),
+            self.cache_key_suffix(image_size)
+        );
+        let stale = match self.lookup(&self.artist_cache, &cache_key).await {
+            CacheLookup::Fresh(info) => return Ok(info),
+            CacheLookup::Stale(info, validators) => Some((info, validators)),
+            CacheLookup::Miss => None,
+        };
+        if self.is_offline() {
+            return Err(AppError::Offline(cache_key));
         }
 
-        let response: raw::ArtistResponse =
-            self.fetch("artist.getinfo", &[("artist", artist)]).await?;
+        let (response, validators): (raw::ArtistResponse, _) = match self
+            .fetch(
+                "artist.getinfo",
+                &[("artist", artist)],
+                stale.as_ref().map(|(_, v)| v),
+            )
+            .await?
+        {
+            Fetched::Modified(response, validators) => (response, validators),
+            Fetched::NotModified => {
+                return Self::revalidated(&self.artist_cache, &cache_key, stale).await
+            }
+        };
 
         let a = response.artist;
         let processed = ProcessedArtistInfo {
             name: a.name,
-            image: get_best_image(&a.image.unwrap_or_default()),
+            image: get_best_image(&a.image.unwrap_or_default(), image_size),
             bio: a
                 .bio
                 .as_ref()
@@ -359,7 +671,7 @@ impl LastFmService {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
-            cache.insert(cache_key, (processed.clone(), now));
+            cache.insert(cache_key, (processed.clone(), now, validators));
         }
 
         // Guardar cache persistente
@@ -370,37 +682,65 @@ impl LastFmService {
         Ok(processed)
     }
 
+    /// Gets album info; `image_size` picks the cover size (largest by default)
     pub async fn get_album_info(
         &self,
         artist: &str,
         album: &str,
+        image_size: Option<ImageSize>,
     ) -> Result<ProcessedAlbumInfo, AppError> {
         // Asegurar que el cache esté cargado
         self.ensure_cache_loaded().await?;
 
-        let cache_key = format!("album:{}:{}", artist.to_lowercase(), album.to_lowercase());
-        {
-            let cache = self.album_cache.read().await;
-            if let Some((info, timestamp)) = cache.get(&cache_key) {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                if now - timestamp < 1800 {
-                    return Ok(info.clone());
-                }
-            }
+        let cache_key = format!(
+            "album:{}:{}{}",
+            artist.to_lowercase(),
+            album.to_lowercase(),
+            self.cache_key_suffix(image_size)
+        );
+        let stale = match self.lookup(&self.album_cache, &cache_key).await {
+            CacheLookup::Fresh(info) => return Ok(info),
+            CacheLookup::Stale(info, validators) => Some((info, validators)),
+            CacheLookup::Miss => None,
+        };
+        if self.is_offline() {
+            return Err(AppError::Offline(cache_key));
         }
 
-        let response: raw::AlbumResponse = self
-            .fetch("album.getinfo", &[("artist", artist), ("album", album)])
-            .await?;
+        let (response, validators): (raw::AlbumResponse, _) = match self
+            .fetch(
+                "album.getinfo",
+                &[("artist", artist), ("album", album)],
+                stale.as_ref().map(|(_, v)| v),
+            )
+            .await?
+        {
+            Fetched::Modified(response, validators) => (response, validators),
+            Fetched::NotModified => {
+                return Self::revalidated(&self.album_cache, &cache_key, stale).await
+            }
+        };
 
         let a = response.album;
+        let image = match get_best_image(&a.image.unwrap_or_default(), image_size) {
+            Some(image) => Some(image),
+            // Last.fm has no art for many smaller releases; try the Cover Art Archive
+            None => CoverArtService::fetch_album_cover(&self.client, artist, album)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "🖼️ Cover art fallback failed for {} - {}: {}",
+                        artist,
+                        album,
+                        e
+                    );
+                    None
+                }),
+        };
         let processed = ProcessedAlbumInfo {
             name: a.name,
             artist: a.artist,
-            image: get_best_image(&a.image.unwrap_or_default()),
+            image,
             summary: a.wiki.map(|w| clean_html(&w.summary)).unwrap_or_default(),
             tags: a
                 .tags
@@ -418,7 +758,7 @@ impl LastFmService {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
-            cache.insert(cache_key, (processed.clone(), now));
+            cache.insert(cache_key, (processed.clone(), now, validators));
         }
 
         // Guardar cache persistente
@@ -429,6 +769,86 @@ impl LastFmService {
         Ok(processed)
     }
 
+    /// Gets an artist's most popular tracks (at most 50)
+    ///
+    /// An unknown artist yields an empty list rather than an error; offline,
+    /// uncached lookups fail with `Offline`.
+    pub async fn get_artist_top_tracks(
+        &self,
+        artist: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<ProcessedTopTrack>, AppError> {
+        // Asegurar que el cache esté cargado
+        self.ensure_cache_loaded().await?;
+
+        let limit = limit.unwrap_or(10).clamp(1, MAX_TOP_TRACKS);
+        let cache_key = format!(
+            "top:{}:{}{}",
+            artist.to_lowercase(),
+            limit,
+            self.cache_key_suffix(None)
+        );
+        let stale = match self.lookup(&self.artist_top_cache, &cache_key).await {
+            CacheLookup::Fresh(tracks) => return Ok(tracks),
+            CacheLookup::Stale(tracks, validators) => Some((tracks, validators)),
+            CacheLookup::Miss => None,
+        };
+        if self.is_offline() {
+            return Err(AppError::Offline(cache_key));
+        }
+
+        let limit_param = limit.to_string();
+        let (response, validators): (raw::TopTracksResponse, _) = match self
+            .fetch(
+                "artist.gettoptracks",
+                &[("artist", artist), ("limit", &limit_param)],
+                stale.as_ref().map(|(_, v)| v),
+            )
+            .await
+        {
+            Ok(Fetched::Modified(response, validators)) => (response, validators),
+            Ok(Fetched::NotModified) => {
+                return Self::revalidated(&self.artist_top_cache, &cache_key, stale).await
+            }
+            Err(AppError::LastFmApi { code, .. }) if code == ERROR_INVALID_PARAMETERS => {
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let processed: Vec<ProcessedTopTrack> = response
+            .toptracks
+            .track
+            .into_iter()
+            .take(limit as usize)
+            .map(|t| ProcessedTopTrack {
+                name: t.name,
+                artist: t.artist.name,
+                rank: t.attr.and_then(|a| a.rank.parse().ok()),
+                playcount: t.playcount.and_then(|p| p.parse().ok()),
+                listeners: t.listeners.and_then(|l| l.parse().ok()),
+                url: t.url,
+                image: get_best_image(&t.image.unwrap_or_default(), None),
+            })
+            .collect();
+
+        {
+            let mut cache = self.artist_top_cache.write().await;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            cache.insert(cache_key, (processed.clone(), now, validators));
+        }
+
+        // Guardar cache persistente
+        if let Err(e) = self.save_cache_to_file().await {
+            tracing::warn!("🎵 Failed to save cache after top tracks update: {}", e);
+        }
+
+        Ok(processed)
+    }
+
     pub async fn enrich_tracks_batch(
         &self,
         tracks: Vec<MusicFile>,
@@ -436,29 +856,154 @@ impl LastFmService {
         use futures::stream::{self, StreamExt};
 
         let results: Vec<EnrichedTrack> = stream::iter(tracks)
-            .map(|track| async move {
-                let artist = track.artist.as_deref().unwrap_or_default();
-                let title = track.title.as_deref().unwrap_or_default();
+            .map(|track| self.enrich_track(track))
+            .buffer_unordered(5) // Limit concurrency to 5
+            .collect()
+            .await;
 
-                let enriched = if !artist.is_empty() && !title.is_empty() {
-                    self.get_track_info(artist, title).await.ok()
-                } else {
-                    None
-                };
+        Ok(results)
+    }
 
-                let album_art_url = enriched.as_ref().and_then(|e| e.image.clone());
+    /// Enriches tracks with one `album.getinfo` call per album instead of one call per track
+    ///
+    /// Tracks sharing an album and artist get the album cover (without per-track
+    /// info). Single tracks, tracks without an album and albums Last.fm has no
+    /// cover for go through the per-track lookup. Results keep the input order.
+    pub async fn enrich_tracks_by_album(
+        &self,
+        tracks: Vec<MusicFile>,
+    ) -> Result<Vec<EnrichedTrack>, AppError> {
+        use futures::stream::{self, StreamExt};
 
-                EnrichedTrack {
-                    original: track,
-                    enriched,
-                    album_art_url,
+        let mut groups: Vec<Vec<(usize, MusicFile)>> = Vec::new();
+        let mut group_index: HashMap<(String, String), usize> = HashMap::new();
+        for (index, track) in tracks.into_iter().enumerate() {
+            let key = match (track.album.as_deref(), track.artist.as_deref()) {
+                (Some(album), Some(artist))
+                    if !album.trim().is_empty() && !artist.trim().is_empty() =>
+                {
+                    Some((album.trim().to_lowercase(), artist.trim().to_lowercase()))
                 }
-            })
+                _ => None,
+            };
+            match key {
+                Some(key) => match group_index.get(&key) {
+                    Some(&group) => groups[group].push((index, track)),
+                    None => {
+                        group_index.insert(key, groups.len());
+                        groups.push(vec![(index, track)]);
+                    }
+                },
+                None => groups.push(vec![(index, track)]),
+            }
+        }
+
+        let album_lookups = groups.iter().filter(|group| group.len() > 1).count();
+        tracing::info!(
+            "🎵 Enriching {} albums ({} groups)",
+            album_lookups,
+            groups.len()
+        );
+
+        let mut results: Vec<(usize, EnrichedTrack)> = stream::iter(groups)
+            .map(|group| self.enrich_album_group(group))
             .buffer_unordered(5) // Limit concurrency to 5
-            .collect()
-            .await;
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
 
-        Ok(results)
+        results.sort_by_key(|(index, _)| *index);
+        Ok(results.into_iter().map(|(_, track)| track).collect())
+    }
+
+    /// Applies one album cover to every track in the group, or enriches them one by one
+    async fn enrich_album_group(
+        &self,
+        group: Vec<(usize, MusicFile)>,
+    ) -> Vec<(usize, EnrichedTrack)> {
+        if group.len() > 1 {
+            let first = &group[0].1;
+            let artist = first.artist.as_deref().unwrap_or_default();
+            let album = first.album.as_deref().unwrap_or_default();
+            let cover = self
+                .get_album_info(artist, album, None)
+                .await
+                .ok()
+                .and_then(|a| a.image);
+
+            if let Some(cover) = cover {
+                return group
+                    .into_iter()
+                    .map(|(index, track)| {
+                        let enriched = EnrichedTrack {
+                            original: track,
+                            enriched: None,
+                            album_art_url: Some(cover.clone()),
+                        };
+                        (index, enriched)
+                    })
+                    .collect();
+            }
+        }
+
+        let mut enriched = Vec::with_capacity(group.len());
+        for (index, track) in group {
+            enriched.push((index, self.enrich_track(track).await));
+        }
+        enriched
+    }
+
+    /// Looks up a single track, falling back to album/artist art when it has none
+    async fn enrich_track(&self, track: MusicFile) -> EnrichedTrack {
+        let artist = track.artist.as_deref().unwrap_or_default();
+        let title = track.title.as_deref().unwrap_or_default();
+
+        let enriched = if !artist.is_empty() && !title.is_empty() {
+            self.get_track_info(artist, title, None).await.ok()
+        } else {
+            None
+        };
+
+        let album_art_url = match enriched.as_ref().and_then(|e| e.image.clone()) {
+            Some(url) => Some(url),
+            None if !artist.is_empty() => {
+                let album = track
+                    .album
+                    .as_deref()
+                    .or_else(|| enriched.as_ref().and_then(|e| e.album.as_deref()));
+                self.fallback_album_art(artist, album).await
+            }
+            None => None,
+        };
+
+        EnrichedTrack {
+            original: track,
+            enriched,
+            album_art_url,
+        }
+    }
+
+    /// Looks for artwork when the track itself has none: album image first, then artist image
+    ///
+    /// Goes through the cached lookups, so repeated albums/artists in a batch hit the cache.
+    async fn fallback_album_art(&self, artist: &str, album: Option<&str>) -> Option<String> {
+        if let Some(album) = album.filter(|a| !a.trim().is_empty()) {
+            if let Some(image) = self
+                .get_album_info(artist, album, None)
+                .await
+                .ok()
+                .and_then(|a| a.image)
+            {
+                return Some(image);
+            }
+        }
+
+        self.get_artist_info(artist, None)
+            .await
+            .ok()
+            .and_then(|a| a.image)
     }
 }
 
@@ -495,16 +1040,24 @@ fn clean_html(html: &str) -> String {
     result.trim().to_string()
 }
 
-fn get_best_image(images: &[LastFmImage]) -> Option<String> {
+/// Picks an image URL, trying `preferred` first and then the largest available
+///
+/// Empty URLs and Last.fm's placeholder star are skipped, so `None` means there
+/// is no real image and callers can fall back to other sources.
+fn get_best_image(images: &[LastFmImage], preferred: Option<ImageSize>) -> Option<String> {
     if images.is_empty() {
         return None;
     }
 
-    let size_order = ["mega", "extralarge", "large", "medium", "small"];
+    let size_order = preferred.into_iter().chain(
+        ImageSize::LARGEST_FIRST
+            .into_iter()
+            .filter(|s| Some(*s) != preferred),
+    );
 
     for size in size_order {
-        if let Some(img) = images.iter().find(|i| i.size == size) {
-            if !img.text.is_empty() {
+        if let Some(img) = images.iter().find(|i| i.size == size.as_str()) {
+            if is_real_image(img) {
                 return Some(img.text.clone());
             }
         }
@@ -512,6 +1065,518 @@ fn get_best_image(images: &[LastFmImage]) -> Option<String> {
 
     images
         .iter()
-        .find(|i| !i.text.is_empty())
+        .find(|i| is_real_image(i))
         .map(|i| i.text.clone())
 }
+
+/// Whether an image has a URL that isn't Last.fm's placeholder
+fn is_real_image(image: &LastFmImage) -> bool {
+    !image.text.is_empty() && !image.text.contains(PLACEHOLDER_IMAGE_HASH)
+}
+
+/// Cache key suffix keeping entries for different image sizes apart
+///
+/// Empty for the default size so existing cache entries stay valid.
+fn image_key_suffix(image_size: Option<ImageSize>) -> String {
+    image_size
+        .map(|size| format!(":{}", size.as_str()))
+        .unwrap_or_default()
+}
+
+/// Current Unix time in seconds
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Whether a cache entry loaded from disk is still useful
+///
+/// Fresh entries are kept, and so are expired ones that can be revalidated,
+/// up to `REVALIDATE_MAX_AGE_SECS`.
+fn is_worth_keeping(now: u64, timestamp: u64, validators: &Validators) -> bool {
+    let age = now.saturating_sub(timestamp);
+    age < CACHE_TTL_SECS || (!validators.is_empty() && age < REVALIDATE_MAX_AGE_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::test_support::{MockRequest, MockResponse, MockServer};
+
+    fn track(artist: &str, name: &str) -> ProcessedTrackInfo {
+        ProcessedTrackInfo {
+            name: name.to_string(),
+            artist: artist.to_string(),
+            album: None,
+            duration: None,
+            playcount: None,
+            listeners: None,
+            tags: Vec::new(),
+            wiki: None,
+            url: String::new(),
+            image: None,
+        }
+    }
+
+    /// A service that never touches the disk cache or the network
+    async fn offline_service() -> LastFmService {
+        let service = LastFmService::new(String::new(), reqwest::Client::new());
+        *service.cache_loaded.write().await = true;
+        service.set_offline(true);
+        service
+    }
+
+    #[tokio::test]
+    async fn spelling_variants_share_a_cached_track() {
+        let service = offline_service().await;
+        let corrected = track("The Beatles", "Hey Jude");
+
+        service
+            .store_track(
+                "track:beatles:hey jude".to_string(),
+                &corrected,
+                None,
+                Validators::default(),
+                now_secs(),
+            )
+            .await;
+
+        for (artist, name) in [("Beatles", "Hey Jude"), ("THE BEATLES", "hey jude")] {
+            let cached = service.get_track_info(artist, name, None).await.unwrap();
+            assert_eq!(cached.artist, "The Beatles");
+        }
+    }
+
+    #[tokio::test]
+    async fn corrected_entry_stored_during_the_fetch_is_kept() {
+        let service = offline_service().await;
+        let fetched_at = now_secs();
+        let key = "track:the beatles:hey jude".to_string();
+        service.track_cache.write().await.insert(
+            key.clone(),
+            (
+                track("The Beatles", "Kept"),
+                fetched_at,
+                Validators::default(),
+            ),
+        );
+
+        service
+            .store_track(
+                "track:beatles:hey jude".to_string(),
+                &track("The Beatles", "Hey Jude"),
+                None,
+                Validators::default(),
+                fetched_at,
+            )
+            .await;
+        assert_eq!(service.track_cache.read().await[&key].0.name, "Kept");
+
+        // One stored before the fetch started is replaced
+        service
+            .store_track(
+                "track:beatles:hey jude".to_string(),
+                &track("The Beatles", "Hey Jude"),
+                None,
+                Validators::default(),
+                fetched_at + 1,
+            )
+            .await;
+        assert_eq!(service.track_cache.read().await[&key].0.name, "Hey Jude");
+    }
+
+    /// A service talking to `server` that persists its cache in `dir`
+    fn mock_service(server: &MockServer, dir: &Path) -> LastFmService {
+        let mut service = LastFmService::new("key".to_string(), reqwest::Client::new());
+        service.api_url = server.url().to_string();
+        service.cache_file = dir.join("lastfm_cache.json");
+        service
+    }
+
+    fn image_list(url: &str) -> serde_json::Value {
+        serde_json::json!([
+            {"#text": "", "size": "small"},
+            {"#text": url, "size": "extralarge"}
+        ])
+    }
+
+    fn track_json(name: &str, artist: &str, album: Option<&str>) -> serde_json::Value {
+        serde_json::json!({"track": {
+            "name": name,
+            "artist": {"name": artist},
+            "album": album.map(|title| serde_json::json!({"title": title, "image": []})),
+            "url": "https://www.last.fm/music/track",
+        }})
+    }
+
+    fn artist_json(name: &str, image: &str) -> serde_json::Value {
+        serde_json::json!({"artist": {
+            "name": name,
+            "image": image_list(image),
+            "url": "https://www.last.fm/music/artist",
+        }})
+    }
+
+    fn album_json(name: &str, artist: &str, image: &str) -> serde_json::Value {
+        serde_json::json!({"album": {
+            "name": name,
+            "artist": artist,
+            "image": image_list(image),
+            "url": "https://www.last.fm/music/album",
+        }})
+    }
+
+    fn methods(requests: &[MockRequest]) -> Vec<String> {
+        requests
+            .iter()
+            .filter_map(|request| request.query("method"))
+            .collect()
+    }
+
+    fn music_file(artist: &str, title: &str, album: Option<&str>) -> MusicFile {
+        let mut file = MusicFile::new(format!("/music/{}.mp3", title), Some(title.to_string()));
+        file.artist = Some(artist.to_string());
+        file.album = album.map(ToString::to_string);
+        file
+    }
+
+    #[tokio::test]
+    async fn tracks_without_an_image_fall_back_to_album_then_artist_art() {
+        let server = MockServer::start(|request| {
+            let body = match request.query("method").as_deref() {
+                Some("track.getinfo") => match request.query("track").as_deref() {
+                    Some("Song") => track_json("Song", "Artist", Some("Album")),
+                    _ => track_json("Loose", "Artist", None),
+                },
+                Some("album.getinfo") => album_json("Album", "Artist", "http://img/album.jpg"),
+                Some("artist.getinfo") => artist_json("Artist", "http://img/artist.jpg"),
+                _ => return MockResponse::new(404, ""),
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+
+        let enriched = service
+            .enrich_tracks_batch(vec![music_file("Artist", "Song", Some("Album"))])
+            .await
+            .unwrap();
+        assert_eq!(
+            enriched[0].album_art_url.as_deref(),
+            Some("http://img/album.jpg")
+        );
+        assert_eq!(
+            methods(&server.requests()),
+            ["track.getinfo", "album.getinfo"]
+        );
+
+        // Without an album the artist image is used
+        let enriched = service
+            .enrich_tracks_batch(vec![music_file("Artist", "Loose", None)])
+            .await
+            .unwrap();
+        assert_eq!(
+            enriched[0].album_art_url.as_deref(),
+            Some("http://img/artist.jpg")
+        );
+        assert_eq!(
+            methods(&server.requests()),
+            [
+                "track.getinfo",
+                "album.getinfo",
+                "track.getinfo",
+                "artist.getinfo"
+            ]
+        );
+    }
+
+    /// Answers `artist.getinfo` with 503 for the first `failures` requests
+    fn flaky_server(failures: usize) -> MockServer {
+        let served = std::sync::atomic::AtomicUsize::new(0);
+        MockServer::start(move |_| {
+            if served.fetch_add(1, Ordering::SeqCst) < failures {
+                MockResponse::new(503, "")
+            } else {
+                MockResponse::new(200, artist_json("Artist", "http://img/a.jpg").to_string())
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_up_to_the_attempt_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = flaky_server(2);
+        let service = mock_service(&server, dir.path()).with_retry_attempts(3);
+        let artist = service.get_artist_info("Artist", None).await.unwrap();
+        assert_eq!(artist.name, "Artist");
+        assert_eq!(server.requests().len(), 3);
+
+        let dir = tempfile::tempdir().unwrap();
+        let server = flaky_server(2);
+        let service = mock_service(&server, dir.path()).with_retry_attempts(2);
+        assert!(matches!(
+            service.get_artist_info("Artist", None).await,
+            Err(AppError::ExternalApi(message)) if message.contains("503")
+        ));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn lastfm_errors_are_not_retried() {
+        let server = MockServer::start(|_| {
+            MockResponse::new(200, r#"{"error": 6, "message": "Artist not found"}"#)
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+
+        assert!(matches!(
+            service.get_artist_info("Nobody", None).await,
+            Err(AppError::LastFmApi { code: 6, .. })
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    fn image(size: &str, url: &str) -> LastFmImage {
+        LastFmImage {
+            text: url.to_string(),
+            size: size.to_string(),
+        }
+    }
+
+    #[test]
+    fn preferred_image_size_is_picked_when_available() {
+        let images = [
+            image("small", "http://img/34s.jpg"),
+            image("medium", "http://img/64s.jpg"),
+            image("extralarge", "http://img/300x300.jpg"),
+        ];
+
+        assert_eq!(
+            get_best_image(&images, Some(ImageSize::Medium)).as_deref(),
+            Some("http://img/64s.jpg")
+        );
+        // Largest first by default
+        assert_eq!(
+            get_best_image(&images, None).as_deref(),
+            Some("http://img/300x300.jpg")
+        );
+        // A missing size falls back to the largest available
+        assert_eq!(
+            get_best_image(&images, Some(ImageSize::Large)).as_deref(),
+            Some("http://img/300x300.jpg")
+        );
+        // An empty URL for the preferred size counts as missing
+        let images = [image("medium", ""), image("small", "http://img/34s.jpg")];
+        assert_eq!(
+            get_best_image(&images, Some(ImageSize::Medium)).as_deref(),
+            Some("http://img/34s.jpg")
+        );
+        assert_eq!(get_best_image(&[], Some(ImageSize::Medium)), None);
+    }
+
+    #[test]
+    fn placeholder_star_is_not_an_image() {
+        let placeholder = format!(
+            "https://lastfm.freetls.fastly.net/i/u/300x300/{}.png",
+            PLACEHOLDER_IMAGE_HASH
+        );
+        let real = "https://lastfm.freetls.fastly.net/i/u/64s/cover.jpg";
+
+        assert_eq!(
+            get_best_image(&[image("extralarge", &placeholder)], None),
+            None
+        );
+        // A real image of another size is used instead
+        assert_eq!(
+            get_best_image(
+                &[image("extralarge", &placeholder), image("medium", real)],
+                None
+            )
+            .as_deref(),
+            Some(real)
+        );
+    }
+
+    /// Answers every method with the art of "Album" by "Artist"
+    fn album_server() -> MockServer {
+        MockServer::start(|request| {
+            let body = match request.query("method").as_deref() {
+                Some("track.getinfo") => {
+                    let name = request.query("track").unwrap_or_default();
+                    track_json(&name, "Artist", None)
+                }
+                Some("album.getinfo") => album_json("Album", "Artist", "http://img/album.jpg"),
+                _ => artist_json("Artist", "http://img/artist.jpg"),
+            };
+            MockResponse::new(200, body.to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn album_enrichment_makes_one_request_per_album() {
+        let tracks = || {
+            vec![
+                music_file("Artist", "One", Some("Album")),
+                music_file("Artist", "Two", Some("album ")),
+                music_file("Artist", "Three", Some("Album")),
+            ]
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let server = album_server();
+        let service = mock_service(&server, dir.path());
+        let enriched = service.enrich_tracks_by_album(tracks()).await.unwrap();
+        assert_eq!(methods(&server.requests()), ["album.getinfo"]);
+        let titles: Vec<_> = enriched
+            .iter()
+            .map(|track| track.original.title.as_deref().unwrap())
+            .collect();
+        assert_eq!(titles, ["One", "Two", "Three"]);
+        assert!(enriched
+            .iter()
+            .all(|track| track.album_art_url.as_deref() == Some("http://img/album.jpg")));
+
+        // The per-track batch looks every track up
+        let dir = tempfile::tempdir().unwrap();
+        let server = album_server();
+        let service = mock_service(&server, dir.path());
+        service.enrich_tracks_batch(tracks()).await.unwrap();
+        let track_lookups = methods(&server.requests())
+            .into_iter()
+            .filter(|method| method == "track.getinfo")
+            .count();
+        assert_eq!(track_lookups, 3);
+    }
+
+    #[tokio::test]
+    async fn stale_entries_are_revalidated_with_their_etag() {
+        let server = MockServer::start(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                return MockResponse::new(304, "");
+            }
+            MockResponse::new(200, artist_json("Artist", "http://img/a.jpg").to_string())
+                .with_header("ETag", "\"v1\"")
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+        service.get_artist_info("Artist", None).await.unwrap();
+
+        // Age the entry past its TTL
+        let key = {
+            let mut cache = service.artist_cache.write().await;
+            let (key, entry) = cache.iter_mut().next().unwrap();
+            entry.1 = now_secs() - CACHE_TTL_SECS - 1;
+            key.clone()
+        };
+
+        let artist = service.get_artist_info("Artist", None).await.unwrap();
+        assert_eq!(artist.image.as_deref(), Some("http://img/a.jpg"));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+        // The 304 restarted the TTL, so the next lookup is served from the cache
+        assert!(service.artist_cache.read().await[&key].1 + CACHE_TTL_SECS > now_secs());
+        service.get_artist_info("Artist", None).await.unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn offline_mode_serves_the_cache_without_requests() {
+        let server = album_server();
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+        service.get_artist_info("Artist", None).await.unwrap();
+        for entry in service.artist_cache.write().await.values_mut() {
+            entry.1 = 0;
+        }
+
+        service.set_offline(true);
+        // Expired entries are still served
+        let artist = service.get_artist_info("Artist", None).await.unwrap();
+        assert_eq!(artist.image.as_deref(), Some("http://img/artist.jpg"));
+        assert!(matches!(
+            service.get_album_info("Artist", "Album", None).await,
+            Err(AppError::Offline(_))
+        ));
+        let enriched = service
+            .enrich_tracks_batch(vec![music_file("Other", "Song", Some("Album"))])
+            .await
+            .unwrap();
+        assert_eq!(enriched[0].album_art_url, None);
+        assert_eq!(server.requests().len(), 1);
+
+        service.set_offline(false);
+        service
+            .get_album_info("Artist", "Album", None)
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_stats_count_hits_and_misses_until_cleared() {
+        let server = album_server();
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+
+        service.get_artist_info("Artist", None).await.unwrap();
+        service.get_artist_info("Artist", None).await.unwrap();
+        service.get_artist_info("ARTIST", None).await.unwrap();
+        let stats = service.cache_stats().await.unwrap();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!(stats.artist_entries, 1);
+
+        service.clear_cache().await.unwrap();
+        let stats = service.cache_stats().await.unwrap();
+        assert_eq!((stats.hits, stats.misses), (0, 0));
+        assert_eq!(stats.artist_entries, 0);
+
+        // The cleared file isn't loaded back
+        service.get_artist_info("Artist", None).await.unwrap();
+        let stats = service.cache_stats().await.unwrap();
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn autocorrect_setting_is_sent_and_cached_apart() {
+        let server = album_server();
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+
+        service.get_artist_info("Artist", None).await.unwrap();
+        service.set_autocorrect(false);
+        service.get_artist_info("Artist", None).await.unwrap();
+        service.set_autocorrect(true);
+        // Served from the autocorrected entry
+        service.get_artist_info("Artist", None).await.unwrap();
+
+        let autocorrect: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|request| request.query("autocorrect").unwrap())
+            .collect();
+        assert_eq!(autocorrect, ["1", "0"]);
+        assert_eq!(service.artist_cache.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn uncached_top_tracks_are_unavailable_offline() {
+        let server = album_server();
+        let dir = tempfile::tempdir().unwrap();
+        let service = mock_service(&server, dir.path());
+
+        service.set_offline(true);
+        // Not an empty list, which would read as an artist without top tracks
+        assert!(matches!(
+            service.get_artist_top_tracks("Artist", None).await,
+            Err(AppError::Offline(_))
+        ));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! Runtime-adjustable logging
+//!
+//! Logs go to the console and to a daily-rotated file in the app data directory,
+//! so packaged builds (which have no console) still leave something to attach to
+//! a bug report. Each output has its own filter: the console one sits behind a
+//! reload layer so `set_log_level` can raise verbosity without restarting, while
+//! the file keeps `LOG_FILE_LEVEL` (default `info`).
+
+use std::path::{Path, PathBuf};
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+use crate::errors::{AppError, FileError};
+use crate::utils::get_app_data_dir;
+
+/// Levels accepted by `set_log_level`
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+/// Environment variable with the log file's filter
+pub const LOG_FILE_LEVEL_ENV: &str = "LOG_FILE_LEVEL";
+/// Log files are named `musicplayer.YYYY-MM-DD.log`
+const LOG_FILE_PREFIX: &str = "musicplayer";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// Handle to the live tracing filter, managed by Tauri
+pub struct LogState {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Flushes the log file on drop; `None` when the file couldn't be opened
+    _file_guard: Option<WorkerGuard>,
+}
+
+impl LogState {
+    /// Installs the global subscriber
+    ///
+    /// The console follows `RUST_LOG` until changed. If the log directory can't be
+    /// created, logging continues on the console only.
+    pub fn init() -> Self {
+        let (console_filter, handle) = reload::Layer::new(EnvFilter::from_default_env());
+        let console = fmt::layer().with_filter(console_filter);
+
+        let file_filter =
+            EnvFilter::try_from_env(LOG_FILE_LEVEL_ENV).unwrap_or_else(|_| EnvFilter::new("info"));
+        let (file, file_guard) = match Self::file_layer(&Self::log_dir(), file_filter) {
+            Ok((layer, guard)) => (Some(layer), Some(guard)),
+            Err(e) => {
+                eprintln!("File logging disabled: {}", e);
+                (None, None)
+            }
+        };
+
+        if let Err(e) = tracing_subscriber::registry()
+            .with(console)
+            .with(file)
+            .try_init()
+        {
+            eprintln!("Tracing subscriber already set: {}", e);
+        }
+
+        Self {
+            handle,
+            _file_guard: file_guard,
+        }
+    }
+
+    /// Directory the log files are written to
+    pub fn log_dir() -> PathBuf {
+        get_app_data_dir().join("logs")
+    }
+
+    /// Builds the layer writing to daily-rotated files in `dir`
+    ///
+    /// Lines are written on a background thread until the guard is dropped.
+    fn file_layer<S>(
+        dir: &Path,
+        filter: EnvFilter,
+    ) -> Result<(impl Layer<S> + Send + Sync, WorkerGuard), String>
+    where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| e.to_string())?;
+
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        let layer = fmt::layer()
+            .with_ansi(false)
+            .with_writer(writer)
+            .with_filter(filter);
+        Ok((layer, guard))
+    }
+
+    /// Path of the log file currently being written (the newest one)
+    pub fn current_log_file() -> Result<PathBuf, AppError> {
+        Self::newest_log_file(&Self::log_dir())
+    }
+
+    /// Newest log file in `dir`
+    fn newest_log_file(dir: &Path) -> Result<PathBuf, AppError> {
+        let prefix = format!("{}.", LOG_FILE_PREFIX);
+        let suffix = format!(".{}", LOG_FILE_SUFFIX);
+
+        std::fs::read_dir(dir)
+            .map_err(|_| FileError::NotFound(dir.display().to_string()))?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with(&prefix) && name.ends_with(&suffix)
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+            .ok_or_else(|| FileError::NotFound(format!("No log file in {}", dir.display())).into())
+    }
+
+    /// Replaces the console filter with `level` for every target
+    ///
+    /// Returns the normalized level; anything outside [`LOG_LEVELS`] is rejected.
+    /// The log file keeps its own level.
+    pub fn set_level(&self, level: &str) -> Result<String, AppError> {
+        let level = level.trim().to_ascii_lowercase();
+        if !LOG_LEVELS.contains(&level.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid log level '{}', expected one of: {}",
+                level,
+                LOG_LEVELS.join(", ")
+            )));
+        }
+
+        self.handle
+            .reload(EnvFilter::new(&level))
+            .map_err(|e| AppError::Unknown(format!("Failed to update log filter: {}", e)))?;
+
+        tracing::info!("📝 Log level set to {}", level);
+        Ok(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Log output captured in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Captured {
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn set_level_changes_the_live_filter() {
+        let captured = Captured::default();
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone())
+                .with_filter(filter),
+        );
+        let logging = LogState {
+            handle,
+            _file_guard: None,
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("before the change");
+            assert_eq!(logging.set_level(" DEBUG ").unwrap(), "debug");
+            tracing::debug!("after the change");
+            logging.set_level("error").unwrap();
+            tracing::info!("after raising it");
+        });
+
+        let output = captured.text();
+        assert!(!output.contains("before the change"));
+        assert!(output.contains("after the change"));
+        assert!(!output.contains("after raising it"));
+    }
+
+    #[test]
+    fn writes_the_rolling_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(LogState::newest_log_file(dir.path()).is_err());
+
+        let (layer, guard) = LogState::file_layer(dir.path(), EnvFilter::new("info")).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("written to the file");
+            tracing::debug!("below the file level");
+        });
+        // Dropping the guard flushes the background writer
+        drop(guard);
+
+        let file = LogState::newest_log_file(dir.path()).unwrap();
+        let name = file.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(
+            name.starts_with("musicplayer.") && name.ends_with(".log"),
+            "{}",
+            name
+        );
+        let content = std::fs::read_to_string(file).unwrap();
+        assert!(content.contains("written to the file"));
+        assert!(!content.contains("below the file level"));
+    }
+
+    #[test]
+    fn rejects_unknown_levels() {
+        let (_filter, handle) = reload::Layer::<EnvFilter, Registry>::new(EnvFilter::new("info"));
+        let logging = LogState {
+            handle,
+            _file_guard: None,
+        };
+
+        assert!(matches!(
+            logging.set_level("verbose"),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -3,12 +3,37 @@
 //! This module contains services that encapsulate business logic
 //! and coordinate between domain models and external APIs.
 
+pub mod acoustid;
+pub mod allowed_roots;
+pub mod art_cache;
+pub mod convert;
+pub mod coverart;
+pub mod diagnostics;
 pub mod download;
+pub mod download_history;
+pub mod download_queue;
 pub mod file;
+pub mod http;
 pub mod lastfm;
+pub mod logging;
+pub mod recent_folders;
+pub mod settings;
+pub mod shutdown;
 pub mod spotify;
+pub mod spotify_client;
 
+pub use acoustid::AcoustIdService;
+pub use allowed_roots::AllowedRoots;
+pub use art_cache::ArtCacheService;
+pub use convert::ConvertService;
+pub use coverart::CoverArtService;
+pub use diagnostics::DiagnosticsService;
 pub use download::DownloadService;
-pub use file::FileService;
+pub use file::{FileService, ScanState};
+pub use http::{HttpService, HttpState};
 pub use lastfm::LastFmService;
+pub use logging::LogState;
+pub use recent_folders::RecentFolders;
+pub use settings::SettingsState;
+pub use shutdown::ShutdownState;
 pub use spotify::SpotifyState;
@@ -0,0 +1,164 @@
+//! Recently scanned folders
+//!
+//! Keeps the last few folders `scan_music_folder` succeeded on in
+//! `recent_folders.json` in the app data dir, most recent first, so the UI can
+//! offer them as a quick pick.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use crate::utils::get_app_data_dir;
+
+/// Folders remembered at most
+const MAX_RECENT_FOLDERS: usize = 10;
+
+/// Serializes read-modify-write cycles from concurrent scans
+static RECENT_LOCK: Mutex<()> = Mutex::new(());
+
+/// One recently scanned folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFolder {
+    /// Canonical folder path
+    pub path: String,
+    /// Unix timestamp (seconds) of the last successful scan
+    pub last_scanned: u64,
+    /// Audio files found by that scan
+    pub file_count: usize,
+}
+
+/// Reads and writes the recent folders file
+pub struct RecentFolders;
+
+impl RecentFolders {
+    /// Location of the recent folders file
+    fn file_path() -> PathBuf {
+        get_app_data_dir().join("recent_folders.json")
+    }
+
+    fn lock() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+        RECENT_LOCK
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("Recent folders lock poisoned: {}", e)))
+    }
+
+    /// Recently scanned folders, most recent first
+    ///
+    /// A missing or unreadable file yields an empty list.
+    pub fn list() -> Vec<RecentFolder> {
+        Self::list_from(&Self::file_path())
+    }
+
+    fn list_from(path: &Path) -> Vec<RecentFolder> {
+        if !path.exists() {
+            return Vec::new();
+        }
+
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Could not read recent folders: {}", e);
+                Vec::new()
+            })
+    }
+
+    /// Moves `folder` to the front of the list, dropping the oldest beyond the cap
+    ///
+    /// `folder` must be canonical so the same folder is never listed twice.
+    pub fn add(folder: &Path, file_count: usize) -> Result<(), AppError> {
+        let _lock = Self::lock()?;
+        Self::add_to(&Self::file_path(), folder, file_count)
+    }
+
+    fn add_to(file: &Path, folder: &Path, file_count: usize) -> Result<(), AppError> {
+        let last_scanned = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = folder.to_string_lossy().to_string();
+
+        let mut folders = Self::list_from(file);
+        folders.retain(|existing| existing.path != path);
+        folders.insert(
+            0,
+            RecentFolder {
+                path,
+                last_scanned,
+                file_count,
+            },
+        );
+        folders.truncate(MAX_RECENT_FOLDERS);
+
+        let json = serde_json::to_string_pretty(&folders)
+            .map_err(|e| AppError::Unknown(format!("Failed to serialize recent folders: {}", e)))?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, json)?;
+        Ok(())
+    }
+
+    /// Records a successful scan, logging instead of failing so the scan result is kept
+    pub fn record(folder: &Path, file_count: usize) {
+        if let Err(e) = Self::add(folder, file_count) {
+            tracing::warn!("⚠️ Could not update recent folders: {}", e);
+        }
+    }
+
+    /// Deletes the recent folders file
+    pub fn clear() -> Result<(), AppError> {
+        let _lock = Self::lock()?;
+        match fs::remove_file(Self::file_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(file: &Path) -> Vec<String> {
+        RecentFolders::list_from(file)
+            .into_iter()
+            .map(|folder| folder.path)
+            .collect()
+    }
+
+    #[test]
+    fn rescanned_folders_move_to_the_front() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("recent_folders.json");
+        assert!(RecentFolders::list_from(&file).is_empty());
+
+        for folder in ["/music/a", "/music/b", "/music/c"] {
+            RecentFolders::add_to(&file, Path::new(folder), 1).unwrap();
+        }
+        assert_eq!(paths(&file), ["/music/c", "/music/b", "/music/a"]);
+
+        RecentFolders::add_to(&file, Path::new("/music/a"), 42).unwrap();
+        assert_eq!(paths(&file), ["/music/a", "/music/c", "/music/b"]);
+        assert_eq!(RecentFolders::list_from(&file)[0].file_count, 42);
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("recent_folders.json");
+
+        for i in 0..MAX_RECENT_FOLDERS + 3 {
+            RecentFolders::add_to(&file, Path::new(&format!("/music/{}", i)), i).unwrap();
+        }
+
+        let folders = paths(&file);
+        assert_eq!(folders.len(), MAX_RECENT_FOLDERS);
+        assert_eq!(folders[0], format!("/music/{}", MAX_RECENT_FOLDERS + 2));
+        assert_eq!(folders[MAX_RECENT_FOLDERS - 1], "/music/3");
+    }
+}
@@ -0,0 +1,211 @@
+//! Persisted app settings
+//!
+//! Settings live in `settings.json` under the app config directory and are
+//! validated before being saved, so the values commands fall back to are
+//! always usable.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::domain::settings::{Settings, MAX_CONCURRENT_DOWNLOADS, MIN_CONCURRENT_DOWNLOADS};
+use crate::errors::AppError;
+use crate::utils::{
+    get_app_config_dir, validate_directory, validate_download_format, validate_file,
+};
+
+/// Current settings, managed by Tauri
+pub struct SettingsState {
+    settings: RwLock<Settings>,
+}
+
+impl SettingsState {
+    /// Loads the saved settings, falling back to the defaults
+    ///
+    /// A missing, unreadable or invalid settings file is logged and ignored.
+    pub fn load() -> Self {
+        let settings = Self::read()
+            .and_then(|settings| Self::validate(&settings).map(|_| settings))
+            .unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Using default settings: {}", e);
+                Settings::default()
+            });
+
+        Self {
+            settings: RwLock::new(settings),
+        }
+    }
+
+    /// Location of the settings file
+    fn file_path() -> PathBuf {
+        get_app_config_dir().join("settings.json")
+    }
+
+    /// Reads the settings file, returning the defaults when there is none
+    fn read() -> Result<Settings, AppError> {
+        Self::read_from(&Self::file_path())
+    }
+
+    fn read_from(path: &Path) -> Result<Settings, AppError> {
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| AppError::Unknown(format!("Failed to parse settings: {}", e)))
+    }
+
+    /// Writes the settings file
+    fn save(settings: &Settings) -> Result<(), AppError> {
+        Self::save_to(&Self::file_path(), settings)
+    }
+
+    fn save_to(path: &Path, settings: &Settings) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(settings)
+            .map_err(|e| AppError::Unknown(format!("Failed to serialize settings: {}", e)))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Checks every value before it is stored
+    fn validate(settings: &Settings) -> Result<(), AppError> {
+        validate_download_format(&settings.default_format)?;
+
+        if !(MIN_CONCURRENT_DOWNLOADS..=MAX_CONCURRENT_DOWNLOADS)
+            .contains(&settings.max_concurrent_downloads)
+        {
+            return Err(AppError::Validation(format!(
+                "Concurrent downloads must be between {} and {}, got {}",
+                MIN_CONCURRENT_DOWNLOADS,
+                MAX_CONCURRENT_DOWNLOADS,
+                settings.max_concurrent_downloads
+            )));
+        }
+        if settings.max_consecutive_failures == 0 {
+            return Err(AppError::Validation(
+                "Consecutive failures before aborting must be at least 1".to_string(),
+            ));
+        }
+        if let Some(ref dir) = settings.output_dir {
+            validate_directory(dir)?;
+        }
+        if let Some(ref file) = settings.cookie_file {
+            validate_file(file)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the current settings
+    pub fn get(&self) -> Result<Settings, AppError> {
+        self.settings
+            .read()
+            .map(|settings| settings.clone())
+            .map_err(|e| AppError::Concurrency(format!("Settings lock poisoned: {}", e)))
+    }
+
+    /// Validates, saves and applies new settings, returning them
+    ///
+    /// Nothing changes when a value is invalid or the file can't be written.
+    pub fn update(&self, settings: Settings) -> Result<Settings, AppError> {
+        Self::validate(&settings)?;
+
+        let mut current = self
+            .settings
+            .write()
+            .map_err(|e| AppError::Concurrency(format!("Settings lock poisoned: {}", e)))?;
+        Self::save(&settings)?;
+        *current = settings.clone();
+
+        tracing::info!("⚙️ Settings updated");
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_settings_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config/settings.json");
+        assert_eq!(
+            SettingsState::read_from(&path).unwrap(),
+            Settings::default()
+        );
+
+        let settings = Settings {
+            default_format: "flac".to_string(),
+            output_dir: Some(dir.path().to_string_lossy().into_owned()),
+            max_concurrent_downloads: 2,
+            max_consecutive_failures: 3,
+            cookie_file: None,
+            follow_symlinks: true,
+        };
+        SettingsState::validate(&settings).unwrap();
+        SettingsState::save_to(&path, &settings).unwrap();
+
+        assert_eq!(SettingsState::read_from(&path).unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_fields_take_their_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"defaultFormat":"opus"}"#).unwrap();
+
+        let settings = SettingsState::read_from(&path).unwrap();
+        assert_eq!(settings.default_format, "opus");
+        assert_eq!(
+            Settings {
+                default_format: Settings::default().default_format,
+                ..settings
+            },
+            Settings::default()
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_rejected_and_not_applied() {
+        let dir = tempfile::tempdir().unwrap();
+        let invalid = [
+            Settings {
+                default_format: "exe".to_string(),
+                ..Settings::default()
+            },
+            Settings {
+                max_concurrent_downloads: MIN_CONCURRENT_DOWNLOADS - 1,
+                ..Settings::default()
+            },
+            Settings {
+                max_concurrent_downloads: MAX_CONCURRENT_DOWNLOADS + 1,
+                ..Settings::default()
+            },
+            Settings {
+                max_consecutive_failures: 0,
+                ..Settings::default()
+            },
+            Settings {
+                output_dir: Some(dir.path().join("missing").to_string_lossy().into_owned()),
+                ..Settings::default()
+            },
+            Settings {
+                cookie_file: Some(dir.path().to_string_lossy().into_owned()),
+                ..Settings::default()
+            },
+        ];
+        let state = SettingsState {
+            settings: RwLock::new(Settings::default()),
+        };
+
+        for settings in invalid {
+            assert!(state.update(settings.clone()).is_err(), "{:?}", settings);
+            assert_eq!(state.get().unwrap(), Settings::default());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Application shutdown coordination
+//!
+//! Tracks the spotdl processes started by the download service so they can be
+//! stopped when the app exits instead of being orphaned.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::services::{LastFmService, ScanState};
+
+/// How long exit waits for cancelled spotdl processes to be killed
+const SHUTDOWN_GRACE_MS: u64 = 2000;
+
+/// Shared shutdown handles, managed by Tauri
+#[derive(Default)]
+pub struct ShutdownState {
+    /// Cancelled on exit; running downloads select on it and kill their child
+    downloads: CancellationToken,
+    /// Number of spotdl processes currently running
+    active_processes: Arc<AtomicUsize>,
+}
+
+/// Marks a spotdl process as running until dropped
+pub struct ProcessGuard {
+    active_processes: Arc<AtomicUsize>,
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        self.active_processes.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ShutdownState {
+    /// Token that is cancelled when the app starts shutting down
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.downloads.clone()
+    }
+
+    /// Registers a running child process; keep the guard alive while it runs
+    pub fn track_process(&self) -> ProcessGuard {
+        self.active_processes.fetch_add(1, Ordering::SeqCst);
+        ProcessGuard {
+            active_processes: Arc::clone(&self.active_processes),
+        }
+    }
+
+    /// Cleans up before exit: stops downloads and scans and flushes the Last.fm cache
+    pub fn shutdown(&self, app: &AppHandle) {
+        tracing::info!("🛑 Shutting down");
+        self.downloads.cancel();
+        if let Some(scan) = app.try_state::<ScanState>() {
+            scan.cancel_all();
+        }
+
+        if let Some(lastfm) = app.try_state::<LastFmService>() {
+            if let Err(e) = tauri::async_runtime::block_on(lastfm.flush_cache()) {
+                tracing::warn!("⚠️ Could not flush Last.fm cache: {}", e);
+            }
+        }
+
+        let remaining = self.wait_for_processes(Duration::from_millis(SHUTDOWN_GRACE_MS));
+        if remaining > 0 {
+            tracing::warn!("⚠️ {} spotdl processes still running at exit", remaining);
+        }
+    }
+
+    /// Blocks until every tracked process has stopped or `grace` has passed,
+    /// returning how many are still running
+    ///
+    /// Cancelled downloads kill their child when the runtime next polls them.
+    fn wait_for_processes(&self, grace: Duration) -> usize {
+        let deadline = Instant::now() + grace;
+        while self.active_processes.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        self.active_processes.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use tokio::process::Command;
+
+    /// Whether `pid` is still alive; a killed child awaiting reaping is a zombie
+    fn is_running(pid: u32) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|stat| {
+                let (_, after_name) = stat.rsplit_once(')')?;
+                after_name
+                    .split_whitespace()
+                    .next()
+                    .map(|state| state != "Z")
+            })
+            .unwrap_or(false)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shutdown_kills_tracked_children() {
+        let state = Arc::new(ShutdownState::default());
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let pid = child.id().unwrap();
+
+        // Same shape as a running download: the child is dropped on cancellation
+        let cancelled = state.cancellation_token();
+        let guard = state.track_process();
+        let download = tokio::spawn(async move {
+            let _guard = guard;
+            tokio::select! {
+                _ = cancelled.cancelled() => false,
+                _ = child.wait() => true,
+            }
+        });
+        assert!(is_running(pid));
+
+        state.downloads.cancel();
+        let waiting = Arc::clone(&state);
+        let remaining =
+            tokio::task::spawn_blocking(move || waiting.wait_for_processes(Duration::from_secs(5)))
+                .await
+                .unwrap();
+
+        assert_eq!(remaining, 0);
+        assert!(!download.await.unwrap(), "child exited on its own");
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while is_running(pid) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!is_running(pid));
+    }
+}
@@ -3,22 +3,95 @@
 //! Handles all Spotify API interactions including OAuth authentication,
 //! fetching user data, playlists, and tracks.
 
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use rspotify::{
-    clients::OAuthClient, model::TimeRange, scopes, AuthCodeSpotify, Config, Credentials, OAuth,
+    clients::{BaseClient, OAuthClient},
+    http::Query,
+    model::{
+        ArtistId, Country, FullEpisode, Market, Page, RecommendationsAttribute, TimeRange, TrackId,
+    },
+    Config, Credentials, OAuth, Token,
 };
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Window};
 use tiny_http::{Response, Server};
 use tokio::time::timeout;
 use tracing::instrument;
 
 use crate::domain::spotify::{
-    SpotifyArtist, SpotifyPlaylist, SpotifyTrack, SpotifyUserProfile, MAX_RETRY_ATTEMPTS,
-    OAUTH_CALLBACK_TIMEOUT_SECS, OAUTH_SERVER_ADDR, SPOTIFY_BATCH_SIZE,
+    ExportFormat, SpotifyAlbum, SpotifyArtist, SpotifyAuthStatus, SpotifyDownloadUrls,
+    SpotifyEpisode, SpotifyFollowedArtists, SpotifyPlaylist, SpotifyPlaylistPage,
+    SpotifyRecommendationTargets, SpotifyTrack, SpotifyTrackPreview, SpotifyUserProfile,
+    DEFAULT_MARKET, DEFAULT_SPOTIFY_SCOPES, KNOWN_SPOTIFY_SCOPES, MAX_OAUTH_TIMEOUT_SECS,
+    MAX_RECOMMENDATIONS_LIMIT, MAX_RECOMMENDATION_SEEDS, MAX_RETRY_ATTEMPTS,
+    MIN_OAUTH_TIMEOUT_SECS, OAUTH_CALLBACK_TIMEOUT_SECS, OAUTH_SERVER_ADDR, RETRY_BASE_DELAY_MS,
+    SPOTIFY_BATCH_SIZE, SPOTIFY_SCOPES_ENV,
 };
-use crate::errors::{AppError, SpotifyError};
+use crate::errors::{AppError, FileError, SpotifyError};
+use crate::events;
+use crate::services::spotify_client::{SpotifyAuthFlow, SpotifyClient};
+use crate::utils::{
+    extract_spotify_id, spotify_track_url, validate_output_path, with_retries, TrackExportWriter,
+};
+
+/// Emitted once the browser is open and the app waits for the OAuth callback
+#[derive(Serialize, Clone)]
+pub struct AuthWaiting {
+    /// Seconds the user has to finish logging in
+    pub timeout_secs: u64,
+}
+
+/// Liked-songs stream start event
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TracksStart {
+    /// Number of saved tracks in the library
+    pub total: u32,
+}
+
+/// One page of the liked-songs stream
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TracksBatch {
+    /// Tracks in this page
+    pub tracks: Vec<SpotifyTrack>,
+    /// Percentage of the library sent so far (0-100)
+    pub progress: u32,
+    /// Number of tracks sent so far
+    pub loaded: u32,
+    /// Number of saved tracks in the library
+    pub total: u32,
+}
+
+/// Liked-songs stream failure event
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TracksError {
+    /// Error message describing what went wrong
+    pub message: String,
+}
+
+/// Liked-songs stream completion event
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TracksComplete {
+    /// Number of tracks sent
+    pub total: u32,
+}
+
+/// Liked-songs export progress event
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ExportProgress {
+    /// Number of tracks written so far
+    pub exported: u32,
+    /// Number of saved tracks in the library
+    pub total: u32,
+}
+
+/// Item of the saved-episodes listing
+#[derive(Deserialize)]
+struct SavedEpisode {
+    episode: FullEpisode,
+}
 
 /// Thread-safe state for Spotify client
 ///
@@ -26,13 +99,13 @@ use crate::errors::{AppError, SpotifyError};
 /// as early as possible to prevent deadlocks.
 pub struct SpotifyState {
     /// Authenticated Spotify client wrapped in Arc<Mutex<>> for thread safety
-    client: Arc<Mutex<Option<AuthCodeSpotify>>>,
+    client: Arc<Mutex<Option<SpotifyClient>>>,
     /// Cached user profile information
     user: Arc<Mutex<Option<SpotifyUserProfile>>>,
     /// Rate limiting: last request timestamp
     last_request_time: Arc<Mutex<std::time::Instant>>,
     /// Cached playlists
-    playlists: Arc<Mutex<Option<Vec<SpotifyPlaylist>>>>,
+    playlists: Arc<Mutex<Option<SpotifyPlaylistPage>>>,
     /// Cached top tracks
     top_tracks: Arc<Mutex<Option<Vec<SpotifyTrack>>>>,
     /// Cached top artists
@@ -57,7 +130,7 @@ impl SpotifyState {
     ///
     /// Returns an error if no authenticated session exists or mutex is poisoned.
     /// Guard is released immediately after cloning to prevent deadlocks.
-    pub fn get_client(&self) -> Result<AuthCodeSpotify, AppError> {
+    pub fn get_client(&self) -> Result<SpotifyClient, AppError> {
         let client_opt = self
             .client
             .lock()
@@ -66,33 +139,47 @@ impl SpotifyState {
         // Clone and release guard immediately
         let client = client_opt
             .clone()
-            .ok_or_else(|| SpotifyError::NotAuthenticated)?;
+            .ok_or(SpotifyError::NotAuthenticated)?;
 
         Ok(client)
     }
 
     /// Sets the Spotify client with safe mutex access
-    pub fn set_client(&self, client: AuthCodeSpotify) -> Result<(), AppError> {
-        let mut guard = self
-            .client
-            .lock()
-            .map_err(|e| SpotifyError::ClientLock(format!("Failed to lock client: {}", e)))?;
+    ///
+    /// Drops the cached profile too, since the new session may be another account.
+    pub fn set_client(&self, client: SpotifyClient) -> Result<(), AppError> {
+        {
+            let mut guard = self
+                .client
+                .lock()
+                .map_err(|e| SpotifyError::ClientLock(format!("Failed to lock client: {}", e)))?;
+            *guard = Some(client);
+        } // Release client guard early
 
-        *guard = Some(client);
-        // Guard is dropped here automatically
+        let mut user_guard = self
+            .user
+            .lock()
+            .map_err(|e| SpotifyError::ClientLock(format!("Failed to lock user: {}", e)))?;
+        *user_guard = None;
 
         Ok(())
     }
 
     /// Clears the client and user state safely
+    ///
+    /// Also deletes rspotify's on-disk token cache, so the session can't be restored
+    /// from it after logging out. A cache that was never written is not an error.
     pub fn clear(&self) -> Result<(), AppError> {
-        {
+        let cache_path = {
             let mut client_guard = self
                 .client
                 .lock()
                 .map_err(|e| SpotifyError::ClientLock(format!("Failed to lock client: {}", e)))?;
-            *client_guard = None;
-        } // Release client guard early
+            client_guard
+                .take()
+                .map(|client| client.get_config().cache_path.clone())
+                .unwrap_or_else(|| Config::default().cache_path)
+        }; // Release client guard early
 
         {
             let mut user_guard = self
@@ -102,9 +189,29 @@ impl SpotifyState {
             *user_guard = None;
         } // Release user guard early
 
+        match std::fs::remove_file(&cache_path) {
+            Ok(()) => tracing::info!("🔐 Removed token cache {}", cache_path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
         Ok(())
     }
 
+    /// Gets the cached user profile, if one was fetched
+    pub fn get_cached_user(&self) -> Result<Option<SpotifyUserProfile>, AppError> {
+        let user = self
+            .user
+            .lock()
+            .map_err(|e| SpotifyError::ClientLock(format!("Failed to lock user: {}", e)))?;
+        Ok(user.clone())
+    }
+
+    /// Gets the authenticated user's country (ISO 3166-1 alpha-2), if the profile was fetched
+    pub fn get_user_country(&self) -> Result<Option<String>, AppError> {
+        Ok(self.get_cached_user()?.and_then(|user| user.country))
+    }
+
     /// Checks if there's an authenticated session
     pub fn is_authenticated(&self) -> bool {
         self.client
@@ -113,6 +220,57 @@ impl SpotifyState {
             .unwrap_or(false)
     }
 
+    /// Describes the session from local state only: client, token expiry and cached profile
+    pub async fn auth_status(&self) -> Result<SpotifyAuthStatus, AppError> {
+        let display_name = self.get_cached_user()?.and_then(|user| user.display_name);
+
+        let Some(token) = self.current_token().await? else {
+            return Ok(SpotifyAuthStatus {
+                authenticated: self.is_authenticated(),
+                token_expired: true,
+                display_name,
+            });
+        };
+
+        Ok(SpotifyAuthStatus {
+            authenticated: true,
+            token_expired: token.is_expired(),
+            display_name,
+        })
+    }
+
+    /// Seconds until the access token expires, negative once it has
+    ///
+    /// `None` when there is no session or the token has no expiry.
+    pub async fn token_expires_in(&self) -> Result<Option<i64>, AppError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        Ok(self
+            .current_token()
+            .await?
+            .and_then(|token| token.expires_at)
+            .map(|expires_at| expires_at.timestamp() - now))
+    }
+
+    /// Copies the session's OAuth token, `None` when not authenticated
+    async fn current_token(&self) -> Result<Option<Token>, AppError> {
+        let client = match self.get_client() {
+            Ok(client) => client,
+            Err(AppError::Spotify(SpotifyError::NotAuthenticated)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let token = client.get_token();
+        let guard = token
+            .lock()
+            .await
+            .map_err(|_| SpotifyError::ClientLock("Failed to lock token".into()))?;
+        Ok(guard.clone())
+    }
+
     /// Enforces rate limiting for Spotify API calls
     pub async fn enforce_rate_limit(&self) -> Result<(), AppError> {
         let last_time = {
@@ -139,20 +297,20 @@ impl SpotifyState {
         Ok(())
     }
 
-    /// Gets cached playlists
-    pub fn get_cached_playlists(&self) -> Result<Option<Vec<SpotifyPlaylist>>, AppError> {
+    /// Gets the cached first page of playlists
+    pub fn get_cached_playlists(&self) -> Result<Option<SpotifyPlaylistPage>, AppError> {
         let playlists = self.playlists.lock().map_err(|e| {
             AppError::Concurrency(format!("Playlists cache mutex poisoned: {}", e))
         })?;
         Ok(playlists.clone())
     }
 
-    /// Caches playlists
-    pub fn cache_playlists(&self, playlists: &[SpotifyPlaylist]) -> Result<(), AppError> {
+    /// Caches the first page of playlists (offset 0)
+    pub fn cache_playlists(&self, page: &SpotifyPlaylistPage) -> Result<(), AppError> {
         let mut cache = self.playlists.lock().map_err(|e| {
             AppError::Concurrency(format!("Playlists cache mutex poisoned: {}", e))
         })?;
-        *cache = Some(playlists.to_vec());
+        *cache = Some(page.clone());
         Ok(())
     }
 
@@ -195,19 +353,54 @@ impl SpotifyState {
 pub struct SpotifyService;
 
 impl SpotifyService {
-    /// Initializes and authenticates with Spotify using Authorization Code Flow
+    /// Initializes and authenticates with Spotify using the configured OAuth flow
+    ///
+    /// The flow comes from `SPOTIFY_AUTH_FLOW` (Authorization Code unless set to
+    /// `pkce`). `timeout_secs` is how long to wait for the user to log in, clamped
+    /// to 30-600 seconds (default 120).
+    #[instrument(skip_all)]
+    pub async fn authenticate(
+        state: &SpotifyState,
+        app: &AppHandle,
+        timeout_secs: Option<u64>,
+    ) -> Result<String, AppError> {
+        Self::authenticate_with_flow(state, app, timeout_secs, SpotifyAuthFlow::from_env()).await
+    }
+
+    /// Authenticates with the Authorization Code flow with PKCE
+    ///
+    /// Only `RSPOTIFY_CLIENT_ID` is needed, so no client secret has to ship with
+    /// the app.
     #[instrument(skip_all)]
-    pub async fn authenticate(state: &SpotifyState, app: &AppHandle) -> Result<String, AppError> {
-        tracing::info!("🔐 Starting Spotify OAuth authentication...");
+    pub async fn authenticate_pkce(
+        state: &SpotifyState,
+        app: &AppHandle,
+        timeout_secs: Option<u64>,
+    ) -> Result<String, AppError> {
+        Self::authenticate_with_flow(state, app, timeout_secs, SpotifyAuthFlow::Pkce).await
+    }
+
+    /// Runs the browser login for `flow` and stores the authenticated client
+    async fn authenticate_with_flow(
+        state: &SpotifyState,
+        app: &AppHandle,
+        timeout_secs: Option<u64>,
+        flow: SpotifyAuthFlow,
+    ) -> Result<String, AppError> {
+        tracing::info!("🔐 Starting Spotify OAuth authentication ({:?})...", flow);
+        let timeout_secs = Self::oauth_timeout_secs(timeout_secs);
         
         let creds = Credentials::from_env().ok_or_else(|| {
             tracing::error!("❌ Spotify credentials not found in environment");
             SpotifyError::CredentialsNotFound
         })?;
+        flow.check_credentials(&creds).inspect_err(|_| {
+            tracing::error!("❌ Client secret missing; set it or use the PKCE flow");
+        })?;
         tracing::info!("✅ Credentials loaded from environment");
 
-        let spotify = Self::create_spotify_client(creds)?;
-        let auth_url = spotify.get_authorize_url(false).map_err(|e| {
+        let mut spotify = Self::create_spotify_client(flow, creds)?;
+        let auth_url = spotify.authorize_url().map_err(|e| {
             tracing::error!("❌ Failed to generate auth URL: {}", e);
             SpotifyError::AuthenticationFailed(format!("Failed to generate auth URL: {}", e))
         })?;
@@ -216,7 +409,8 @@ impl SpotifyService {
         Self::open_browser(app, &auth_url)?;
         tracing::info!("🌐 Browser opened, waiting for OAuth callback on http://{}/callback", OAUTH_SERVER_ADDR);
         
-        let code = Self::wait_for_oauth_callback().await?;
+        let _ = app.emit(events::SPOTIFY_AUTH_WAITING, AuthWaiting { timeout_secs });
+        let code = Self::wait_for_oauth_callback(timeout_secs, &spotify.get_oauth().state).await?;
         tracing::info!("✅ OAuth callback received, exchanging code for token...");
         
         Self::exchange_token(&spotify, &code).await?;
@@ -228,19 +422,26 @@ impl SpotifyService {
         Ok("Autenticación exitosa".to_string())
     }
 
-    /// Creates a configured Spotify client
-    fn create_spotify_client(creds: Credentials) -> Result<AuthCodeSpotify, AppError> {
+    /// Clamps a requested OAuth timeout to the supported range
+    fn oauth_timeout_secs(requested: Option<u64>) -> u64 {
+        requested
+            .unwrap_or(OAUTH_CALLBACK_TIMEOUT_SECS)
+            .clamp(MIN_OAUTH_TIMEOUT_SECS, MAX_OAUTH_TIMEOUT_SECS)
+    }
+
+    /// Creates a configured Spotify client for `flow`
+    ///
+    /// Both flows share the redirect URI, scopes and token cache.
+    fn create_spotify_client(
+        flow: SpotifyAuthFlow,
+        creds: Credentials,
+    ) -> Result<SpotifyClient, AppError> {
+        let scopes = Self::parse_scopes(std::env::var(SPOTIFY_SCOPES_ENV).ok().as_deref())?;
+        tracing::info!("🔐 Requesting scopes: {:?}", scopes);
+
         let oauth = OAuth {
             redirect_uri: format!("http://{}/callback", OAUTH_SERVER_ADDR),
-            scopes: scopes!(
-                "user-read-private",
-                "user-read-email",
-                "user-library-read",
-                "playlist-read-private",
-                "playlist-read-collaborative",
-                "user-top-read",
-                "user-read-recently-played"
-            ),
+            scopes,
             ..Default::default()
         };
 
@@ -250,7 +451,41 @@ impl SpotifyService {
             ..Default::default()
         };
 
-        Ok(AuthCodeSpotify::with_config(creds, oauth, config))
+        Ok(SpotifyClient::new(flow, creds, oauth, config))
+    }
+
+    /// Parses a comma-separated `SPOTIFY_SCOPES` value
+    ///
+    /// Unset or blank falls back to the default scopes; any scope Spotify doesn't
+    /// define is rejected rather than silently dropped.
+    fn parse_scopes(value: Option<&str>) -> Result<HashSet<String>, AppError> {
+        let requested: Vec<&str> = value
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|scope| !scope.is_empty())
+            .collect();
+
+        if requested.is_empty() {
+            return Ok(DEFAULT_SPOTIFY_SCOPES
+                .iter()
+                .map(ToString::to_string)
+                .collect());
+        }
+
+        requested
+            .into_iter()
+            .map(|scope| {
+                if KNOWN_SPOTIFY_SCOPES.contains(&scope) {
+                    Ok(scope.to_string())
+                } else {
+                    Err(AppError::Validation(format!(
+                        "Unknown Spotify scope in {}: {}",
+                        SPOTIFY_SCOPES_ENV, scope
+                    )))
+                }
+            })
+            .collect()
     }
 
     /// Opens browser with authorization URL
@@ -265,23 +500,35 @@ impl SpotifyService {
     }
 
     /// Waits for OAuth callback with timeout
-    async fn wait_for_oauth_callback() -> Result<String, AppError> {
+    ///
+    /// `expected_state` is the `state` sent in the authorize URL; a callback carrying
+    /// any other value is rejected.
+    async fn wait_for_oauth_callback(
+        timeout_secs: u64,
+        expected_state: &str,
+    ) -> Result<String, AppError> {
         tracing::info!("⏳ Starting OAuth server on http://{}", OAUTH_SERVER_ADDR);
         
         let server = Server::http(OAUTH_SERVER_ADDR).map_err(|e| {
             tracing::error!("❌ Failed to start OAuth server on {}: {}", OAUTH_SERVER_ADDR, e);
             SpotifyError::OAuthServer(format!("Failed to start OAuth server: {}", e))
         })?;
-        tracing::info!("✅ OAuth server started, waiting for callback (timeout: {}s)...", OAUTH_CALLBACK_TIMEOUT_SECS);
+        tracing::info!(
+            "✅ OAuth server started, waiting for callback (timeout: {}s)...",
+            timeout_secs
+        );
 
         let request = timeout(
-            Duration::from_secs(OAUTH_CALLBACK_TIMEOUT_SECS),
+            Duration::from_secs(timeout_secs),
             tokio::task::spawn_blocking(move || server.recv()),
         )
         .await
         .map_err(|_| {
-            tracing::error!("❌ OAuth callback timeout after {}s - user didn't complete auth in browser", OAUTH_CALLBACK_TIMEOUT_SECS);
-            SpotifyError::OAuthTimeout(OAUTH_CALLBACK_TIMEOUT_SECS)
+            tracing::error!(
+                "❌ OAuth callback timeout after {}s - user didn't complete auth in browser",
+                timeout_secs
+            );
+            SpotifyError::OAuthTimeout(timeout_secs)
         })?
         .map_err(|e| {
             tracing::error!("❌ Error in OAuth server thread: {}", e);
@@ -295,7 +542,7 @@ impl SpotifyService {
         let url = request.url().to_string();
         tracing::info!("📥 Received callback: {}", url);
         Self::send_oauth_response(request);
-        Self::extract_auth_code(&url)
+        Self::extract_auth_code(&url, expected_state)
     }
 
     /// Sends HTML response to browser
@@ -310,32 +557,101 @@ impl SpotifyService {
     }
 
     /// Extracts authorization code from callback URL
-    fn extract_auth_code(url: &str) -> Result<String, AppError> {
-        let code = url
-            .split("code=")
-            .nth(1)
-            .and_then(|s| s.split('&').next())
-            .ok_or_else(|| SpotifyError::InvalidAuthCode)?;
+    ///
+    /// Fails when Spotify reports an error (e.g. the user declined access) or when
+    /// `state` doesn't match the value sent in the authorize URL, which guards
+    /// against a forged callback (CSRF).
+    fn extract_auth_code(url: &str, expected_state: &str) -> Result<String, AppError> {
+        let query = url
+            .split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default();
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        };
+
+        if let Some(error) = param("error") {
+            tracing::warn!("🔐 Spotify returned an OAuth error: {}", error);
+            let reason = match error {
+                "access_denied" => "user denied access".to_string(),
+                other => format!("Spotify returned error: {}", other),
+            };
+            return Err(SpotifyError::AuthenticationFailed(reason).into());
+        }
 
-        if code.is_empty() {
-            return Err(SpotifyError::InvalidAuthCode.into());
+        if param("state") != Some(expected_state) {
+            tracing::error!("❌ OAuth state mismatch in callback");
+            return Err(SpotifyError::OAuthStateMismatch.into());
         }
 
-        Ok(code.to_string())
+        match param("code") {
+            Some(code) if !code.is_empty() => Ok(code.to_string()),
+            _ => Err(SpotifyError::InvalidAuthCode.into()),
+        }
     }
 
     /// Exchanges authorization code for access token
-    async fn exchange_token(spotify: &AuthCodeSpotify, code: &str) -> Result<(), AppError> {
+    async fn exchange_token(spotify: &SpotifyClient, code: &str) -> Result<(), AppError> {
         spotify.request_token(code).await.map_err(|e| {
             SpotifyError::TokenExchange(format!("Failed to obtain access token: {}", e))
         })?;
         Ok(())
     }
 
-    /// Gets the authenticated user's profile information
+    /// Refreshes the access token now instead of on the next request
+    ///
+    /// rspotify only refreshes lazily, so the first call after a long idle period
+    /// can fail; the UI calls this e.g. when the app regains focus. The new token is
+    /// shared with the stored client and written to the token cache. Returns the new
+    /// expiry as a Unix timestamp.
     #[instrument(skip_all)]
-    pub async fn get_profile(state: &SpotifyState) -> Result<SpotifyUserProfile, AppError> {
+    pub async fn refresh_token(state: &SpotifyState) -> Result<Option<i64>, AppError> {
+        let spotify = state.get_client()?;
+
+        // rspotify would replace the token with `None` when there is no refresh token
+        let has_refresh_token = state
+            .current_token()
+            .await?
+            .is_some_and(|token| token.refresh_token.is_some());
+        if !has_refresh_token {
+            return Err(SpotifyError::NoRefreshToken.into());
+        }
+
+        spotify
+            .refresh_token()
+            .await
+            .map_err(|e| SpotifyError::TokenRefresh(e.to_string()))?;
+
+        let expires_at = state
+            .current_token()
+            .await?
+            .and_then(|token| token.expires_at)
+            .map(|expires_at| expires_at.timestamp());
+        tracing::info!("🔑 Spotify token refreshed");
+        Ok(expires_at)
+    }
+
+    /// Gets the authenticated user's profile information
+    ///
+    /// Returns the profile cached by an earlier call unless `force_refresh` is set,
+    /// so repeated reads (e.g. rendering a header) don't hit the network.
+    #[instrument(skip_all, fields(force_refresh))]
+    pub async fn get_profile(
+        state: &SpotifyState,
+        force_refresh: bool,
+    ) -> Result<SpotifyUserProfile, AppError> {
         let spotify = state.get_client()?;
+        if !force_refresh {
+            if let Some(profile) = state.get_cached_user()? {
+                tracing::debug!("🎵 Using cached Spotify profile");
+                return Ok(profile);
+            }
+        }
+
         let user = spotify
             .current_user()
             .await
@@ -353,7 +669,7 @@ impl SpotifyService {
             id: user.id.to_string(),
             display_name: user.display_name.clone(),
             email: user.email.clone(),
-            country: user.country.map(|c| format!("{:?}", c)),
+            country: user.country.map(|c| <&'static str>::from(c).to_string()),
             product: user.product.map(|p| format!("{:?}", p)),
             followers: user.followers.as_ref().map(|f| f.total).unwrap_or(0),
             images: user
@@ -378,42 +694,88 @@ impl SpotifyService {
         Ok(())
     }
 
-    /// Gets the user's playlists with optional limit
-    #[instrument(skip_all, fields(limit))]
+    /// Gets one page of the user's playlists (limit defaults to 20, max 50)
+    ///
+    /// The first page is cached; pages at a later `offset` always hit the API.
+    #[instrument(skip_all, fields(limit, offset))]
     pub async fn get_playlists(
         state: &SpotifyState,
         limit: Option<u32>,
-    ) -> Result<Vec<SpotifyPlaylist>, AppError> {
-        let requested_limit = limit.unwrap_or(20).min(50) as usize;
-        
-        // Check cache first - only use if we have enough items
-        if let Some(cached) = state.get_cached_playlists()? {
-            if cached.len() >= requested_limit {
-                // Return only the requested number of playlists
-                return Ok(cached.iter().take(requested_limit).cloned().collect());
+        offset: Option<u32>,
+    ) -> Result<SpotifyPlaylistPage, AppError> {
+        let limit = limit.unwrap_or(20).min(50);
+        let offset = offset.unwrap_or(0);
+
+        // The cache holds the first page; use it if it has enough items
+        if offset == 0 {
+            if let Some(cached) = state.get_cached_playlists()? {
+                if cached.playlists.len() >= limit as usize {
+                    return Ok(SpotifyPlaylistPage {
+                        playlists: cached.playlists.into_iter().take(limit as usize).collect(),
+                        ..cached
+                    });
+                }
             }
-            // If cached has fewer items than requested, we need to fetch more
         }
-        
+
         state.enforce_rate_limit().await?;
-        
-        let spotify = state.get_client()?;
-        let final_limit = requested_limit as u32;
 
-        let playlists = spotify
-            .current_user_playlists_manual(Some(final_limit), None)
+        let spotify = state.get_client()?;
+        let page = spotify
+            .current_user_playlists_manual(Some(limit), Some(offset))
             .await
             .map_err(|e| SpotifyError::GetPlaylists(format!("Failed to get playlists: {}", e)))?;
 
-        let result: Vec<SpotifyPlaylist> =
-            playlists.items.iter().map(Self::convert_playlist).collect();
+        let result = SpotifyPlaylistPage {
+            playlists: page.items.iter().map(Self::convert_playlist).collect(),
+            offset,
+            total: page.total,
+        };
 
-        // Cache the result
-        state.cache_playlists(&result)?;
+        if offset == 0 {
+            state.cache_playlists(&result)?;
+        }
 
         Ok(result)
     }
 
+    /// Gets every playlist of the user, paging through the whole listing
+    ///
+    /// Failed pages are retried like the saved-tracks stream. The result replaces
+    /// the playlists cache.
+    #[instrument(skip_all)]
+    pub async fn get_all_playlists(state: &SpotifyState) -> Result<Vec<SpotifyPlaylist>, AppError> {
+        let spotify = &state.get_client()?;
+        let mut playlists = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let page = Self::with_page_retries(|| async move {
+                state.enforce_rate_limit().await?;
+                spotify
+                    .current_user_playlists_manual(Some(SPOTIFY_BATCH_SIZE), Some(offset))
+                    .await
+                    .map_err(|e| SpotifyError::GetPlaylists(e.to_string()).into())
+            })
+            .await?;
+
+            playlists.extend(page.items.iter().map(Self::convert_playlist));
+
+            if page.next.is_none() || Self::is_last_page(page.items.len()) {
+                break;
+            }
+            offset += SPOTIFY_BATCH_SIZE;
+        }
+
+        tracing::info!("📋 Loaded {} playlists", playlists.len());
+        state.cache_playlists(&SpotifyPlaylistPage {
+            playlists: playlists.clone(),
+            offset: 0,
+            total: playlists.len() as u32,
+        })?;
+        Ok(playlists)
+    }
+
     /// Converts rspotify playlist to our domain model
     fn convert_playlist(p: &rspotify::model::SimplifiedPlaylist) -> SpotifyPlaylist {
         SpotifyPlaylist {
@@ -435,23 +797,86 @@ impl SpotifyService {
         }
     }
 
+    /// Gets Spotify's new album releases (catalog endpoint, no extra scopes)
+    #[instrument(skip_all, fields(limit))]
+    pub async fn get_new_releases(
+        state: &SpotifyState,
+        limit: Option<u32>,
+    ) -> Result<Vec<SpotifyAlbum>, AppError> {
+        let final_limit = limit.unwrap_or(20).min(SPOTIFY_BATCH_SIZE);
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let releases = spotify
+            .new_releases_manual(None, Some(final_limit), None)
+            .await
+            .map_err(|e| {
+                SpotifyError::GetNewReleases(format!("Failed to get new releases: {}", e))
+            })?;
+
+        Ok(releases.items.iter().map(Self::convert_album).collect())
+    }
+
+    /// Gets Spotify's featured playlists (catalog endpoint, no extra scopes)
+    #[instrument(skip_all, fields(limit))]
+    pub async fn get_featured_playlists(
+        state: &SpotifyState,
+        limit: Option<u32>,
+    ) -> Result<Vec<SpotifyPlaylist>, AppError> {
+        let final_limit = limit.unwrap_or(20).min(SPOTIFY_BATCH_SIZE);
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let featured = spotify
+            .featured_playlists(None, None, None, Some(final_limit), None)
+            .await
+            .map_err(|e| {
+                SpotifyError::GetFeaturedPlaylists(format!(
+                    "Failed to get featured playlists: {}",
+                    e
+                ))
+            })?;
+
+        Ok(featured
+            .playlists
+            .items
+            .iter()
+            .map(Self::convert_playlist)
+            .collect())
+    }
+
+    /// Converts rspotify album to our domain model
+    fn convert_album(album: &rspotify::model::SimplifiedAlbum) -> SpotifyAlbum {
+        SpotifyAlbum {
+            id: album.id.as_ref().map(|id| id.to_string()),
+            name: album.name.clone(),
+            artists: album.artists.iter().map(|a| a.name.clone()).collect(),
+            album_type: album.album_type.clone(),
+            release_date: album.release_date.clone(),
+            images: album.images.iter().map(|img| img.url.clone()).collect(),
+            external_url: album.external_urls.get("spotify").cloned(),
+        }
+    }
+
     /// Gets the user's saved tracks with pagination support
+    ///
+    /// Tracks are relinked for `market`, defaulting to the user's country.
     #[instrument(skip_all, fields(limit, offset))]
     pub async fn get_saved_tracks(
         state: &SpotifyState,
         limit: Option<u32>,
         offset: Option<u32>,
+        market: Option<String>,
     ) -> Result<Vec<SpotifyTrack>, AppError> {
+        let market = Self::user_market(state, market.as_deref())?;
         let spotify = state.get_client()?;
         let final_limit = limit.unwrap_or(SPOTIFY_BATCH_SIZE).min(SPOTIFY_BATCH_SIZE);
         let final_offset = offset.unwrap_or(0);
 
         let saved = spotify
-            .current_user_saved_tracks_manual(
-                None::<rspotify::model::Market>,
-                Some(final_limit),
-                Some(final_offset),
-            )
+            .current_user_saved_tracks_manual(Some(market), Some(final_limit), Some(final_offset))
             .await
             .map_err(|e| {
                 SpotifyError::GetSavedTracks(format!("Failed to get saved tracks: {}", e))
@@ -466,13 +891,125 @@ impl SpotifyService {
         Ok(tracks)
     }
 
+    /// Gets how many tracks the user has saved, for paging through `get_saved_tracks`
+    #[instrument(skip_all)]
+    pub async fn get_saved_tracks_count(
+        state: &SpotifyState,
+        market: Option<String>,
+    ) -> Result<u32, AppError> {
+        let market = Self::user_market(state, market.as_deref())?;
+        let spotify = state.get_client()?;
+        Self::get_total_tracks(&spotify, market).await
+    }
+
+    /// Gets the user's saved podcast episodes with pagination support
+    #[instrument(skip_all)]
+    pub async fn get_saved_episodes(
+        state: &SpotifyState,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<SpotifyEpisode>, AppError> {
+        let spotify = state.get_client()?;
+        let limit = limit
+            .unwrap_or(SPOTIFY_BATCH_SIZE)
+            .min(SPOTIFY_BATCH_SIZE)
+            .to_string();
+        let offset = offset.unwrap_or(0).to_string();
+        let query = Query::from([("limit", limit.as_str()), ("offset", offset.as_str())]);
+
+        state.enforce_rate_limit().await?;
+
+        // rspotify has no wrapper for the saved-episodes endpoint
+        let body = spotify.api_get("me/episodes", &query).await.map_err(|e| {
+            SpotifyError::GetSavedEpisodes(format!("Failed to get saved episodes: {}", e))
+        })?;
+        let saved: Page<SavedEpisode> = serde_json::from_str(&body)
+            .map_err(|e| SpotifyError::GetSavedEpisodes(format!("Unexpected response: {}", e)))?;
+
+        Ok(saved
+            .items
+            .iter()
+            .map(|item| Self::convert_episode(&item.episode))
+            .collect())
+    }
+
+    /// Gets a single track by id, `open.spotify.com` URL or `spotify:track:` URI
+    #[instrument(skip_all, fields(track_id = %track_id))]
+    pub async fn get_track(state: &SpotifyState, track_id: &str) -> Result<SpotifyTrack, AppError> {
+        let track_id = Self::parse_track_id(track_id)?;
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let track = spotify
+            .track(track_id, None)
+            .await
+            .map_err(|e| SpotifyError::GetTrack(format!("Failed to get track: {}", e)))?;
+
+        Ok(Self::convert_spotify_track(&track))
+    }
+
+    /// Gets many tracks by id, URL or URI, preserving the input order
+    ///
+    /// Ids are requested in chunks of 50 (the Spotify limit). Malformed ids fail the
+    /// whole call up front; ids Spotify cannot find are skipped, so the result may be
+    /// shorter than the input.
+    #[instrument(skip_all, fields(id_count = track_ids.len()))]
+    pub async fn get_tracks(
+        state: &SpotifyState,
+        track_ids: Vec<String>,
+    ) -> Result<Vec<SpotifyTrack>, AppError> {
+        let chunks = Self::track_id_chunks(&track_ids)?;
+
+        let spotify = state.get_client()?;
+        let mut result = Vec::with_capacity(track_ids.len());
+
+        for chunk_ids in chunks {
+            state.enforce_rate_limit().await?;
+
+            match spotify.tracks(chunk_ids.clone(), None).await {
+                Ok(tracks) => result.extend(tracks.iter().map(Self::convert_spotify_track)),
+                Err(e) => {
+                    // Unknown ids come back as null and break the batch response,
+                    // so retry this chunk one id at a time and skip the missing ones
+                    tracing::warn!("🎵 Batch track lookup failed, retrying individually: {}", e);
+                    for track_id in chunk_ids {
+                        state.enforce_rate_limit().await?;
+                        match spotify.track(track_id.clone(), None).await {
+                            Ok(track) => result.push(Self::convert_spotify_track(&track)),
+                            Err(e) => tracing::warn!("🎵 Skipping track {}: {}", track_id, e),
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Parses ids, URLs or URIs into track ids, in request-sized chunks
+    fn track_id_chunks(track_ids: &[String]) -> Result<Vec<Vec<TrackId<'static>>>, AppError> {
+        let ids = track_ids
+            .iter()
+            .map(|id| Self::parse_track_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ids
+            .chunks(SPOTIFY_BATCH_SIZE as usize)
+            .map(<[_]>::to_vec)
+            .collect())
+    }
+
     /// Gets the user's top artists based on listening history
+    ///
+    /// `time_range` is `short_term`, `medium_term` (the default) or `long_term`.
     #[instrument(skip_all, fields(limit, time_range))]
     pub async fn get_top_artists(
         state: &SpotifyState,
         limit: Option<u32>,
         time_range: Option<String>,
     ) -> Result<Vec<SpotifyArtist>, AppError> {
+        let range = Self::parse_time_range(time_range.as_deref())?;
         let requested_limit = limit.unwrap_or(20).min(50) as usize;
         
         // Check cache first - only use if we have enough items
@@ -488,7 +1025,6 @@ impl SpotifyService {
         
         let spotify = state.get_client()?;
         let final_limit = requested_limit as u32;
-        let range = Self::parse_time_range(time_range.as_deref());
 
         let artists = spotify
             .current_user_top_artists_manual(Some(range), Some(final_limit), None)
@@ -505,40 +1041,260 @@ impl SpotifyService {
         Ok(result)
     }
 
-    /// Parses time range string to TimeRange enum
-    fn parse_time_range(time_range: Option<&str>) -> TimeRange {
-        match time_range {
-            Some("short_term") => TimeRange::ShortTerm,
-            Some("long_term") => TimeRange::LongTerm,
-            _ => TimeRange::MediumTerm,
-        }
-    }
+    /// Gets artists similar to the given one ("fans also like")
+    ///
+    /// Accepts an artist id, `open.spotify.com/artist/` URL or `spotify:artist:` URI.
+    #[instrument(skip_all, fields(artist_id = %artist_id))]
+    pub async fn get_related_artists(
+        state: &SpotifyState,
+        artist_id: &str,
+    ) -> Result<Vec<SpotifyArtist>, AppError> {
+        let artist_id = Self::parse_artist_id(artist_id)?;
 
-    /// Converts rspotify artist to our domain model
-    fn convert_artist(artist: &rspotify::model::FullArtist) -> SpotifyArtist {
-        SpotifyArtist {
-            id: artist.id.to_string(),
-            name: artist.name.clone(),
-            genres: artist.genres.clone(),
-            popularity: artist.popularity,
-            followers: artist.followers.total,
-            images: artist.images.iter().map(|img| img.url.clone()).collect(),
-            external_url: artist.external_urls.get("spotify").cloned(),
-        }
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let artists = spotify
+            .artist_related_artists(artist_id)
+            .await
+            .map_err(|e| {
+                SpotifyError::GetRelatedArtists(format!("Failed to get related artists: {}", e))
+            })?;
+
+        Ok(artists.iter().map(Self::convert_artist).collect())
     }
 
-    /// Gets the user's top tracks with optional time range and limit
-    #[instrument(skip_all, fields(limit, time_range))]
-    pub async fn get_top_tracks(
+    /// Gets an artist's most popular tracks in a market
+    ///
+    /// The market defaults to the cached profile's country, then to `US`.
+    #[instrument(skip_all, fields(artist_id = %artist_id))]
+    pub async fn get_artist_top_tracks(
         state: &SpotifyState,
-        limit: Option<u32>,
-        time_range: Option<String>,
+        artist_id: &str,
+        market: Option<String>,
     ) -> Result<Vec<SpotifyTrack>, AppError> {
-        let requested_limit = limit.unwrap_or(20).min(50) as usize;
-        
-        // Check cache first - only use if we have enough items
-        if let Some(cached) = state.get_cached_top_tracks()? {
-            if cached.len() >= requested_limit {
+        let artist_id = Self::parse_artist_id(artist_id)?;
+        let market = Self::resolve_market(state, market.as_deref())?;
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let tracks = spotify
+            .artist_top_tracks(artist_id, Some(market))
+            .await
+            .map_err(|e| {
+                SpotifyError::GetArtistTopTracks(format!("Failed to get artist top tracks: {}", e))
+            })?;
+
+        Ok(tracks.iter().map(Self::convert_spotify_track).collect())
+    }
+
+    /// Resolves the market to query: explicit code, else cached profile country, else `US`
+    fn resolve_market(state: &SpotifyState, market: Option<&str>) -> Result<Market, AppError> {
+        match Self::user_market(state, market)? {
+            Market::FromToken => Self::parse_country(DEFAULT_MARKET)
+                .map(Market::Country)
+                .ok_or_else(|| SpotifyError::InvalidMarket(DEFAULT_MARKET.to_string()).into()),
+            resolved => Ok(resolved),
+        }
+    }
+
+    /// Resolves the market for user-scoped endpoints: explicit code, else cached
+    /// profile country, else `from_token` so Spotify uses the account's country
+    fn user_market(state: &SpotifyState, market: Option<&str>) -> Result<Market, AppError> {
+        if let Some(code) = market {
+            return Self::parse_country(code)
+                .map(Market::Country)
+                .ok_or_else(|| SpotifyError::InvalidMarket(code.to_string()).into());
+        }
+
+        Ok(state
+            .get_user_country()?
+            .and_then(|code| Self::parse_country(&code))
+            .map(Market::Country)
+            .unwrap_or(Market::FromToken))
+    }
+
+    /// Parses an ISO 3166-1 alpha-2 country code
+    fn parse_country(code: &str) -> Option<Country> {
+        serde_json::from_value(serde_json::Value::String(code.trim().to_uppercase())).ok()
+    }
+
+    /// Normalizes user input into an rspotify artist id
+    fn parse_artist_id(input: &str) -> Result<ArtistId<'static>, AppError> {
+        let id = extract_spotify_id(input, "artist")?;
+        ArtistId::from_id(id.clone())
+            .map_err(|e| SpotifyError::InvalidId(format!("{}: {}", id, e)).into())
+    }
+
+    /// Normalizes user input into an rspotify track id
+    fn parse_track_id(input: &str) -> Result<TrackId<'static>, AppError> {
+        let id = extract_spotify_id(input, "track")?;
+        TrackId::from_id(id.clone())
+            .map_err(|e| SpotifyError::InvalidId(format!("{}: {}", id, e)).into())
+    }
+
+    /// Gets track recommendations from artist, track and genre seeds
+    ///
+    /// Between 1 and 5 seeds must be given in total. Tracks are relinked for the
+    /// user's market.
+    #[instrument(skip_all, fields(
+        artists = seed_artists.len(),
+        tracks = seed_tracks.len(),
+        genres = seed_genres.len(),
+        limit
+    ))]
+    pub async fn get_recommendations(
+        state: &SpotifyState,
+        seed_artists: Vec<String>,
+        seed_tracks: Vec<String>,
+        seed_genres: Vec<String>,
+        targets: Option<SpotifyRecommendationTargets>,
+        limit: Option<u32>,
+    ) -> Result<Vec<SpotifyTrack>, AppError> {
+        Self::validate_seed_count(seed_artists.len() + seed_tracks.len() + seed_genres.len())?;
+
+        let artist_ids = seed_artists
+            .iter()
+            .map(|id| Self::parse_artist_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let track_ids = seed_tracks
+            .iter()
+            .map(|id| Self::parse_track_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let genres: Vec<&str> = seed_genres.iter().map(|g| g.trim()).collect();
+
+        let targets = targets.unwrap_or_default();
+        let mut attributes = Vec::new();
+        if let Some(energy) = targets.target_energy {
+            attributes.push(RecommendationsAttribute::TargetEnergy(
+                energy.clamp(0.0, 1.0),
+            ));
+        }
+        if let Some(valence) = targets.target_valence {
+            attributes.push(RecommendationsAttribute::TargetValence(
+                valence.clamp(0.0, 1.0),
+            ));
+        }
+
+        let market = Self::user_market(state, None)?;
+        let final_limit = limit.unwrap_or(20).min(MAX_RECOMMENDATIONS_LIMIT);
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let recommendations = spotify
+            .recommendations(
+                attributes,
+                (!artist_ids.is_empty()).then_some(artist_ids),
+                (!genres.is_empty()).then_some(genres),
+                (!track_ids.is_empty()).then_some(track_ids),
+                Some(market),
+                Some(final_limit),
+            )
+            .await
+            .map_err(|e| {
+                SpotifyError::GetRecommendations(format!("Failed to get recommendations: {}", e))
+            })?;
+
+        Ok(recommendations
+            .tracks
+            .iter()
+            .map(Self::convert_simplified_track)
+            .collect())
+    }
+
+    /// Checks the combined seed count against Spotify's 1..=5 range
+    fn validate_seed_count(total: usize) -> Result<(), AppError> {
+        if (1..=MAX_RECOMMENDATION_SEEDS).contains(&total) {
+            Ok(())
+        } else {
+            Err(SpotifyError::InvalidSeedCount(total).into())
+        }
+    }
+
+    /// Gets one page of the artists the user follows (requires `user-follow-read`)
+    ///
+    /// Pass the returned `next_cursor` as `after` to get the following page.
+    #[instrument(skip_all, fields(limit))]
+    pub async fn get_followed_artists(
+        state: &SpotifyState,
+        limit: Option<u32>,
+        after: Option<String>,
+    ) -> Result<SpotifyFollowedArtists, AppError> {
+        let final_limit = limit.unwrap_or(20).min(SPOTIFY_BATCH_SIZE);
+
+        state.enforce_rate_limit().await?;
+
+        let spotify = state.get_client()?;
+        let page = spotify
+            .current_user_followed_artists(after.as_deref(), Some(final_limit))
+            .await
+            .map_err(|e| {
+                SpotifyError::GetFollowedArtists(format!("Failed to get followed artists: {}", e))
+            })?;
+
+        Ok(Self::convert_followed_artists(page))
+    }
+
+    /// Converts a cursor-based page of artists, exposing the cursor only when more pages exist
+    fn convert_followed_artists(
+        page: rspotify::model::CursorBasedPage<rspotify::model::FullArtist>,
+    ) -> SpotifyFollowedArtists {
+        let next_cursor = page.next.as_ref().and(page.cursors.and_then(|c| c.after));
+
+        SpotifyFollowedArtists {
+            artists: page.items.iter().map(Self::convert_artist).collect(),
+            next_cursor,
+            total: page.total,
+        }
+    }
+
+    /// Parses a time range string, defaulting `None` to medium-term
+    ///
+    /// Anything other than `short_term`, `medium_term` or `long_term` is rejected
+    /// instead of silently falling back, so typos like `medium` surface.
+    fn parse_time_range(time_range: Option<&str>) -> Result<TimeRange, AppError> {
+        match time_range {
+            None | Some("medium_term") => Ok(TimeRange::MediumTerm),
+            Some("short_term") => Ok(TimeRange::ShortTerm),
+            Some("long_term") => Ok(TimeRange::LongTerm),
+            Some(other) => Err(AppError::Validation(format!(
+                "Invalid time range '{}', expected short_term, medium_term or long_term",
+                other
+            ))),
+        }
+    }
+
+    /// Converts rspotify artist to our domain model
+    fn convert_artist(artist: &rspotify::model::FullArtist) -> SpotifyArtist {
+        SpotifyArtist {
+            id: artist.id.to_string(),
+            name: artist.name.clone(),
+            genres: artist.genres.clone(),
+            popularity: artist.popularity,
+            followers: artist.followers.total,
+            images: artist.images.iter().map(|img| img.url.clone()).collect(),
+            external_url: artist.external_urls.get("spotify").cloned(),
+        }
+    }
+
+    /// Gets the user's top tracks with optional time range and limit
+    ///
+    /// `time_range` is `short_term`, `medium_term` (the default) or `long_term`.
+    /// The top-items endpoint takes no market, so tracks are not relinked here.
+    #[instrument(skip_all, fields(limit, time_range))]
+    pub async fn get_top_tracks(
+        state: &SpotifyState,
+        limit: Option<u32>,
+        time_range: Option<String>,
+    ) -> Result<Vec<SpotifyTrack>, AppError> {
+        let range = Self::parse_time_range(time_range.as_deref())?;
+        let requested_limit = limit.unwrap_or(20).min(50) as usize;
+        
+        // Check cache first - only use if we have enough items
+        if let Some(cached) = state.get_cached_top_tracks()? {
+            if cached.len() >= requested_limit {
                 // Return only the requested number of tracks
                 return Ok(cached.iter().take(requested_limit).cloned().collect());
             }
@@ -549,7 +1305,6 @@ impl SpotifyService {
         
         let spotify = state.get_client()?;
         let final_limit = requested_limit as u32;
-        let range = Self::parse_time_range(time_range.as_deref());
 
         let tracks = spotify
             .current_user_top_tracks_manual(Some(range), Some(final_limit), None)
@@ -568,59 +1323,166 @@ impl SpotifyService {
         Ok(result)
     }
 
+    /// Gets all liked songs in one response, paging through the library
+    ///
+    /// Simpler to consume than `stream_all_liked_songs` for small libraries, but the
+    /// UI sees nothing until every page is fetched and all tracks are held in memory.
+    /// Prefer the streamed variant above ~1000 songs.
+    #[instrument(skip_all)]
+    pub async fn get_all_liked_songs(
+        state: &SpotifyState,
+        market: Option<String>,
+    ) -> Result<Vec<SpotifyTrack>, AppError> {
+        let market = Self::user_market(state, market.as_deref())?;
+        let spotify = state.get_client()?;
+
+        let mut all_tracks = Vec::new();
+        Self::for_each_liked_page(state, &spotify, market, |tracks| {
+            all_tracks.extend(tracks);
+            Ok(())
+        })
+        .await?;
+
+        tracing::info!("🎵 Fetched {} liked songs", all_tracks.len());
+        Ok(all_tracks)
+    }
+
+    /// Exports all liked songs straight to a JSON or CSV file
+    ///
+    /// Pages are written as they arrive, so the library never has to go through the
+    /// frontend. Emits `spotify-export-progress` after each page and returns the
+    /// number of tracks written.
+    #[instrument(skip_all, fields(format = ?format))]
+    pub async fn export_liked_songs(
+        state: &SpotifyState,
+        window: &Window,
+        path: &str,
+        format: ExportFormat,
+        market: Option<String>,
+    ) -> Result<u32, AppError> {
+        Self::export_liked_songs_with(state, path, format, market, |progress| {
+            let _ = window.emit(events::SPOTIFY_EXPORT_PROGRESS, progress);
+        })
+        .await
+    }
+
+    /// Exports the liked songs, reporting progress after each page to `on_progress`
+    async fn export_liked_songs_with(
+        state: &SpotifyState,
+        path: &str,
+        format: ExportFormat,
+        market: Option<String>,
+        mut on_progress: impl FnMut(ExportProgress),
+    ) -> Result<u32, AppError> {
+        let path = validate_output_path(path)?;
+        if path.is_dir() {
+            return Err(FileError::NotFile(path.display().to_string()).into());
+        }
+
+        let market = Self::user_market(state, market.as_deref())?;
+        let spotify = state.get_client()?;
+        let total = Self::get_total_tracks(&spotify, market).await?;
+
+        let mut writer = TrackExportWriter::create(&path, format)?;
+        let mut exported = 0;
+        let result = Self::for_each_liked_page(state, &spotify, market, |tracks| {
+            writer.write_tracks(&tracks)?;
+            exported += tracks.len() as u32;
+            on_progress(ExportProgress { exported, total });
+            Ok(())
+        })
+        .await;
+
+        if let Err(e) = result {
+            writer.discard();
+            return Err(e);
+        }
+        writer.finish()?;
+
+        tracing::info!("🎵 Exported {} liked songs to {}", exported, path.display());
+        Ok(exported)
+    }
+
+    /// Pages through the saved tracks, retrying failed pages, and hands each
+    /// converted page to `on_page`
+    async fn for_each_liked_page<F>(
+        state: &SpotifyState,
+        spotify: &SpotifyClient,
+        market: Market,
+        mut on_page: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(Vec<SpotifyTrack>) -> Result<(), AppError>,
+    {
+        let mut offset = 0;
+
+        loop {
+            let saved = Self::with_page_retries(|| async move {
+                state.enforce_rate_limit().await?;
+                Self::fetch_tracks_batch(spotify, offset, market).await
+            })
+            .await?;
+
+            let is_last = saved.next.is_none() || Self::is_last_page(saved.items.len());
+            on_page(
+                saved
+                    .items
+                    .iter()
+                    .map(|item| Self::convert_spotify_track(&item.track))
+                    .collect(),
+            )?;
+
+            if is_last {
+                return Ok(());
+            }
+            offset += SPOTIFY_BATCH_SIZE;
+        }
+    }
+
+    /// Fetches one page of a listing, retrying Spotify failures with backoff
+    ///
+    /// Other errors (e.g. a poisoned rate-limit lock) are returned immediately.
+    async fn with_page_retries<T, F, Fut>(op: F) -> Result<T, AppError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, AppError>>,
+    {
+        with_retries(
+            MAX_RETRY_ATTEMPTS,
+            Duration::from_millis(RETRY_BASE_DELAY_MS),
+            |e| matches!(e, AppError::Spotify(_)),
+            op,
+        )
+        .await
+    }
+
     /// Streams all liked songs progressively using Tauri events
     /// Recommended for large libraries (>1000 songs)
     #[instrument(skip_all)]
     pub async fn stream_all_liked_songs(
         state: &SpotifyState,
         window: &Window,
+        market: Option<String>,
     ) -> Result<(), AppError> {
+        let market = Self::user_market(state, market.as_deref())?;
         let spotify = state.get_client()?;
-        let total_tracks = Self::get_total_tracks(&spotify).await?;
+        let total_tracks = Self::get_total_tracks(&spotify, market).await?;
 
         Self::emit_start_event(window, total_tracks)?;
 
-        let mut offset = 0;
         let mut total_sent = 0;
-        let mut retries = 0;
-
-        loop {
-            state.enforce_rate_limit().await?;
-            
-            match Self::fetch_tracks_batch(&spotify, offset).await {
-                Ok(saved) => {
-                    let batch_size = saved.items.len();
-                    let tracks: Vec<SpotifyTrack> = saved
-                        .items
-                        .iter()
-                        .map(|item| Self::convert_spotify_track(&item.track))
-                        .collect();
-
-                    total_sent += batch_size as u32;
-                    let progress = Self::calculate_progress(total_sent, total_tracks);
-
-                    Self::emit_batch_event(window, &tracks, progress, total_sent, total_tracks)?;
-
-                    if batch_size < SPOTIFY_BATCH_SIZE as usize {
-                        break;
-                    }
-
-                    offset += SPOTIFY_BATCH_SIZE;
-                    retries = 0;
-                }
-                Err(e) => {
-                    retries += 1;
-                    if retries >= MAX_RETRY_ATTEMPTS {
-                        Self::emit_error_event(window, MAX_RETRY_ATTEMPTS)?;
-                        return Err(SpotifyError::GetSavedTracks(format!(
-                            "Error after {} attempts: {}",
-                            MAX_RETRY_ATTEMPTS, e
-                        ))
-                        .into());
-                    }
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                }
+        let streamed = Self::for_each_liked_page(state, &spotify, market, |tracks| {
+            total_sent += tracks.len() as u32;
+            let progress = Self::calculate_progress(total_sent, total_tracks);
+            Self::emit_batch_event(window, tracks, progress, total_sent, total_tracks)
+        })
+        .await;
+
+        if let Err(e) = streamed {
+            if matches!(e, AppError::Spotify(_)) {
+                Self::emit_error_event(window, MAX_RETRY_ATTEMPTS)?;
             }
+            return Err(e);
         }
 
         Self::emit_complete_event(window, total_sent)?;
@@ -628,11 +1490,9 @@ impl SpotifyService {
     }
 
     /// Gets total number of saved tracks
-    async fn get_total_tracks(spotify: &AuthCodeSpotify) -> Result<u32, AppError> {
-        use rspotify::model::Market;
-
+    async fn get_total_tracks(spotify: &SpotifyClient, market: Market) -> Result<u32, AppError> {
         let first_batch = spotify
-            .current_user_saved_tracks_manual(None::<Market>, Some(1), Some(0))
+            .current_user_saved_tracks_manual(Some(market), Some(1), Some(0))
             .await
             .map_err(|e| {
                 SpotifyError::GetSavedTracks(format!("Error getting initial info: {}", e))
@@ -643,23 +1503,23 @@ impl SpotifyService {
 
     /// Fetches a batch of tracks
     async fn fetch_tracks_batch(
-        spotify: &AuthCodeSpotify,
+        spotify: &SpotifyClient,
         offset: u32,
+        market: Market,
     ) -> Result<rspotify::model::Page<rspotify::model::SavedTrack>, AppError> {
-        use rspotify::model::Market;
-
         spotify
-            .current_user_saved_tracks_manual(
-                None::<Market>,
-                Some(SPOTIFY_BATCH_SIZE),
-                Some(offset),
-            )
+            .current_user_saved_tracks_manual(Some(market), Some(SPOTIFY_BATCH_SIZE), Some(offset))
             .await
             .map_err(|e| {
                 SpotifyError::GetSavedTracks(format!("Failed to get tracks: {}", e)).into()
             })
     }
 
+    /// A page shorter than the batch size is the last one of a paged listing
+    fn is_last_page(batch_size: usize) -> bool {
+        batch_size < SPOTIFY_BATCH_SIZE as usize
+    }
+
     /// Calculates progress percentage
     fn calculate_progress(total_sent: u32, total_tracks: u32) -> u32 {
         if total_tracks > 0 {
@@ -672,30 +1532,27 @@ impl SpotifyService {
     /// Emits start event
     fn emit_start_event(window: &Window, total: u32) -> Result<(), AppError> {
         window
-            .emit(
-                "spotify-tracks-start",
-                serde_json::json!({ "total": total }),
-            )
+            .emit(events::SPOTIFY_TRACKS_START, TracksStart { total })
             .map_err(|e| AppError::Unknown(format!("Error emitting start event: {}", e)))
     }
 
     /// Emits batch event
     fn emit_batch_event(
         window: &Window,
-        tracks: &[SpotifyTrack],
+        tracks: Vec<SpotifyTrack>,
         progress: u32,
         loaded: u32,
         total: u32,
     ) -> Result<(), AppError> {
         window
             .emit(
-                "spotify-tracks-batch",
-                serde_json::json!({
-                    "tracks": tracks,
-                    "progress": progress,
-                    "loaded": loaded,
-                    "total": total
-                }),
+                events::SPOTIFY_TRACKS_BATCH,
+                TracksBatch {
+                    tracks,
+                    progress,
+                    loaded,
+                    total,
+                },
             )
             .map_err(|e| AppError::Unknown(format!("Error emitting batch event: {}", e)))
     }
@@ -703,10 +1560,10 @@ impl SpotifyService {
     /// Emits error event
     fn emit_error_event(window: &Window, retries: u32) -> Result<(), AppError> {
         let _ = window.emit(
-            "spotify-tracks-error",
-            serde_json::json!({
-                "message": format!("Error después de {} intentos", retries)
-            }),
+            events::SPOTIFY_TRACKS_ERROR,
+            TracksError {
+                message: format!("Error después de {} intentos", retries),
+            },
         );
         Ok(())
     }
@@ -714,13 +1571,91 @@ impl SpotifyService {
     /// Emits completion event
     fn emit_complete_event(window: &Window, total: u32) -> Result<(), AppError> {
         window
-            .emit(
-                "spotify-tracks-complete",
-                serde_json::json!({ "total": total }),
-            )
+            .emit(events::SPOTIFY_TRACKS_COMPLETE, TracksComplete { total })
             .map_err(|e| AppError::Unknown(format!("Error emitting complete event: {}", e)))
     }
 
+    /// Maps selected tracks to the URLs `download_spotify_tracks_segmented` expects
+    ///
+    /// Local tracks (no id) and malformed ids are skipped and counted.
+    pub fn build_download_urls(tracks: &[SpotifyTrack]) -> SpotifyDownloadUrls {
+        let urls: Vec<String> = tracks
+            .iter()
+            .filter_map(|track| spotify_track_url(track.id.as_deref()?).ok())
+            .collect();
+
+        SpotifyDownloadUrls {
+            skipped: tracks.len() - urls.len(),
+            urls,
+        }
+    }
+
+    /// Checks a download URL resolves to a track without downloading it
+    ///
+    /// Without a session only the URL format can be checked, and `track` is `None`.
+    #[instrument(skip_all, fields(url = %url))]
+    pub async fn preview_track(
+        state: &SpotifyState,
+        url: &str,
+    ) -> Result<SpotifyTrackPreview, AppError> {
+        let id = extract_spotify_id(url, "track")?;
+        let url = spotify_track_url(&id)?;
+
+        let track = if state.is_authenticated() {
+            Some(Self::get_track(state, &id).await?)
+        } else {
+            None
+        };
+
+        Ok(SpotifyTrackPreview { url, id, track })
+    }
+
+    /// Converts a simplified rspotify track, which carries no popularity score
+    fn convert_simplified_track(track: &rspotify::model::SimplifiedTrack) -> SpotifyTrack {
+        SpotifyTrack {
+            id: track.id.as_ref().map(|id| id.to_string()),
+            name: track.name.clone(),
+            artists: track.artists.iter().map(|a| a.name.clone()).collect(),
+            album: track
+                .album
+                .as_ref()
+                .map(|album| album.name.clone())
+                .unwrap_or_default(),
+            album_image: track
+                .album
+                .as_ref()
+                .and_then(|album| album.images.first())
+                .map(|img| img.url.clone()),
+            duration_ms: track.duration.num_milliseconds() as u32,
+            popularity: None,
+            preview_url: track.preview_url.clone(),
+            external_url: track.external_urls.get("spotify").cloned(),
+        }
+    }
+
+    /// Converts a saved podcast episode, including where the user stopped listening
+    fn convert_episode(episode: &FullEpisode) -> SpotifyEpisode {
+        SpotifyEpisode {
+            id: episode.id.to_string(),
+            name: episode.name.clone(),
+            show: episode.show.name.clone(),
+            publisher: episode.show.publisher.clone(),
+            description: episode.description.clone(),
+            image: episode.images.first().map(|img| img.url.clone()),
+            duration_ms: episode.duration.num_milliseconds() as u32,
+            resume_position_ms: episode
+                .resume_point
+                .as_ref()
+                .map(|point| point.resume_position.num_milliseconds() as u32),
+            fully_played: episode
+                .resume_point
+                .as_ref()
+                .is_some_and(|point| point.fully_played),
+            release_date: episode.release_date.clone(),
+            external_url: episode.external_urls.get("spotify").cloned(),
+        }
+    }
+
     /// Helper to convert a Spotify track to our domain model
     fn convert_spotify_track(track: &rspotify::model::FullTrack) -> SpotifyTrack {
         SpotifyTrack {
@@ -736,3 +1671,1058 @@ impl SpotifyService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[test]
+    fn profile_country_round_trips_as_an_alpha2_code() {
+        let code = <&'static str>::from(Country::Spain);
+
+        assert_eq!(code, "ES");
+        assert_eq!(SpotifyService::parse_country(code), Some(Country::Spain));
+        assert_eq!(SpotifyService::parse_country(" es "), Some(Country::Spain));
+        // The old Debug-name form is not a market
+        assert_eq!(SpotifyService::parse_country("Spain"), None);
+    }
+
+    #[test]
+    fn batch_track_lookup_is_split_into_requests_of_fifty() {
+        use rspotify::prelude::Id;
+
+        let ids: Vec<String> = (0..120).map(|n| format!("{:0>22}", n)).collect();
+
+        let chunks = SpotifyService::track_id_chunks(&ids).unwrap();
+
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [50, 50, 20]);
+        let flattened: Vec<&str> = chunks.iter().flatten().map(|id| id.id()).collect();
+        assert_eq!(flattened, ids);
+
+        let mut with_bad_id = ids.clone();
+        with_bad_id[70] = "not-an-id".to_string();
+        assert!(SpotifyService::track_id_chunks(&with_bad_id).is_err());
+    }
+
+    #[test]
+    fn recommendations_need_one_to_five_seeds() {
+        for total in [1, MAX_RECOMMENDATION_SEEDS] {
+            assert!(
+                SpotifyService::validate_seed_count(total).is_ok(),
+                "{}",
+                total
+            );
+        }
+        for total in [0, MAX_RECOMMENDATION_SEEDS + 1] {
+            assert!(matches!(
+                SpotifyService::validate_seed_count(total),
+                Err(AppError::Spotify(SpotifyError::InvalidSeedCount(n))) if n == total
+            ));
+        }
+    }
+
+    #[test]
+    fn auth_code_is_extracted_from_the_callback() {
+        let url = "/callback?code=abc123&state=expected";
+
+        assert_eq!(
+            SpotifyService::extract_auth_code(url, "expected").unwrap(),
+            "abc123"
+        );
+    }
+
+    #[test]
+    fn callback_errors_and_bad_codes_are_rejected() {
+        assert!(matches!(
+            SpotifyService::extract_auth_code("/callback?error=access_denied&state=s", "s"),
+            Err(AppError::Spotify(SpotifyError::AuthenticationFailed(reason)))
+                if reason == "user denied access"
+        ));
+        assert!(matches!(
+            SpotifyService::extract_auth_code("/callback?code=abc&state=forged", "s"),
+            Err(AppError::Spotify(SpotifyError::OAuthStateMismatch))
+        ));
+        assert!(matches!(
+            SpotifyService::extract_auth_code("/callback?code=abc", "s"),
+            Err(AppError::Spotify(SpotifyError::OAuthStateMismatch))
+        ));
+        for url in ["/callback?state=s", "/callback?code=&state=s"] {
+            assert!(
+                matches!(
+                    SpotifyService::extract_auth_code(url, "s"),
+                    Err(AppError::Spotify(SpotifyError::InvalidAuthCode))
+                ),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn unset_or_blank_scopes_fall_back_to_the_defaults() {
+        let defaults: HashSet<String> = DEFAULT_SPOTIFY_SCOPES
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        for value in [None, Some(""), Some("  "), Some(" , ,")] {
+            assert_eq!(
+                SpotifyService::parse_scopes(value).unwrap(),
+                defaults,
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn listed_scopes_are_trimmed_and_validated() {
+        let scopes =
+            SpotifyService::parse_scopes(Some(" user-read-email ,user-follow-read,")).unwrap();
+
+        let expected: HashSet<String> = ["user-read-email", "user-follow-read"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(scopes, expected);
+
+        assert!(matches!(
+            SpotifyService::parse_scopes(Some("user-read-email, user-read-minds")),
+            Err(AppError::Validation(message)) if message.contains("user-read-minds")
+        ));
+    }
+
+    #[test]
+    fn oauth_timeout_defaults_and_is_clamped() {
+        assert_eq!(SpotifyService::oauth_timeout_secs(None), 120);
+        assert_eq!(SpotifyService::oauth_timeout_secs(Some(300)), 300);
+        assert_eq!(SpotifyService::oauth_timeout_secs(Some(0)), 30);
+        assert_eq!(SpotifyService::oauth_timeout_secs(Some(29)), 30);
+        assert_eq!(SpotifyService::oauth_timeout_secs(Some(601)), 600);
+        assert_eq!(SpotifyService::oauth_timeout_secs(Some(u64::MAX)), 600);
+    }
+
+    fn client(config: Config) -> SpotifyClient {
+        SpotifyClient::new(
+            SpotifyAuthFlow::AuthCode,
+            Credentials::new("client-id", "secret"),
+            OAuth::default(),
+            config,
+        )
+    }
+
+    #[test]
+    fn clear_deletes_the_token_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("token.json");
+        std::fs::write(&cache_path, "{}").unwrap();
+        let state = SpotifyState::default();
+        state
+            .set_client(client(Config {
+                cache_path: cache_path.clone(),
+                ..Default::default()
+            }))
+            .unwrap();
+
+        state.clear().unwrap();
+
+        assert!(!state.is_authenticated());
+        assert!(!cache_path.exists());
+    }
+
+    #[test]
+    fn clear_tolerates_a_missing_token_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = SpotifyState::default();
+        state
+            .set_client(client(Config {
+                cache_path: dir.path().join("never-written.json"),
+                ..Default::default()
+            }))
+            .unwrap();
+
+        assert!(state.clear().is_ok());
+        assert!(!state.is_authenticated());
+    }
+
+    fn token(expires_at: &str, refresh_token: Option<&str>) -> Token {
+        serde_json::from_value(serde_json::json!({
+            "access_token": "access",
+            "expires_in": 3600,
+            "expires_at": expires_at,
+            "refresh_token": refresh_token,
+        }))
+        .unwrap()
+    }
+
+    /// Session whose client holds `token`
+    async fn state_with_token(token: Option<Token>) -> SpotifyState {
+        session(Config::default(), token).await
+    }
+
+    async fn session(config: Config, token: Option<Token>) -> SpotifyState {
+        let state = SpotifyState::default();
+        let client = client(config);
+        *client.get_token().lock().await.unwrap() = token;
+        state.set_client(client).unwrap();
+        state
+    }
+
+    /// Logged-in session whose API requests go to `server`
+    async fn mock_session(server: &MockServer) -> SpotifyState {
+        let config = Config {
+            api_base_url: format!("{}/v1/", server.url()),
+            ..Default::default()
+        };
+        session(config, Some(token("2100-01-01T00:00:00Z", None))).await
+    }
+
+    fn profile(display_name: &str) -> SpotifyUserProfile {
+        SpotifyUserProfile {
+            id: "user".to_string(),
+            display_name: Some(display_name.to_string()),
+            email: None,
+            country: Some("ES".to_string()),
+            product: None,
+            followers: 0,
+            images: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn auth_status_reflects_client_token_and_profile() {
+        let status = SpotifyState::default().auth_status().await.unwrap();
+        assert!(!status.authenticated);
+        assert!(status.token_expired);
+        assert_eq!(status.display_name, None);
+
+        let status = state_with_token(None).await.auth_status().await.unwrap();
+        assert!(status.authenticated);
+        assert!(status.token_expired);
+
+        let state = state_with_token(Some(token("2000-01-01T00:00:00Z", None))).await;
+        let status = state.auth_status().await.unwrap();
+        assert!(status.authenticated);
+        assert!(status.token_expired);
+
+        let state = state_with_token(Some(token("2100-01-01T00:00:00Z", None))).await;
+        SpotifyService::cache_user_profile(&state, &profile("Ana")).unwrap();
+        let status = state.auth_status().await.unwrap();
+        assert!(status.authenticated);
+        assert!(!status.token_expired);
+        assert_eq!(status.display_name.as_deref(), Some("Ana"));
+    }
+
+    #[tokio::test]
+    async fn token_expiry_is_relative_to_now() {
+        assert_eq!(
+            SpotifyState::default().token_expires_in().await.unwrap(),
+            None
+        );
+
+        let state = state_with_token(Some(token("2000-01-01T00:00:00Z", None))).await;
+        assert!(state.token_expires_in().await.unwrap().unwrap() < 0);
+
+        let state = state_with_token(Some(token("2100-01-01T00:00:00Z", None))).await;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let expires_in = state.token_expires_in().await.unwrap().unwrap();
+        // 2100-01-01T00:00:00Z
+        assert!((4_102_444_800 - now - expires_in).abs() <= 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_needs_a_session_with_a_refresh_token() {
+        assert!(matches!(
+            SpotifyService::refresh_token(&SpotifyState::default()).await,
+            Err(AppError::Spotify(SpotifyError::NotAuthenticated))
+        ));
+
+        for token in [None, Some(token("2100-01-01T00:00:00Z", None))] {
+            let state = state_with_token(token).await;
+            assert!(matches!(
+                SpotifyService::refresh_token(&state).await,
+                Err(AppError::Spotify(SpotifyError::NoRefreshToken))
+            ));
+            // The session is left as it was
+            assert!(state.is_authenticated());
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_profile_is_reused_until_the_client_changes() {
+        let server = MockServer::start(|request| match request.path() {
+            "/v1/me/" => MockResponse::new(
+                200,
+                r#"{"id": "bea", "display_name": "Bea", "country": "ES",
+                    "external_urls": {}, "href": "https://api.spotify.com/v1/users/bea"}"#,
+            ),
+            _ => MockResponse::new(404, ""),
+        });
+        let state = mock_session(&server).await;
+        SpotifyService::cache_user_profile(&state, &profile("Ana")).unwrap();
+
+        let cached = SpotifyService::get_profile(&state, false).await.unwrap();
+        assert_eq!(cached.display_name.as_deref(), Some("Ana"));
+        assert!(server.requests().is_empty());
+
+        // A new session may be another account
+        state.set_client(state.get_client().unwrap()).unwrap();
+        assert!(state.get_cached_user().unwrap().is_none());
+
+        let fetched = SpotifyService::get_profile(&state, false).await.unwrap();
+        assert_eq!(fetched.display_name.as_deref(), Some("Bea"));
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            state.get_cached_user().unwrap().map(|user| user.id),
+            Some(fetched.id)
+        );
+    }
+
+    #[test]
+    fn top_tracks_market_falls_back_to_the_profile_then_us() {
+        let state = SpotifyState::default();
+        assert_eq!(
+            SpotifyService::resolve_market(&state, None).unwrap(),
+            Market::Country(Country::UnitedStates)
+        );
+
+        SpotifyService::cache_user_profile(&state, &profile("Ana")).unwrap();
+        assert_eq!(
+            SpotifyService::resolve_market(&state, None).unwrap(),
+            Market::Country(Country::Spain)
+        );
+
+        // An explicit market wins over the profile
+        assert_eq!(
+            SpotifyService::resolve_market(&state, Some("mx")).unwrap(),
+            Market::Country(Country::Mexico)
+        );
+
+        let mut no_country = profile("Ana");
+        no_country.country = None;
+        SpotifyService::cache_user_profile(&state, &no_country).unwrap();
+        assert_eq!(
+            SpotifyService::resolve_market(&state, None).unwrap(),
+            Market::Country(Country::UnitedStates)
+        );
+    }
+
+    const ARTIST_ID: &str = "0OdUWJ0sBjDrqHygGUXeCF";
+    const TRACK_ID: &str = "4uLU6hMCjMI75M1A2tKUQC";
+
+    fn artist_json(id: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "external_urls": {"spotify": format!("https://open.spotify.com/artist/{}", id)},
+            "followers": {"href": null, "total": 1200},
+            "genres": ["indie rock"],
+            "href": format!("https://api.spotify.com/v1/artists/{}", id),
+            "id": id,
+            "images": [{"url": "https://i.scdn.co/image/artist", "height": 640, "width": 640}],
+            "name": name,
+            "popularity": 61,
+            "type": "artist",
+            "uri": format!("spotify:artist:{}", id),
+        })
+    }
+
+    #[tokio::test]
+    async fn related_artists_accept_ids_urls_and_uris() {
+        let server = MockServer::start(|_| {
+            let body =
+                serde_json::json!({"artists": [artist_json("1vCWHaC5f2uS3yhpwWbIA6", "Avicii")]});
+            MockResponse::new(200, body.to_string())
+        });
+        let state = mock_session(&server).await;
+
+        for input in [
+            ARTIST_ID.to_string(),
+            format!(
+                "https://open.spotify.com/intl-es/artist/{}?si=abc",
+                ARTIST_ID
+            ),
+            format!("spotify:artist:{}", ARTIST_ID),
+        ] {
+            let related = SpotifyService::get_related_artists(&state, &input)
+                .await
+                .unwrap();
+            assert_eq!(related.len(), 1, "{}", input);
+            assert_eq!(related[0].name, "Avicii");
+        }
+
+        let paths: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|request| request.path().to_string())
+            .collect();
+        let expected = format!("/v1/artists/{}/related-artists", ARTIST_ID);
+        assert_eq!(paths, [expected.as_str(); 3]);
+
+        for input in ["not-an-id", "spotify:track:0OdUWJ0sBjDrqHygGUXeCF"] {
+            assert!(matches!(
+                SpotifyService::get_related_artists(&state, input).await,
+                Err(AppError::Spotify(SpotifyError::InvalidId(_)))
+            ));
+        }
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn followed_artists_page_by_cursor() {
+        let server = MockServer::start(|request| {
+            let (items, next, after) = match request.query("after").as_deref() {
+                None => (
+                    vec![artist_json(ARTIST_ID, "First")],
+                    serde_json::json!(
+                        "https://api.spotify.com/v1/me/following?type=artist&after=cursor-1"
+                    ),
+                    "cursor-1",
+                ),
+                // Spotify still returns a cursor on the last page
+                Some(_) => (
+                    vec![artist_json("1vCWHaC5f2uS3yhpwWbIA6", "Last")],
+                    serde_json::Value::Null,
+                    "cursor-2",
+                ),
+            };
+            let body = serde_json::json!({"artists": {
+                "href": "https://api.spotify.com/v1/me/following?type=artist",
+                "items": items,
+                "limit": 1,
+                "next": next,
+                "cursors": {"after": after},
+                "total": 2,
+            }});
+            MockResponse::new(200, body.to_string())
+        });
+        let state = mock_session(&server).await;
+
+        let first = SpotifyService::get_followed_artists(&state, Some(1), None)
+            .await
+            .unwrap();
+        assert_eq!(first.artists[0].name, "First");
+        assert_eq!(first.next_cursor.as_deref(), Some("cursor-1"));
+        assert_eq!(first.total, Some(2));
+
+        let last = SpotifyService::get_followed_artists(&state, Some(500), first.next_cursor)
+            .await
+            .unwrap();
+        assert_eq!(last.artists[0].name, "Last");
+        assert_eq!(last.next_cursor, None);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].query("after"), None);
+        assert_eq!(requests[1].query("after").as_deref(), Some("cursor-1"));
+        // The limit is capped at Spotify's page size
+        assert_eq!(requests[1].query("limit").as_deref(), Some("50"));
+    }
+
+    const PLAYLIST_ID: &str = "37i9dQZF1DXcBWIGoYBM5M";
+
+    fn playlist_json(id: &str, name: &str, owner: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "collaborative": false,
+            "external_urls": {"spotify": format!("https://open.spotify.com/playlist/{}", id)},
+            "href": format!("https://api.spotify.com/v1/playlists/{}", id),
+            "id": id,
+            "images": [
+                {"url": "https://i.scdn.co/image/large", "height": 640, "width": 640},
+                {"url": "https://i.scdn.co/image/small", "height": 60, "width": 60}
+            ],
+            "name": name,
+            "owner": {
+                "display_name": owner,
+                "external_urls": {},
+                "href": "https://api.spotify.com/v1/users/spotify",
+                "id": "spotify",
+            },
+            "public": true,
+            "snapshot_id": "snapshot",
+            "tracks": {"href": "https://api.spotify.com/v1/playlists/tracks", "total": 50},
+        })
+    }
+
+    #[test]
+    fn converts_album_fixtures() {
+        let album: rspotify::model::SimplifiedAlbum = serde_json::from_value(serde_json::json!({
+            "album_type": "single",
+            "artists": [
+                {"external_urls": {}, "href": null, "id": ARTIST_ID, "name": "Bad Bunny"},
+                {"external_urls": {}, "href": null, "id": null, "name": "Guest"}
+            ],
+            "external_urls": {"spotify": "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy"},
+            "href": null,
+            "id": "4aawyAB9vmqN3uQ7FjRGTy",
+            "images": [{"url": "https://i.scdn.co/image/cover", "height": 640, "width": 640}],
+            "name": "Single",
+            "release_date": "2024-03",
+            "release_date_precision": "month",
+        }))
+        .unwrap();
+
+        let converted = SpotifyService::convert_album(&album);
+
+        assert_eq!(
+            converted.id.as_deref(),
+            Some("spotify:album:4aawyAB9vmqN3uQ7FjRGTy")
+        );
+        assert_eq!(converted.name, "Single");
+        assert_eq!(converted.artists, ["Bad Bunny", "Guest"]);
+        assert_eq!(converted.album_type.as_deref(), Some("single"));
+        assert_eq!(converted.release_date.as_deref(), Some("2024-03"));
+        assert_eq!(converted.images, ["https://i.scdn.co/image/cover"]);
+        assert_eq!(
+            converted.external_url.as_deref(),
+            Some("https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy")
+        );
+    }
+
+    #[test]
+    fn converts_playlist_fixtures() {
+        let playlist: rspotify::model::SimplifiedPlaylist = serde_json::from_value(playlist_json(
+            PLAYLIST_ID,
+            "Today's Top Hits",
+            Some("Spotify"),
+        ))
+        .unwrap();
+
+        let converted = SpotifyService::convert_playlist(&playlist);
+
+        assert_eq!(converted.id, format!("spotify:playlist:{}", PLAYLIST_ID));
+        assert_eq!(converted.name, "Today's Top Hits");
+        assert_eq!(converted.owner, "Spotify");
+        assert_eq!(converted.tracks_total, 50);
+        // Only the largest image is kept
+        assert_eq!(converted.images, ["https://i.scdn.co/image/large"]);
+        assert_eq!(converted.public, Some(true));
+
+        // Owners without a display name fall back to their id
+        let playlist: rspotify::model::SimplifiedPlaylist =
+            serde_json::from_value(playlist_json(PLAYLIST_ID, "Mine", None)).unwrap();
+        assert_eq!(
+            SpotifyService::convert_playlist(&playlist).owner,
+            "spotify:user:spotify"
+        );
+    }
+
+    #[test]
+    fn converts_artist_fixtures() {
+        let artist: rspotify::model::FullArtist =
+            serde_json::from_value(artist_json(ARTIST_ID, "Bad Bunny")).unwrap();
+
+        let converted = SpotifyService::convert_artist(&artist);
+
+        assert_eq!(converted.id, format!("spotify:artist:{}", ARTIST_ID));
+        assert_eq!(converted.name, "Bad Bunny");
+        assert_eq!(converted.genres, ["indie rock"]);
+        assert_eq!(converted.popularity, 61);
+        assert_eq!(converted.followers, 1200);
+        assert_eq!(converted.images, ["https://i.scdn.co/image/artist"]);
+        assert_eq!(
+            converted.external_url,
+            Some(format!("https://open.spotify.com/artist/{}", ARTIST_ID))
+        );
+    }
+
+    fn track_json(id: Option<&str>, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "album": {
+                "album_type": "album",
+                "artists": [{"external_urls": {}, "href": null, "id": null, "name": "Artist"}],
+                "external_urls": {},
+                "href": null,
+                "id": null,
+                "images": [{"url": "https://i.scdn.co/image/album", "height": 640, "width": 640}],
+                "name": "Album",
+            },
+            "artists": [{"external_urls": {}, "href": null, "id": null, "name": "Artist"}],
+            "disc_number": 1,
+            "duration_ms": 215000,
+            "explicit": false,
+            "external_ids": {},
+            "external_urls": id.map_or(serde_json::json!({}), |id| {
+                serde_json::json!({"spotify": format!("https://open.spotify.com/track/{}", id)})
+            }),
+            "href": null,
+            "id": id,
+            "is_local": id.is_none(),
+            "name": name,
+            "popularity": 70,
+            "preview_url": "https://p.scdn.co/mp3-preview/abc",
+            "track_number": 1,
+        })
+    }
+
+    /// Offset-paginated response holding `items`
+    fn page_json(items: Vec<serde_json::Value>, offset: u32, total: u32) -> serde_json::Value {
+        let end = offset + items.len() as u32;
+        serde_json::json!({
+            "href": "https://api.spotify.com/v1/me/tracks",
+            "limit": 50,
+            "next": (end < total).then(|| format!("https://api.spotify.com/v1/me/tracks?offset={}", end)),
+            "offset": offset,
+            "previous": null,
+            "total": total,
+            "items": items,
+        })
+    }
+
+    fn saved_track_json(id: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({"added_at": "2024-01-01T00:00:00Z", "track": track_json(Some(id), name)})
+    }
+
+    #[tokio::test]
+    async fn saved_tracks_pass_the_market_through() {
+        let server = MockServer::start(|_| {
+            let page = page_json(vec![saved_track_json(TRACK_ID, "Song")], 0, 1);
+            MockResponse::new(200, page.to_string())
+        });
+        let state = mock_session(&server).await;
+
+        let tracks = SpotifyService::get_saved_tracks(&state, None, None, Some("mx".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(tracks[0].name, "Song");
+
+        // Without a market or profile Spotify picks the token's country
+        SpotifyService::get_saved_tracks(&state, None, None, None)
+            .await
+            .unwrap();
+
+        SpotifyService::cache_user_profile(&state, &profile("Ana")).unwrap();
+        SpotifyService::get_saved_tracks(&state, None, None, None)
+            .await
+            .unwrap();
+
+        let markets: Vec<Option<String>> = server
+            .requests()
+            .iter()
+            .map(|request| request.query("market"))
+            .collect();
+        assert_eq!(
+            markets,
+            [
+                Some("MX".to_string()),
+                Some("from_token".to_string()),
+                Some("ES".to_string())
+            ]
+        );
+    }
+
+    /// Serves a library of `total` saved tracks, paged by the `offset` and `limit` query
+    fn liked_songs_server(total: u32) -> MockServer {
+        MockServer::start(move |request| {
+            let offset: u32 = request.query("offset").map_or(0, |o| o.parse().unwrap());
+            let limit: u32 = request.query("limit").map_or(20, |l| l.parse().unwrap());
+            let items = (offset..total.min(offset + limit))
+                .map(|n| saved_track_json(TRACK_ID, &format!("Song {}", n)))
+                .collect();
+            MockResponse::new(200, page_json(items, offset, total).to_string())
+        })
+    }
+
+    #[test]
+    fn a_short_page_is_the_last_one() {
+        assert!(SpotifyService::is_last_page(0));
+        assert!(SpotifyService::is_last_page(
+            SPOTIFY_BATCH_SIZE as usize - 1
+        ));
+        assert!(!SpotifyService::is_last_page(SPOTIFY_BATCH_SIZE as usize));
+    }
+
+    #[tokio::test]
+    async fn liked_songs_stop_at_a_short_page_or_when_next_is_missing() {
+        // 120 = 50 + 50 + a short page of 20
+        let server = liked_songs_server(120);
+        let state = mock_session(&server).await;
+        let tracks = SpotifyService::get_all_liked_songs(&state, None)
+            .await
+            .unwrap();
+        assert_eq!(tracks.len(), 120);
+        assert_eq!(tracks[119].name, "Song 119");
+        assert_eq!(server.requests().len(), 3);
+
+        // The second full page has no `next`, so no empty third page is requested
+        let server = liked_songs_server(100);
+        let state = mock_session(&server).await;
+        let tracks = SpotifyService::get_all_liked_songs(&state, None)
+            .await
+            .unwrap();
+        assert_eq!(tracks.len(), 100);
+        let offsets: Vec<Option<String>> = server
+            .requests()
+            .iter()
+            .map(|request| request.query("offset"))
+            .collect();
+        assert_eq!(offsets, [Some("0".to_string()), Some("50".to_string())]);
+    }
+
+    /// Serves `total` playlists named by position, paged like `liked_songs_server`
+    fn playlists_server(total: u32) -> MockServer {
+        MockServer::start(move |request| {
+            let offset: u32 = request.query("offset").map_or(0, |o| o.parse().unwrap());
+            let limit: u32 = request.query("limit").map_or(20, |l| l.parse().unwrap());
+            let items = (offset..total.min(offset + limit))
+                .map(|n| playlist_json(&format!("{:0>22}", n), &format!("Playlist {}", n), None))
+                .collect();
+            MockResponse::new(200, page_json(items, offset, total).to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn all_playlists_are_loaded_across_pages() {
+        let server = playlists_server(75);
+        let state = mock_session(&server).await;
+
+        let playlists = SpotifyService::get_all_playlists(&state).await.unwrap();
+
+        assert_eq!(playlists.len(), 75);
+        assert_eq!(playlists[74].name, "Playlist 74");
+        let offsets: Vec<Option<String>> = server
+            .requests()
+            .iter()
+            .map(|request| request.query("offset"))
+            .collect();
+        assert_eq!(offsets, [Some("0".to_string()), Some("50".to_string())]);
+
+        let cached = state.get_cached_playlists().unwrap().unwrap();
+        assert_eq!(cached.playlists.len(), 75);
+        assert_eq!(cached.total, 75);
+    }
+
+    #[tokio::test]
+    async fn only_the_first_playlists_page_is_served_from_cache() {
+        let server = playlists_server(45);
+        let state = mock_session(&server).await;
+
+        let first = SpotifyService::get_playlists(&state, Some(20), None)
+            .await
+            .unwrap();
+        assert_eq!(first.total, 45);
+        let cached = SpotifyService::get_playlists(&state, Some(20), Some(0))
+            .await
+            .unwrap();
+        assert_eq!(cached.playlists.len(), 20);
+        assert_eq!(server.requests().len(), 1);
+
+        let second = SpotifyService::get_playlists(&state, Some(20), Some(20))
+            .await
+            .unwrap();
+        assert_eq!(second.offset, 20);
+        assert_eq!(second.total, 45);
+        assert_eq!(second.playlists[0].name, "Playlist 20");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].query("offset").as_deref(), Some("20"));
+        assert_eq!(requests[1].query("limit").as_deref(), Some("20"));
+        // A later page doesn't replace the cached first page
+        let cache = state.get_cached_playlists().unwrap().unwrap();
+        assert_eq!(cache.playlists[0].name, "Playlist 0");
+    }
+
+    #[test]
+    fn time_ranges_default_to_medium_term() {
+        for (input, expected) in [
+            (None, TimeRange::MediumTerm),
+            (Some("short_term"), TimeRange::ShortTerm),
+            (Some("medium_term"), TimeRange::MediumTerm),
+            (Some("long_term"), TimeRange::LongTerm),
+        ] {
+            assert_eq!(
+                SpotifyService::parse_time_range(input).unwrap(),
+                expected,
+                "{:?}",
+                input
+            );
+        }
+
+        for input in ["forever", "LONG_TERM", ""] {
+            assert!(matches!(
+                SpotifyService::parse_time_range(Some(input)),
+                Err(AppError::Validation(message)) if message.contains("Invalid time range")
+            ));
+        }
+    }
+
+    /// Serializes `payload`, checks the field names and deserializes it back
+    fn round_trip<T>(payload: &T, fields: &[&str]) -> serde_json::Value
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let json = serde_json::to_value(payload).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, fields);
+
+        let parsed: T = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        json
+    }
+
+    #[test]
+    fn stream_payloads_round_trip() {
+        let start = round_trip(&TracksStart { total: 120 }, &["total"]);
+        assert_eq!(start["total"], 120);
+
+        let track: rspotify::model::FullTrack =
+            serde_json::from_value(track_json(Some(TRACK_ID), "Song")).unwrap();
+        let batch = round_trip(
+            &TracksBatch {
+                tracks: vec![SpotifyService::convert_spotify_track(&track)],
+                progress: 42,
+                loaded: 50,
+                total: 120,
+            },
+            &["loaded", "progress", "total", "tracks"],
+        );
+        assert_eq!(batch["tracks"][0]["name"], "Song");
+        assert_eq!(batch["progress"], 42);
+
+        let error = round_trip(
+            &TracksError {
+                message: "rate limited".to_string(),
+            },
+            &["message"],
+        );
+        assert_eq!(error["message"], "rate limited");
+
+        let complete = round_trip(&TracksComplete { total: 120 }, &["total"]);
+        assert_eq!(complete["total"], 120);
+    }
+
+    #[tokio::test]
+    async fn liked_songs_are_exported_page_by_page() {
+        let server = liked_songs_server(60);
+        let state = mock_session(&server).await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_path = dir.path().join("liked.json");
+        let mut progress = Vec::new();
+        let exported = SpotifyService::export_liked_songs_with(
+            &state,
+            json_path.to_str().unwrap(),
+            ExportFormat::Json,
+            None,
+            |p| progress.push(p),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(exported, 60);
+        assert_eq!(
+            progress,
+            [
+                ExportProgress {
+                    exported: 50,
+                    total: 60
+                },
+                ExportProgress {
+                    exported: 60,
+                    total: 60
+                }
+            ]
+        );
+        let tracks: Vec<SpotifyTrack> =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        let names: Vec<String> = tracks.into_iter().map(|track| track.name).collect();
+        let expected: Vec<String> = (0..60).map(|n| format!("Song {}", n)).collect();
+        assert_eq!(names, expected);
+
+        let csv_path = dir.path().join("liked.csv");
+        SpotifyService::export_liked_songs_with(
+            &state,
+            csv_path.to_str().unwrap(),
+            ExportFormat::Csv,
+            None,
+            |_| {},
+        )
+        .await
+        .unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 61);
+        assert_eq!(
+            lines[0],
+            "id,name,artists,album,duration_ms,popularity,external_url"
+        );
+        assert!(lines[1].contains("Song 0"), "{}", lines[1]);
+        // No temporary file is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn download_urls_skip_tracks_without_a_usable_id() {
+        let track = |id: Option<&str>| {
+            let track: rspotify::model::FullTrack =
+                serde_json::from_value(track_json(id, "Song")).unwrap();
+            SpotifyService::convert_spotify_track(&track)
+        };
+        let mut malformed = track(Some(TRACK_ID));
+        malformed.id = Some("spotify:track:short".to_string());
+        let tracks = [
+            track(Some(TRACK_ID)),
+            track(None),
+            malformed,
+            track(Some("1301WleyT98MSxVHPZCA6M")),
+        ];
+
+        let built = SpotifyService::build_download_urls(&tracks);
+
+        assert_eq!(
+            built.urls,
+            [
+                format!("https://open.spotify.com/track/{}", TRACK_ID),
+                "https://open.spotify.com/track/1301WleyT98MSxVHPZCA6M".to_string()
+            ]
+        );
+        assert_eq!(built.skipped, 2);
+    }
+
+    #[tokio::test]
+    async fn preview_only_checks_the_url_without_a_session() {
+        let state = SpotifyState::default();
+
+        let preview = SpotifyService::preview_track(
+            &state,
+            &format!("https://open.spotify.com/track/{}?si=abc", TRACK_ID),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(preview.id, TRACK_ID);
+        assert_eq!(
+            preview.url,
+            format!("https://open.spotify.com/track/{}", TRACK_ID)
+        );
+        assert!(preview.track.is_none());
+
+        for url in [
+            "https://open.spotify.com/album/4aawyAB9vmqN3uQ7FjRGTy",
+            "not a url",
+        ] {
+            assert!(matches!(
+                SpotifyService::preview_track(&state, url).await,
+                Err(AppError::Spotify(SpotifyError::InvalidId(_)))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn preview_resolves_the_track_with_a_session() {
+        let server = MockServer::start(|request| {
+            if request.path() == format!("/v1/tracks/{}", TRACK_ID) {
+                MockResponse::new(200, track_json(Some(TRACK_ID), "Song").to_string())
+            } else {
+                MockResponse::new(404, "")
+            }
+        });
+        let state = mock_session(&server).await;
+
+        let preview = SpotifyService::preview_track(&state, &format!("spotify:track:{}", TRACK_ID))
+            .await
+            .unwrap();
+
+        let track = preview.track.expect("track details");
+        assert_eq!(track.name, "Song");
+        assert_eq!(track.artists, ["Artist"]);
+        assert_eq!(track.album, "Album");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    fn episode_json(resume_point: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "audio_preview_url": null,
+            "description": "Episode notes",
+            "duration_ms": 3_600_000,
+            "explicit": false,
+            "external_urls": {"spotify": "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"},
+            "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+            "id": "512ojhOuo1ktJprKbVcKyQ",
+            "images": [{"url": "https://i.scdn.co/image/episode", "height": 640, "width": 640}],
+            "is_externally_hosted": false,
+            "is_playable": true,
+            "language": "es",
+            "languages": ["es"],
+            "name": "Episode 1",
+            "release_date": "2024-05-01",
+            "release_date_precision": "day",
+            "resume_point": resume_point,
+            "show": {
+                "available_markets": [],
+                "copyrights": [],
+                "description": "A show",
+                "explicit": false,
+                "external_urls": {},
+                "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+                "id": "38bS44xjbVVZ3No3ByF1dJ",
+                "images": [],
+                "is_externally_hosted": null,
+                "languages": ["es"],
+                "media_type": "audio",
+                "name": "The Show",
+                "publisher": "Publisher",
+            },
+        })
+    }
+
+    #[test]
+    fn converts_episode_fixtures() {
+        let episode: FullEpisode = serde_json::from_value(episode_json(
+            serde_json::json!({"fully_played": false, "resume_position_ms": 90_500}),
+        ))
+        .unwrap();
+
+        let converted = SpotifyService::convert_episode(&episode);
+
+        assert_eq!(converted.id, "spotify:episode:512ojhOuo1ktJprKbVcKyQ");
+        assert_eq!(converted.name, "Episode 1");
+        assert_eq!(converted.show, "The Show");
+        assert_eq!(converted.publisher, "Publisher");
+        assert_eq!(converted.description, "Episode notes");
+        assert_eq!(
+            converted.image.as_deref(),
+            Some("https://i.scdn.co/image/episode")
+        );
+        assert_eq!(converted.duration_ms, 3_600_000);
+        assert_eq!(converted.resume_position_ms, Some(90_500));
+        assert!(!converted.fully_played);
+        assert_eq!(converted.release_date, "2024-05-01");
+        assert_eq!(
+            converted.external_url.as_deref(),
+            Some("https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ")
+        );
+
+        // Episodes never started have no resume point
+        let episode: FullEpisode =
+            serde_json::from_value(episode_json(serde_json::Value::Null)).unwrap();
+        let converted = SpotifyService::convert_episode(&episode);
+        assert_eq!(converted.resume_position_ms, None);
+        assert!(!converted.fully_played);
+    }
+
+    #[tokio::test]
+    async fn saved_tracks_count_reports_the_library_total() {
+        let server = liked_songs_server(137);
+        let state = mock_session(&server).await;
+
+        let total = SpotifyService::get_saved_tracks_count(&state, None)
+            .await
+            .unwrap();
+
+        assert_eq!(total, 137);
+        // A one-item page is enough to read the total
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].query("limit").as_deref(), Some("1"));
+    }
+}
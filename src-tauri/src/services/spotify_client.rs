@@ -0,0 +1,207 @@
+//! Spotify client shared by both OAuth flows
+//!
+//! The Authorization Code flow needs the client secret, which a desktop build
+//! has to ship; the PKCE flow only needs the client ID. Both clients expose the
+//! same API through rspotify's traits, so the session stores either one.
+
+use std::sync::Arc;
+
+use rspotify::{
+    clients::{BaseClient, OAuthClient},
+    http::HttpClient,
+    sync::Mutex,
+    AuthCodePkceSpotify, AuthCodeSpotify, ClientResult, Config, Credentials, OAuth, Token,
+};
+
+use crate::errors::SpotifyError;
+
+/// Environment variable selecting the OAuth flow (`pkce` or `auth_code`)
+pub const SPOTIFY_AUTH_FLOW_ENV: &str = "SPOTIFY_AUTH_FLOW";
+
+/// OAuth flow used to log in to Spotify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotifyAuthFlow {
+    /// Authorization Code flow, needs `RSPOTIFY_CLIENT_SECRET`
+    AuthCode,
+    /// Authorization Code flow with PKCE, needs only `RSPOTIFY_CLIENT_ID`
+    Pkce,
+}
+
+impl SpotifyAuthFlow {
+    /// Reads the flow from `SPOTIFY_AUTH_FLOW`, defaulting to the Authorization Code flow
+    pub fn from_env() -> Self {
+        match std::env::var(SPOTIFY_AUTH_FLOW_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("pkce") => Self::Pkce,
+            _ => Self::AuthCode,
+        }
+    }
+
+    /// Checks that `creds` has what this flow needs
+    ///
+    /// Only the Authorization Code flow needs the client secret.
+    pub fn check_credentials(self, creds: &Credentials) -> Result<(), SpotifyError> {
+        match self {
+            Self::AuthCode if creds.secret.is_none() => Err(SpotifyError::CredentialsNotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Authenticated Spotify client for either OAuth flow
+///
+/// Delegates rspotify's client traits to the wrapped client, so every endpoint
+/// (and token refreshing) works the same whichever flow logged in.
+#[derive(Clone, Debug)]
+pub enum SpotifyClient {
+    AuthCode(AuthCodeSpotify),
+    Pkce(AuthCodePkceSpotify),
+}
+
+// rspotify's client traits require `Default`
+impl Default for SpotifyClient {
+    fn default() -> Self {
+        Self::AuthCode(AuthCodeSpotify::default())
+    }
+}
+
+impl SpotifyClient {
+    /// Creates a client for `flow`
+    pub fn new(flow: SpotifyAuthFlow, creds: Credentials, oauth: OAuth, config: Config) -> Self {
+        match flow {
+            SpotifyAuthFlow::AuthCode => {
+                Self::AuthCode(AuthCodeSpotify::with_config(creds, oauth, config))
+            }
+            SpotifyAuthFlow::Pkce => {
+                Self::Pkce(AuthCodePkceSpotify::with_config(creds, oauth, config))
+            }
+        }
+    }
+
+    /// Builds the URL the user logs in at
+    ///
+    /// For PKCE this also generates the code verifier that `request_token` sends,
+    /// so it must be called before exchanging the code.
+    pub fn authorize_url(&mut self) -> ClientResult<String> {
+        match self {
+            Self::AuthCode(client) => client.get_authorize_url(false),
+            Self::Pkce(client) => client.get_authorize_url(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseClient for SpotifyClient {
+    fn get_config(&self) -> &Config {
+        match self {
+            Self::AuthCode(client) => client.get_config(),
+            Self::Pkce(client) => client.get_config(),
+        }
+    }
+
+    fn get_http(&self) -> &HttpClient {
+        match self {
+            Self::AuthCode(client) => client.get_http(),
+            Self::Pkce(client) => client.get_http(),
+        }
+    }
+
+    fn get_creds(&self) -> &Credentials {
+        match self {
+            Self::AuthCode(client) => client.get_creds(),
+            Self::Pkce(client) => client.get_creds(),
+        }
+    }
+
+    fn get_token(&self) -> Arc<Mutex<Option<Token>>> {
+        match self {
+            Self::AuthCode(client) => client.get_token(),
+            Self::Pkce(client) => client.get_token(),
+        }
+    }
+
+    async fn refetch_token(&self) -> ClientResult<Option<Token>> {
+        match self {
+            Self::AuthCode(client) => client.refetch_token().await,
+            Self::Pkce(client) => client.refetch_token().await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthClient for SpotifyClient {
+    fn get_oauth(&self) -> &OAuth {
+        match self {
+            Self::AuthCode(client) => client.get_oauth(),
+            Self::Pkce(client) => client.get_oauth(),
+        }
+    }
+
+    async fn request_token(&self, code: &str) -> ClientResult<()> {
+        match self {
+            Self::AuthCode(client) => client.request_token(code).await,
+            Self::Pkce(client) => client.request_token(code).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+
+    fn oauth() -> OAuth {
+        OAuth {
+            redirect_uri: "http://127.0.0.1:8888/callback".to_string(),
+            state: "state".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pkce_challenge_is_the_hashed_verifier() {
+        let mut client = SpotifyClient::new(
+            SpotifyAuthFlow::Pkce,
+            Credentials::new_pkce("client-id"),
+            oauth(),
+            Config::default(),
+        );
+
+        let url = tauri::Url::parse(&client.authorize_url().unwrap()).unwrap();
+
+        let SpotifyClient::Pkce(pkce) = &client else {
+            panic!("expected a PKCE client");
+        };
+        let verifier = pkce.verifier.as_deref().expect("verifier generated");
+        assert!((43..=128).contains(&verifier.len()));
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let expected = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(Sha256::digest(verifier.as_bytes()));
+        assert_eq!(param("code_challenge"), Some(expected));
+        assert_eq!(param("code_challenge_method").as_deref(), Some("S256"));
+        assert_eq!(param("client_id").as_deref(), Some("client-id"));
+    }
+
+    #[test]
+    fn only_the_auth_code_flow_needs_a_secret() {
+        let without_secret = Credentials::new_pkce("client-id");
+        let with_secret = Credentials::new("client-id", "secret");
+
+        assert!(matches!(
+            SpotifyAuthFlow::AuthCode.check_credentials(&without_secret),
+            Err(SpotifyError::CredentialsNotFound)
+        ));
+        assert!(SpotifyAuthFlow::AuthCode
+            .check_credentials(&with_secret)
+            .is_ok());
+        assert!(SpotifyAuthFlow::Pkce
+            .check_credentials(&without_secret)
+            .is_ok());
+    }
+}
@@ -0,0 +1,232 @@
+//! Helpers shared by unit tests
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use tiny_http::{Header, Response, Server};
+
+#[cfg(target_os = "linux")]
+use {
+    crate::services::allowed_roots::ALLOWED_ROOTS_ENV, crate::utils::path::ENV_LOCK,
+    std::ffi::OsString, std::path::PathBuf, std::sync::MutexGuard,
+};
+
+/// Request received by a [`MockServer`]
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Path and query string, e.g. `/2.0/?method=track.getinfo`
+    pub url: String,
+    /// Header names are lowercased
+    pub headers: Vec<(String, String)>,
+}
+
+impl MockRequest {
+    /// Value of a request header, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Decoded value of a query string parameter
+    pub fn query(&self, name: &str) -> Option<String> {
+        let (_, query) = self.url.split_once('?')?;
+        serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+            .ok()?
+            .into_iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Path without the query string
+    pub fn path(&self) -> &str {
+        self.url.split('?').next().unwrap_or_default()
+    }
+}
+
+/// Response a [`MockServer`] sends back
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+}
+
+/// HTTP server on a random local port answering with a handler
+///
+/// Every request is recorded; the server stops when dropped.
+pub struct MockServer {
+    server: Arc<Server>,
+    url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts the server; each request is answered on its own thread, so the
+    /// handler may block to simulate a slow response
+    pub fn start(handler: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static) -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("mock server"));
+        let url = format!("http://{}", server.server_addr());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+
+        let thread = {
+            let server = Arc::clone(&server);
+            let requests = Arc::clone(&requests);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    let recorded = MockRequest {
+                        url: request.url().to_string(),
+                        headers: request
+                            .headers()
+                            .iter()
+                            .map(|header| {
+                                (
+                                    header.field.as_str().as_str().to_ascii_lowercase(),
+                                    header.value.as_str().to_string(),
+                                )
+                            })
+                            .collect(),
+                    };
+                    requests.lock().unwrap().push(recorded.clone());
+
+                    let handler = Arc::clone(&handler);
+                    std::thread::spawn(move || {
+                        let reply = handler(&recorded);
+                        let mut response =
+                            Response::from_data(reply.body).with_status_code(reply.status);
+                        for (name, value) in reply.headers {
+                            response.add_header(
+                                Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap(),
+                            );
+                        }
+                        let _ = request.respond(response);
+                    });
+                }
+            })
+        };
+
+        Self {
+            server,
+            url,
+            requests,
+            thread: Some(thread),
+        }
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:41234`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Writes an MP3 of silent MPEG-1 Layer III frames (128 kbps, 44.1 kHz, ~0.5 s)
+pub fn write_silent_mp3(path: &Path) {
+    const FRAME_LEN: usize = 417;
+    let mut frame = vec![0u8; FRAME_LEN];
+    frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+    std::fs::write(path, frame.repeat(20)).unwrap();
+}
+
+/// Points `HOME` and the XDG folders at a temporary home until dropped
+///
+/// Also clears `MUSICPLAYER_ALLOWED_ROOTS`; holds [`ENV_LOCK`] meanwhile.
+#[cfg(target_os = "linux")]
+pub struct FakeHome {
+    home: tempfile::TempDir,
+    saved: Vec<(&'static str, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+#[cfg(target_os = "linux")]
+impl FakeHome {
+    const VARS: [&'static str; 5] = [
+        "HOME",
+        "XDG_CONFIG_HOME",
+        "XDG_DATA_HOME",
+        "XDG_MUSIC_DIR",
+        ALLOWED_ROOTS_ENV,
+    ];
+
+    pub fn new() -> Self {
+        let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let home = tempfile::tempdir().unwrap();
+        let saved = Self::VARS
+            .iter()
+            .map(|var| (*var, std::env::var_os(var)))
+            .collect();
+        std::env::set_var("HOME", home.path());
+        std::env::set_var("XDG_CONFIG_HOME", home.path().join(".config"));
+        std::env::set_var("XDG_DATA_HOME", home.path().join(".local/share"));
+        std::env::remove_var("XDG_MUSIC_DIR");
+        std::env::remove_var(ALLOWED_ROOTS_ENV);
+        Self {
+            home,
+            saved,
+            _lock: lock,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.home.path()
+    }
+
+    pub fn mkdir(&self, name: &str) -> PathBuf {
+        let dir = self.path().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    pub fn write_user_dirs(&self, music: &str) {
+        let config = self.mkdir(".config");
+        std::fs::write(
+            config.join("user-dirs.dirs"),
+            format!("XDG_MUSIC_DIR=\"{}\"\n", music),
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for FakeHome {
+    fn drop(&mut self) {
+        for (var, value) in &self.saved {
+            match value {
+                Some(value) => std::env::set_var(var, value),
+                None => std::env::remove_var(var),
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Track export helpers
+//!
+//! Writes tracks to JSON or CSV incrementally, so a large library can be exported
+//! page by page, and local tracks to extended M3U playlists. Output goes to a
+//! `.part` file that is renamed when complete, so an interrupted export never
+//! leaves a truncated file at the requested path.
+
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::domain::music::MusicFile;
+use crate::domain::spotify::{ExportFormat, SpotifyTrack};
+use crate::errors::{AppError, FileError};
+
+/// CSV header, matching the columns written by `csv_row`
+const CSV_HEADER: &str = "id,name,artists,album,duration_ms,popularity,external_url";
+
+/// Streams tracks to an export file
+pub struct TrackExportWriter {
+    writer: BufWriter<File>,
+    format: ExportFormat,
+    written: usize,
+    part_path: PathBuf,
+    final_path: PathBuf,
+}
+
+impl TrackExportWriter {
+    /// Creates the export file and writes the format's header
+    pub fn create(path: &Path, format: ExportFormat) -> Result<Self, AppError> {
+        let mut part_name = path.as_os_str().to_owned();
+        part_name.push(".part");
+        let part_path = PathBuf::from(part_name);
+
+        let mut writer = BufWriter::new(File::create(&part_path)?);
+        match format {
+            ExportFormat::Json => writer.write_all(b"[")?,
+            ExportFormat::Csv => writeln!(writer, "{}", CSV_HEADER)?,
+        }
+
+        Ok(Self {
+            writer,
+            format,
+            written: 0,
+            part_path,
+            final_path: path.to_path_buf(),
+        })
+    }
+
+    /// Appends tracks to the file
+    pub fn write_tracks(&mut self, tracks: &[SpotifyTrack]) -> Result<(), AppError> {
+        for track in tracks {
+            match self.format {
+                ExportFormat::Json => {
+                    let json = serde_json::to_string(track)
+                        .map_err(|e| FileError::Export(e.to_string()))?;
+                    let separator = if self.written == 0 { "\n  " } else { ",\n  " };
+                    write!(self.writer, "{}{}", separator, json)?;
+                }
+                ExportFormat::Csv => writeln!(self.writer, "{}", csv_row(track))?,
+            }
+            self.written += 1;
+        }
+        Ok(())
+    }
+
+    /// Writes the footer and moves the file into place, returning the track count
+    pub fn finish(mut self) -> Result<usize, AppError> {
+        if self.format == ExportFormat::Json {
+            self.writer.write_all(b"\n]\n")?;
+        }
+        self.writer.flush()?;
+        fs::rename(&self.part_path, &self.final_path)?;
+        Ok(self.written)
+    }
+
+    /// Removes the partial file after a failed export
+    pub fn discard(self) {
+        drop(self.writer);
+        let _ = fs::remove_file(&self.part_path);
+    }
+}
+
+/// Writes local tracks to an extended M3U playlist, keeping their order
+///
+/// Entries use the tracks' absolute paths, so the playlist keeps working if it
+/// is moved.
+pub fn export_m3u(path: &Path, tracks: &[MusicFile]) -> Result<(), AppError> {
+    let mut part_name = path.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+
+    let written = File::create(&part_path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "#EXTM3U")?;
+        for track in tracks {
+            writeln!(writer, "{}", m3u_extinf(track))?;
+            writeln!(writer, "{}", track.path)?;
+        }
+        writer.flush()
+    });
+
+    if let Err(e) = written.and_then(|_| fs::rename(&part_path, path)) {
+        let _ = fs::remove_file(&part_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Formats the `#EXTINF:<seconds>,<artist> - <title>` line for a track
+///
+/// The duration is `-1` when unknown, as the format expects.
+fn m3u_extinf(track: &MusicFile) -> String {
+    let duration = track.duration.map_or(-1, i64::from);
+    let title = track.title.as_deref().unwrap_or_default();
+    let display = match track.artist.as_deref() {
+        Some(artist) if !artist.is_empty() => format!("{} - {}", artist, title),
+        _ => title.to_string(),
+    };
+    // A line break would end the directive early
+    format!(
+        "#EXTINF:{},{}",
+        duration,
+        display.replace(['\r', '\n'], " ")
+    )
+}
+
+/// Formats a track as one CSV line (RFC 4180 quoting, artists joined by `; `)
+fn csv_row(track: &SpotifyTrack) -> String {
+    [
+        csv_field(track.id.as_deref().unwrap_or_default()),
+        csv_field(&track.name),
+        csv_field(&track.artists.join("; ")),
+        csv_field(&track.album),
+        Cow::Owned(track.duration_ms.to_string()),
+        Cow::Owned(track.popularity.map(|p| p.to_string()).unwrap_or_default()),
+        csv_field(track.external_url.as_deref().unwrap_or_default()),
+    ]
+    .join(",")
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
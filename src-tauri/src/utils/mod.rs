@@ -3,8 +3,16 @@
 //! This module contains reusable utility functions for validation,
 //! path manipulation, and other common operations.
 
+pub mod export;
 pub mod path;
+pub mod playlist;
+pub mod retry;
+pub mod text;
 pub mod validation;
 
+pub use export::*;
 pub use path::*;
+pub use playlist::*;
+pub use retry::*;
+pub use text::*;
 pub use validation::*;
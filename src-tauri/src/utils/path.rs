@@ -1,33 +1,144 @@
 //! Path manipulation and validation utilities
 
-use std::path::{Path, PathBuf};
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
 
 use crate::errors::{AppError, FileError};
 
+/// Marks a path that isn't valid Unicode; the OS-native bytes follow in base64
+///
+/// Such paths can't travel to the frontend as plain strings, so
+/// [`path_to_string`] encodes them this way and [`validate_path`] (and so every
+/// file command) accepts the encoded form.
+pub const NATIVE_PATH_PREFIX: &str = "native-path:";
+
+/// Converts a path into the string form commands accept
+///
+/// Valid Unicode paths are returned as they are; others are encoded after
+/// [`NATIVE_PATH_PREFIX`] so the exact file can still be found again.
+pub fn path_to_string(path: &Path) -> String {
+    match path.to_str() {
+        Some(path_str) => path_str.to_string(),
+        None => format!(
+            "{}{}",
+            NATIVE_PATH_PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(os_bytes(path.as_os_str()))
+        ),
+    }
+}
+
+/// Parses a path received from the frontend, decoding the native form
+pub fn path_from_string(path: &str) -> Result<PathBuf, AppError> {
+    let Some(encoded) = path.strip_prefix(NATIVE_PATH_PREFIX) else {
+        return Ok(PathBuf::from(path));
+    };
+
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(os_string_from_bytes)
+        .map(PathBuf::from)
+        .ok_or_else(|| FileError::InvalidPath(path.to_string()).into())
+}
+
+/// Raw bytes of an OS string: bytes on Unix, UTF-16 code units (little endian) on Windows
+#[cfg(unix)]
+fn os_bytes(value: &std::ffi::OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    value.as_bytes().to_vec()
+}
+
+#[cfg(windows)]
+fn os_bytes(value: &std::ffi::OsStr) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+    value.encode_wide().flat_map(u16::to_le_bytes).collect()
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(OsString::from_vec(bytes))
+}
+
+#[cfg(windows)]
+fn os_string_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    Some(OsString::from_wide(&wide))
+}
+
 /// Validates that a file path is safe and exists
 ///
 /// Prevents path traversal attacks and ensures the path is accessible.
-/// Returns a canonicalized path if valid.
+/// Accepts the [`NATIVE_PATH_PREFIX`] form for paths that aren't valid Unicode.
+/// Returns a canonicalized path (see [`canonical_path`]) if valid.
 pub fn validate_path(path: &str) -> Result<PathBuf, AppError> {
-    let path_buf = PathBuf::from(path);
+    let path_buf = path_from_string(path)?;
 
-    // Check if path exists
-    if !path_buf.exists() {
-        return Err(FileError::NotFound(path.to_string()).into());
+    // Check if path exists, keeping permission failures distinct from missing paths
+    if let Err(e) = std::fs::metadata(&path_buf) {
+        return Err(match e.kind() {
+            ErrorKind::PermissionDenied => FileError::PermissionDenied(path.to_string()),
+            _ => FileError::NotFound(path.to_string()),
+        }
+        .into());
     }
 
     // Prevent path traversal attacks
-    if path.contains("..") {
+    if path.contains("..") || path_buf.components().any(|c| c == Component::ParentDir) {
         return Err(FileError::PathTraversal(path.to_string()).into());
     }
 
     // Canonicalize path to prevent symlink attacks
-    path_buf
-        .canonicalize()
-        .map_err(|e| FileError::Canonicalize(format!("{}: {}", path, e)).into())
+    canonical_path(&path_buf).map_err(|e| match e.kind() {
+        ErrorKind::PermissionDenied => FileError::PermissionDenied(path.to_string()).into(),
+        _ => FileError::Canonicalize(format!("{}: {}", path, e)).into(),
+    })
+}
+
+/// Canonicalizes a path into the absolute form the app hands to the frontend
+///
+/// On Windows `canonicalize` returns verbatim paths (`\\?\C:\...`), which the
+/// webview and the fs plugin don't accept, so the prefix is removed again.
+pub fn canonical_path(path: &Path) -> std::io::Result<PathBuf> {
+    let canonical = path.canonicalize()?;
+
+    if cfg!(windows) {
+        if let Some(stripped) = canonical.to_str().and_then(strip_verbatim_prefix) {
+            return Ok(PathBuf::from(stripped));
+        }
+    }
+    Ok(canonical)
 }
 
-/// Validates that a path is a directory
+/// Turns `\\?\C:\dir` into `C:\dir` and `\\?\UNC\server\share` into `\\server\share`
+///
+/// Returns `None` for paths without the prefix and for other verbatim forms
+/// (e.g. volume GUIDs), which have no plain equivalent.
+fn strip_verbatim_prefix(path: &str) -> Option<String> {
+    let rest = path.strip_prefix(r"\\?\")?;
+
+    if let Some(unc) = rest.strip_prefix(r"UNC\") {
+        return Some(format!(r"\\{}", unc));
+    }
+
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => Some(rest.to_string()),
+        _ => None,
+    }
+}
+
+/// Validates that a path is a readable directory
 pub fn validate_directory(path: &str) -> Result<PathBuf, AppError> {
     let validated = validate_path(path)?;
 
@@ -35,6 +146,27 @@ pub fn validate_directory(path: &str) -> Result<PathBuf, AppError> {
         return Err(FileError::NotDirectory(path.to_string()).into());
     }
 
+    // Listing the directory is what scans need, so check it up front
+    if let Err(e) = std::fs::read_dir(&validated) {
+        if e.kind() == ErrorKind::PermissionDenied {
+            return Err(FileError::PermissionDenied(path.to_string()).into());
+        }
+    }
+
+    Ok(validated)
+}
+
+/// Validates a path and ensures it lies inside one of `roots`
+///
+/// `roots` must be canonical. Returns the canonical path, like [`validate_path`],
+/// so symlinks and `\\?\` prefixes can't be used to escape a root.
+pub fn validate_within_roots(path: &str, roots: &[PathBuf]) -> Result<PathBuf, AppError> {
+    let validated = validate_path(path)?;
+
+    if !roots.iter().any(|root| validated.starts_with(root)) {
+        return Err(FileError::OutsideAllowedRoots(path.to_string()).into());
+    }
+
     Ok(validated)
 }
 
@@ -50,6 +182,9 @@ pub fn validate_file(path: &str) -> Result<PathBuf, AppError> {
 }
 
 /// Validates and sanitizes an output path to prevent path traversal attacks
+///
+/// Requires the parent directory to exist; see `validate_or_create_output_path`
+/// for the variant used by downloads.
 pub fn validate_output_path(path: &str) -> Result<PathBuf, AppError> {
     if path.contains("..") {
         return Err(FileError::PathTraversal(path.to_string()).into());
@@ -69,6 +204,65 @@ pub fn validate_output_path(path: &str) -> Result<PathBuf, AppError> {
     Ok(path_buf)
 }
 
+/// Validates an output directory and creates it (with any missing parents) if needed
+///
+/// The traversal check runs before anything is created on disk.
+pub fn validate_or_create_output_path(path: &str) -> Result<PathBuf, AppError> {
+    if path.contains("..") {
+        return Err(FileError::PathTraversal(path.to_string()).into());
+    }
+
+    let path_buf = PathBuf::from(path);
+
+    if !path_buf.is_dir() {
+        tracing::info!("📁 Creating output directory: {}", path_buf.display());
+        std::fs::create_dir_all(&path_buf).map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => FileError::PermissionDenied(path.to_string()).into(),
+            _ => AppError::Io(e),
+        })?;
+    }
+
+    Ok(path_buf)
+}
+
+/// Ensures files can be created in a directory
+///
+/// Creates and removes a probe file, since permission bits alone miss read-only
+/// mounts and ACLs. Lets downloads fail up front instead of midway through a batch.
+pub fn validate_writable(dir: &Path) -> Result<(), AppError> {
+    let probe = dir.join(format!(".musicplayer-write-test-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => {
+                FileError::PermissionDenied(dir.display().to_string()).into()
+            }
+            _ => AppError::Io(e),
+        })?;
+
+    if let Err(e) = std::fs::remove_file(&probe) {
+        tracing::warn!("⚠️ Could not remove {}: {}", probe.display(), e);
+    }
+    Ok(())
+}
+
+/// Gets the directory where the app keeps its own state (caches, download queue)
+pub fn get_app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .or_else(|| std::env::temp_dir().parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("musicplayer")
+}
+
+/// Gets the directory holding the user's settings
+pub fn get_app_config_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|dir| dir.join("musicplayer"))
+        .unwrap_or_else(get_app_data_dir)
+}
+
 /// Gets the default music folder path for the current operating system
 pub fn get_default_music_folder() -> Result<String, AppError> {
     let music_path = get_music_folder_path()?;
@@ -80,48 +274,45 @@ pub fn get_default_music_folder() -> Result<String, AppError> {
         .map_err(AppError::from)
 }
 
-/// Gets the music folder path based on the operating system
+/// Gets the music folder path for the current user
+///
+/// Uses the platform's Music folder (`dirs::audio_dir`, which reads `user-dirs.dirs`
+/// on Linux and so knows localized names), then `~/Music`. Falls back to the home
+/// directory when no Music folder exists, so first-run users always get a usable
+/// starting point. Only fails if the home directory itself cannot be resolved.
 fn get_music_folder_path() -> Result<PathBuf, AppError> {
-    #[cfg(target_os = "windows")]
-    {
-        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
-            let music_path = PathBuf::from(user_profile).join("Music");
-            if music_path.exists() && music_path.is_dir() {
-                return Ok(music_path);
-            }
-        }
-    }
+    let home = dirs::home_dir()
+        .filter(|home| home.is_dir())
+        .ok_or_else(|| {
+            FileError::NotFound(
+                "Default music folder not found: home directory unknown".to_string(),
+            )
+        })?;
 
-    #[cfg(target_os = "macos")]
-    {
-        if let Some(home) = std::env::var_os("HOME") {
-            let music_path = PathBuf::from(home).join("Music");
-            if music_path.exists() && music_path.is_dir() {
-                return Ok(music_path);
-            }
-        }
+    // user-dirs.dirs points a disabled folder at $HOME
+    if let Some(music_path) = dirs::audio_dir().filter(|p| p.is_dir() && *p != home) {
+        return Ok(music_path);
     }
 
     #[cfg(target_os = "linux")]
-    {
-        if let Some(home) = std::env::var_os("HOME") {
-            // Try XDG_MUSIC_DIR first
-            if let Some(xdg_music) = std::env::var_os("XDG_MUSIC_DIR") {
-                let music_path = PathBuf::from(xdg_music);
-                if music_path.exists() && music_path.is_dir() {
-                    return Ok(music_path);
-                }
-            }
-
-            // Fallback to ~/Music
-            let music_path = PathBuf::from(home).join("Music");
-            if music_path.exists() && music_path.is_dir() {
-                return Ok(music_path);
-            }
+    if let Some(xdg_music) = std::env::var_os("XDG_MUSIC_DIR") {
+        let music_path = PathBuf::from(xdg_music);
+        if music_path.is_dir() {
+            return Ok(music_path);
         }
     }
 
-    Err(FileError::NotFound("Default music folder not found".to_string()).into())
+    // ~/Music on every platform
+    let music_path = home.join("Music");
+    if music_path.is_dir() {
+        return Ok(music_path);
+    }
+
+    tracing::warn!(
+        "📁 Music folder not found, falling back to home directory: {}",
+        home.display()
+    );
+    Ok(home)
 }
 
 /// Checks if a file has a valid audio extension
@@ -133,3 +324,179 @@ pub fn is_audio_file(path: &Path) -> bool {
         .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
         .unwrap_or(false)
 }
+
+/// Serializes tests that change or read process-wide env vars such as `HOME`
+#[cfg(test)]
+pub(crate) static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(target_os = "linux")]
+    use crate::test_support::FakeHome;
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_directory_reports_permission_denied() {
+        use crate::services::FileService;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Root ignores permission bits, so there is nothing to check
+        let readable = std::fs::read_dir(&locked).is_ok();
+        let locked_str = locked.to_string_lossy().into_owned();
+        let validated = validate_directory(&locked_str);
+        let scanned = FileService::scan_music_folder(&locked_str, false, None, None);
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        if readable {
+            return;
+        }
+
+        assert!(matches!(
+            validated,
+            Err(AppError::File(FileError::PermissionDenied(_)))
+        ));
+        assert!(matches!(
+            scanned,
+            Err(AppError::File(FileError::PermissionDenied(_)))
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn music_folder_prefers_the_user_dirs_entry() {
+        let home = FakeHome::new();
+        let musik = home.mkdir("Musik");
+        home.mkdir("Music");
+        home.write_user_dirs("$HOME/Musik");
+
+        assert_eq!(get_music_folder_path().unwrap(), musik);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn music_folder_falls_back_to_xdg_music_dir_then_home_music() {
+        let home = FakeHome::new();
+        let music = home.mkdir("Music");
+        // A disabled entry points at $HOME and is ignored
+        home.write_user_dirs("$HOME/");
+
+        assert_eq!(get_music_folder_path().unwrap(), music);
+
+        let elsewhere = home.mkdir("elsewhere");
+        std::env::set_var("XDG_MUSIC_DIR", &elsewhere);
+        assert_eq!(get_music_folder_path().unwrap(), elsewhere);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn music_folder_falls_back_to_home() {
+        let home = FakeHome::new();
+
+        assert_eq!(get_music_folder_path().unwrap(), home.path());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn music_folder_fails_without_a_home() {
+        let home = FakeHome::new();
+        std::env::set_var("HOME", home.path().join("missing"));
+
+        assert!(matches!(
+            get_music_folder_path(),
+            Err(AppError::File(FileError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn verbatim_prefixes_are_stripped() {
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\C:\Music\song.mp3").as_deref(),
+            Some(r"C:\Music\song.mp3")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\UNC\server\share\song.mp3").as_deref(),
+            Some(r"\\server\share\song.mp3")
+        );
+        // No plain equivalent, or nothing to strip
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\Volume{b75e2c83-0000-0000-0000-602f00000000}\Music"),
+            None
+        );
+        assert_eq!(strip_verbatim_prefix(r"C:\Music"), None);
+        assert_eq!(strip_verbatim_prefix("/home/me/Music"), None);
+    }
+
+    #[test]
+    fn canonical_paths_are_absolute_without_a_verbatim_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let canonical = canonical_path(&nested.join(".")).unwrap();
+        assert!(canonical.is_absolute());
+        assert!(!canonical.to_string_lossy().starts_with(r"\\?\"));
+        assert_eq!(canonical, canonical_path(&nested).unwrap());
+        assert!(canonical.ends_with(Path::new("a").join("b")));
+    }
+
+    #[test]
+    fn missing_output_directories_are_created_with_their_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("Downloads").join("Artist").join("Album");
+
+        let created = validate_or_create_output_path(&nested.to_string_lossy()).unwrap();
+        assert_eq!(created, nested);
+        assert!(nested.is_dir());
+        // Existing directories are accepted as they are
+        assert_eq!(
+            validate_or_create_output_path(&nested.to_string_lossy()).unwrap(),
+            nested
+        );
+
+        // A traversal is rejected before anything is created
+        let escaping = dir.path().join("new").join("..").join("escaped");
+        assert!(matches!(
+            validate_or_create_output_path(&escaping.to_string_lossy()),
+            Err(AppError::File(FileError::PathTraversal(_)))
+        ));
+        assert!(!dir.path().join("new").exists());
+    }
+
+    #[test]
+    fn writable_directory_check_leaves_no_probe_behind() {
+        let dir = tempfile::tempdir().unwrap();
+
+        validate_writable(dir.path()).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_is_not_writable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Root ignores permission bits, so there is nothing to check
+        let writable = std::fs::write(locked.join("probe"), b"").is_ok();
+        let result = validate_writable(&locked);
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        if writable {
+            return;
+        }
+
+        assert!(matches!(
+            result,
+            Err(AppError::File(FileError::PermissionDenied(_)))
+        ));
+        assert_eq!(std::fs::read_dir(&locked).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,39 @@
+//! Playlist file parsing (M3U, extended M3U and PLS)
+
+/// Playlist extensions that can be imported
+pub const PLAYLIST_EXTENSIONS: &[&str] = &["m3u", "m3u8", "pls"];
+
+/// Returns the entries of an M3U/M3U8 playlist in order
+///
+/// Comments and extended M3U directives (`#EXTM3U`, `#EXTINF:...`) are skipped,
+/// so plain path lists and extended playlists parse the same way.
+pub fn parse_m3u(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Returns the `FileN=` entries of a PLS playlist, ordered by N
+///
+/// `TitleN`, `LengthN` and the `[playlist]` header are ignored.
+pub fn parse_pls(content: &str) -> Vec<String> {
+    let mut entries: Vec<(usize, String)> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim_start_matches('\u{feff}').split_once('=')?;
+            let key = key.trim();
+            let number = key
+                .get(..4)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("file"))
+                .and_then(|_| key[4..].parse::<usize>().ok())?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| (number, value.to_string()))
+        })
+        .collect();
+
+    entries.sort_by_key(|(number, _)| *number);
+    entries.into_iter().map(|(_, value)| value).collect()
+}
@@ -0,0 +1,178 @@
+//! Retrying fallible async operations with exponential backoff
+
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// Fraction of each backoff delay added or removed at random
+const BACKOFF_JITTER: f64 = 0.25;
+
+/// Runs `op` until it succeeds, returning the last error once `attempts` tries
+/// (at least one) are used up or `is_retryable` rejects an error
+///
+/// Waits `base_delay` after the first failure and twice as long after each
+/// further one, ±25% so callers failing at the same time don't retry in step.
+pub async fn with_retries<T, E, P, F, Fut>(
+    attempts: u32,
+    base_delay: Duration,
+    is_retryable: P,
+    mut op: F,
+) -> Result<T, E>
+where
+    E: Display,
+    P: Fn(&E) -> bool,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let attempts = attempts.max(1);
+    let mut jitter = Jitter::from_clock();
+    let mut attempt = 1;
+
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts && is_retryable(&e) => {
+                let backoff = base_delay.saturating_mul(2u32.saturating_pow(attempt - 1));
+                let delay = jitter.apply(backoff, BACKOFF_JITTER);
+                tracing::warn!(
+                    "⚠️ Attempt {}/{} failed, retrying in {:?}: {}",
+                    attempt,
+                    attempts,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Small seeded random generator (SplitMix64) for spreading out delays
+///
+/// A fixed seed gives a reproducible sequence.
+pub struct Jitter {
+    state: u64,
+}
+
+impl Jitter {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seeds from the system clock
+    pub fn from_clock() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(nanos)
+    }
+
+    /// Next value in `[0, 1)`
+    pub fn next_unit(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// `delay` scaled by a random factor in `[1 - fraction, 1 + fraction]`
+    ///
+    /// `fraction` must be between 0 and 1.
+    pub fn apply(&mut self, delay: Duration, fraction: f64) -> Duration {
+        delay.mul_f64(1.0 + fraction * (2.0 * self.next_unit() - 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const DELAY: Duration = Duration::from_millis(1);
+
+    #[tokio::test]
+    async fn succeeds_after_transient_failures() {
+        let calls = Cell::new(0);
+        let result: Result<u32, String> = with_retries(
+            5,
+            DELAY,
+            |_| true,
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move {
+                    if call < 3 {
+                        Err(format!("failure {}", call))
+                    } else {
+                        Ok(call)
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_error_when_attempts_run_out() {
+        let calls = Cell::new(0);
+        let result: Result<(), String> = with_retries(
+            3,
+            DELAY,
+            |_| true,
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move { Err(format!("failure {}", call)) }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Err("failure 3".to_string()));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_at_a_non_retryable_error() {
+        let calls = Cell::new(0);
+        let result: Result<(), String> = with_retries(
+            5,
+            DELAY,
+            |e: &String| e != "fatal",
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move { Err(if call == 2 { "fatal" } else { "transient" }.to_string()) }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Err("fatal".to_string()));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn zero_attempts_still_runs_once() {
+        let calls = Cell::new(0);
+        let result: Result<(), String> = with_retries(
+            0,
+            DELAY,
+            |_| true,
+            || {
+                calls.set(calls.get() + 1);
+                async { Err("failure".to_string()) }
+            },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,310 @@
+//! Text normalization helpers for search and matching
+
+/// Lowercases text and strips diacritics so "Björk" and "bjork" compare equal
+pub fn normalize_for_search(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_diacritic)
+        .collect()
+}
+
+/// Normalizes text for comparing names across sources
+///
+/// Like [`normalize_for_search`], and punctuation is treated as whitespace so
+/// "AC/DC" and "ac dc" or "Don't Stop" and "dont stop" compare equal.
+pub fn normalize_for_matching(text: &str) -> String {
+    normalize_for_search(text)
+        .chars()
+        .filter(|c| *c != '\'' && *c != '’')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Trims text and collapses runs of whitespace into single spaces
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Words [`title_case`] keeps lowercase unless they start the text
+const TITLE_CASE_MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "of", "on", "or", "the", "to", "vs",
+];
+
+/// Title-cases text written entirely in lower or upper case
+///
+/// Mixed-case text is returned unchanged since its casing is usually deliberate
+/// ("McCartney", "iPhone"). Words with digits, `/` or `.`, like "AC/DC", "2PAC"
+/// or "R.E.M.", are kept as written.
+pub fn title_case(text: &str) -> String {
+    let has_lower = text.chars().any(char::is_lowercase);
+    let has_upper = text.chars().any(char::is_uppercase);
+    if has_lower && has_upper {
+        return text.to_string();
+    }
+
+    text.split(' ')
+        .enumerate()
+        .map(|(index, word)| {
+            if word
+                .chars()
+                .any(|c| c.is_ascii_digit() || c == '/' || c == '.')
+            {
+                return word.to_string();
+            }
+            let lower = word.to_lowercase();
+            if index > 0 && TITLE_CASE_MINOR_WORDS.contains(&lower.as_str()) {
+                return lower;
+            }
+            // Capitalize the first letter, after any opening bracket or quote
+            let Some((start, first)) = lower.char_indices().find(|(_, c)| c.is_alphabetic()) else {
+                return lower;
+            };
+            let rest = &lower[start + first.len_utf8()..];
+            format!("{}{}{}", &lower[..start], first.to_uppercase(), rest)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Moves a leading "The" to the end, so "The Beatles" becomes "Beatles, The"
+///
+/// Text already in that form, or that is only "The", is returned unchanged.
+pub fn move_leading_article(text: &str) -> String {
+    match text.split_once(' ') {
+        Some((article, rest)) if article.eq_ignore_ascii_case("the") && !rest.trim().is_empty() => {
+            format!("{}, {}", rest.trim(), article)
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Strips a trailing remaster note from a title or album name
+///
+/// Handles bracketed notes ("Help! (Remastered 2009)", "Abbey Road [2019 Remaster]")
+/// and dash-separated ones ("Let It Be - Remastered"). Other notes are kept.
+pub fn strip_remaster_suffix(text: &str) -> &str {
+    let trimmed = text.trim_end();
+
+    if let Some(open) = match trimmed.chars().last() {
+        Some(')') => Some('('),
+        Some(']') => Some('['),
+        _ => None,
+    } {
+        if let Some(start) = trimmed.rfind(open) {
+            // Both brackets are one byte long
+            if is_remaster_note(&trimmed[start + 1..trimmed.len() - 1]) {
+                return trimmed[..start].trim_end();
+            }
+        }
+    }
+
+    if let Some(start) = trimmed.rfind(" - ") {
+        if is_remaster_note(&trimmed[start + 3..]) {
+            return trimmed[..start].trim_end();
+        }
+    }
+
+    trimmed
+}
+
+/// Whether a note only says the track was remastered, e.g. "2011 Remastered Version"
+fn is_remaster_note(note: &str) -> bool {
+    let mut remaster = false;
+    for word in note.split_whitespace() {
+        let word = word.to_lowercase();
+        match word.as_str() {
+            "remaster" | "remastered" => remaster = true,
+            "version" | "edition" | "digital" => {}
+            _ if word.chars().all(|c| c.is_ascii_digit()) => {}
+            _ => return false,
+        }
+    }
+    remaster
+}
+
+/// Maps a lowercase accented Latin character to its unaccented base letter
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => c,
+    }
+}
+
+/// Parses a ReplayGain tag value such as "-6.54 dB" or "0.988553"
+///
+/// The unit suffix is optional and case-insensitive. Returns `None` for
+/// empty, non-numeric or non-finite values.
+pub fn parse_replaygain_value(value: &str) -> Option<f32> {
+    let trimmed = value.trim();
+    let number = trimmed
+        .len()
+        .checked_sub(2)
+        .filter(|&split| trimmed.is_char_boundary(split))
+        .filter(|&split| trimmed[split..].eq_ignore_ascii_case("db"))
+        .map_or(trimmed, |split| trimmed[..split].trim_end());
+
+    number.parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+/// Separators between credited artists inside a single tag value (matched case-insensitively)
+///
+/// A slash only counts when surrounded by spaces so names like "AC/DC" stay whole.
+const ARTIST_SEPARATORS: &[&str] = &[
+    " (feat. ",
+    " [feat. ",
+    " (ft. ",
+    " feat. ",
+    " feat ",
+    " ft. ",
+    " featuring ",
+    " / ",
+];
+
+/// Splits artist tag values into individual artists
+///
+/// Each value may hold several artists separated by NUL (ID3v2.4 multi-value
+/// frames), `;`, ` / ` or a featuring credit ("A feat. B"). Duplicates are
+/// dropped case-insensitively and the order of appearance is kept.
+pub fn split_artists<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut artists: Vec<String> = Vec::new();
+    for value in values {
+        for part in value.split(['\0', ';']) {
+            for name in split_on_artist_separators(part) {
+                let name = name.trim_matches(|c: char| {
+                    c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']')
+                });
+                if !name.is_empty() && !artists.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+                    artists.push(name.to_string());
+                }
+            }
+        }
+    }
+    artists
+}
+
+/// Splits `text` at every [`ARTIST_SEPARATORS`] occurrence
+fn split_on_artist_separators(text: &str) -> Vec<&str> {
+    // ASCII lowercasing keeps byte offsets, so indices map back onto `text`
+    let lower = text.to_ascii_lowercase();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some((index, len)) = ARTIST_SEPARATORS
+        .iter()
+        .filter_map(|sep| lower[start..].find(sep).map(|i| (start + i, sep.len())))
+        .min()
+    {
+        parts.push(&text[start..index]);
+        start = index + len;
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapse_whitespace_trims_and_joins_runs() {
+        assert_eq!(collapse_whitespace("  Hey \t Jude\n "), "Hey Jude");
+        assert_eq!(collapse_whitespace("   "), "");
+    }
+
+    #[test]
+    fn title_case_fixes_single_case_text_only() {
+        assert_eq!(
+            title_case("the dark side of the moon"),
+            "The Dark Side of the Moon"
+        );
+        assert_eq!(title_case("LET IT BE"), "Let It Be");
+        assert_eq!(
+            title_case("(what's the story) morning glory"),
+            "(What's the Story) Morning Glory"
+        );
+        // Deliberate casing and special words are left alone
+        assert_eq!(title_case("McCartney II"), "McCartney II");
+        assert_eq!(
+            title_case("AC/DC LIVE AT R.E.M. 1999"),
+            "AC/DC Live at R.E.M. 1999"
+        );
+    }
+
+    #[test]
+    fn move_leading_article_only_moves_a_leading_the() {
+        assert_eq!(move_leading_article("The Beatles"), "Beatles, The");
+        assert_eq!(move_leading_article("the  Who"), "Who, the");
+        assert_eq!(move_leading_article("Beatles, The"), "Beatles, The");
+        assert_eq!(move_leading_article("The"), "The");
+        assert_eq!(
+            move_leading_article("Theatre of Tragedy"),
+            "Theatre of Tragedy"
+        );
+    }
+
+    #[test]
+    fn strip_remaster_suffix_drops_remaster_notes_only() {
+        assert_eq!(strip_remaster_suffix("Help! (Remastered 2009)"), "Help!");
+        assert_eq!(
+            strip_remaster_suffix("Abbey Road [2019 Remaster]"),
+            "Abbey Road"
+        );
+        assert_eq!(strip_remaster_suffix("Let It Be - Remastered"), "Let It Be");
+        assert_eq!(
+            strip_remaster_suffix("Heroes - 2017 Remastered Version "),
+            "Heroes"
+        );
+        assert_eq!(strip_remaster_suffix("Hey Jude (Live)"), "Hey Jude (Live)");
+        assert_eq!(
+            strip_remaster_suffix("Yesterday - Take 1"),
+            "Yesterday - Take 1"
+        );
+        assert_eq!(strip_remaster_suffix("(Remastered)"), "");
+    }
+
+    #[test]
+    fn split_artists_handles_every_separator_once() {
+        assert_eq!(
+            split_artists(["Daft Punk feat. Pharrell Williams"]),
+            ["Daft Punk", "Pharrell Williams"]
+        );
+        assert_eq!(
+            split_artists(["Artist (Feat. Guest)", "Second; Third\0Fourth / Fifth"]),
+            ["Artist", "Guest", "Second", "Third", "Fourth", "Fifth"]
+        );
+        // Slashes inside names and repeated credits stay whole and single
+        assert_eq!(split_artists(["AC/DC", "ac/dc ft. AC/DC"]), ["AC/DC"]);
+        assert!(split_artists([" ; ", ""]).is_empty());
+        assert!(split_artists(None).is_empty());
+    }
+
+    #[test]
+    fn parse_replaygain_value_accepts_optional_units() {
+        assert_eq!(parse_replaygain_value("-6.54 dB"), Some(-6.54));
+        assert_eq!(parse_replaygain_value(" +2.10DB "), Some(2.1));
+        assert_eq!(parse_replaygain_value("0.988553"), Some(0.988553));
+        for invalid in ["", "dB", "loud", "NaN", "inf dB", "é"] {
+            assert_eq!(parse_replaygain_value(invalid), None, "{}", invalid);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! Validation utilities for user input and external data
 
-use crate::domain::music::AUDIO_EXTENSIONS;
-use crate::errors::{AppError, DownloadError, FileError};
+use crate::domain::music::{AUDIO_EXTENSIONS, DOWNLOAD_FORMATS};
+use crate::errors::{AppError, DownloadError, FileError, SpotifyError};
 
 /// Validates that a URL is a proper Spotify track URL
 pub fn validate_spotify_url(url: &str) -> Result<(), AppError> {
@@ -11,6 +11,23 @@ pub fn validate_spotify_url(url: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Builds the canonical `open.spotify.com/track/<id>` URL spotdl expects
+///
+/// Accepts the same inputs as [`extract_spotify_id`], including the
+/// `spotify:track:<id>` URIs stored in `SpotifyTrack::id`.
+pub fn spotify_track_url(id: &str) -> Result<String, AppError> {
+    let id = extract_spotify_id(id, "track")?;
+    Ok(format!("https://open.spotify.com/track/{}", id))
+}
+
+/// Builds the canonical `open.spotify.com/album/<id>` URL spotdl expands into tracks
+///
+/// Accepts the same inputs as [`extract_spotify_id`].
+pub fn spotify_album_url(id: &str) -> Result<String, AppError> {
+    let id = extract_spotify_id(id, "album")?;
+    Ok(format!("https://open.spotify.com/album/{}", id))
+}
+
 /// Validates an audio file extension
 ///
 /// Currently unused but available for future validation needs
@@ -24,24 +41,287 @@ pub fn validate_audio_extension(ext: &str) -> Result<(), AppError> {
 
 /// Validates download format
 pub fn validate_download_format(format: &str) -> Result<(), AppError> {
-    let valid_formats = ["mp3", "flac", "ogg", "m4a", "opus"];
-    if !valid_formats.contains(&format) {
+    if !DOWNLOAD_FORMATS.contains(&format) {
         return Err(DownloadError::InvalidFormat(format!(
             "Use one of: {}",
-            valid_formats.join(", ")
+            DOWNLOAD_FORMATS.join(", ")
         ))
         .into());
     }
     Ok(())
 }
 
+/// Validates a playlist name used as a file name
+///
+/// Rejects empty names, path separators, `..` and control characters.
+pub fn validate_playlist_name(name: &str) -> Result<String, AppError> {
+    const MAX_NAME_LEN: usize = 200;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Playlist name cannot be empty".into()));
+    }
+    if name.contains("..") {
+        return Err(FileError::PathTraversal(name.to_string()).into());
+    }
+    if name.len() > MAX_NAME_LEN
+        || name.chars().any(|c| {
+            c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+        })
+    {
+        return Err(FileError::InvalidPath(name.to_string()).into());
+    }
+    Ok(name.to_string())
+}
+
+/// Validates the number of songs in a batch download against `max_songs`
+pub fn validate_batch_size(count: usize, max_songs: usize) -> Result<(), AppError> {
+    if count > max_songs {
+        return Err(DownloadError::TooManySongs(max_songs).into());
+    }
+    Ok(())
+}
+
+/// spotdl placeholders accepted in output templates
+///
+/// - `{title}`, `{artist}` (first artist), `{artists}` (all artists), `{album}`,
+///   `{album-artist}`, `{genre}`, `{year}`, `{original-date}`
+/// - `{track-number}`, `{tracks-count}`, `{disc-number}`, `{disc-count}`, `{duration}`
+/// - `{isrc}`, `{track-id}`, `{publisher}`
+/// - `{list-name}`, `{list-position}`, `{list-length}` (playlist downloads)
+/// - `{output-ext}`: extension of the chosen format
+pub const OUTPUT_TEMPLATE_VARIABLES: &[&str] = &[
+    "title",
+    "artist",
+    "artists",
+    "album",
+    "album-artist",
+    "genre",
+    "year",
+    "original-date",
+    "track-number",
+    "tracks-count",
+    "disc-number",
+    "disc-count",
+    "duration",
+    "isrc",
+    "track-id",
+    "publisher",
+    "list-name",
+    "list-position",
+    "list-length",
+    "output-ext",
+];
+
+/// Validates a spotdl output template and returns it trimmed
+///
+/// The template is joined under the output directory, so it must stay relative:
+/// absolute paths, drive prefixes and `..` segments are rejected. Placeholders
+/// must be one of [`OUTPUT_TEMPLATE_VARIABLES`]; literal text may use letters,
+/// digits, spaces, `/` for subfolders and `- _ . , ( ) [ ] ' & !`. An empty
+/// template is allowed and means spotdl's default naming.
+pub fn sanitize_output_template(template: &str) -> Result<String, AppError> {
+    let template = template.trim();
+    let invalid =
+        |reason: &str| DownloadError::InvalidTemplate(format!("{} ({})", template, reason));
 
+    if template.starts_with('/') || template.starts_with('\\') || template.contains(':') {
+        return Err(invalid("must be a relative path").into());
+    }
+
+    if template
+        .split(['/', '\\'])
+        .any(|segment| segment.trim() == "..")
+    {
+        return Err(invalid("'..' is not allowed").into());
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        check_template_literal(&rest[..start])
+            .map_err(|c| invalid(&format!("character '{}'", c)))?;
+
+        if rest[start..].starts_with('}') {
+            return Err(invalid("unmatched '}'").into());
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| invalid("unclosed '{'"))?;
+        let variable = &rest[start + 1..start + end];
+        if !OUTPUT_TEMPLATE_VARIABLES.contains(&variable) {
+            return Err(invalid(&format!("unknown variable {{{}}}", variable)).into());
+        }
+        rest = &rest[start + end + 1..];
+    }
+    check_template_literal(rest).map_err(|c| invalid(&format!("character '{}'", c)))?;
+
+    Ok(template.to_string())
+}
+
+/// Returns the first character not allowed in template literal text
+fn check_template_literal(literal: &str) -> Result<(), char> {
+    const ALLOWED_PUNCTUATION: &[char] = &[
+        ' ', '/', '-', '_', '.', ',', '(', ')', '[', ']', '\'', '&', '!',
+    ];
+
+    match literal
+        .chars()
+        .find(|c| !c.is_alphanumeric() && !ALLOWED_PUNCTUATION.contains(c))
+    {
+        Some(c) => Err(c),
+        None => Ok(()),
+    }
+}
 
 /// Extracts the song ID from a Spotify URL
 pub fn extract_song_id(url: &str) -> String {
     url.split('/')
-        .last()
+        .next_back()
         .and_then(|s| s.split('?').next())
         .unwrap_or("unknown")
         .to_string()
 }
+
+/// Extracts a Spotify id of the given kind (`track`, `artist`, ...) from user input
+///
+/// Accepts a raw id, an `https://open.spotify.com/<kind>/<id>` URL (query string and
+/// `intl-xx` locale segment allowed) or a `spotify:<kind>:<id>` URI.
+pub fn extract_spotify_id(input: &str, kind: &str) -> Result<String, AppError> {
+    let input = input.trim();
+    let invalid = || SpotifyError::InvalidId(format!("{} ({})", input, kind));
+
+    let id = if let Some(rest) = input.strip_prefix("spotify:") {
+        let (uri_kind, id) = rest.split_once(':').ok_or_else(invalid)?;
+        if uri_kind != kind {
+            return Err(invalid().into());
+        }
+        id
+    } else if let Some(idx) = input.find("open.spotify.com/") {
+        let path = &input[idx + "open.spotify.com/".len()..];
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let mut segments = path
+            .split('/')
+            .filter(|s| !s.is_empty() && !s.starts_with("intl-"));
+        if segments.next() != Some(kind) {
+            return Err(invalid().into());
+        }
+        segments.next().ok_or_else(invalid)?
+    } else {
+        input
+    };
+
+    // Spotify ids are 22 base62 characters
+    if id.len() != 22 || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(invalid().into());
+    }
+
+    Ok(id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_known_template_variables() {
+        for template in [
+            "",
+            "{artist} - {title}.{output-ext}",
+            "{album-artist}/{album}/{track-number} - {title}.{output-ext}",
+            "  {title} (Live) [2020] & more!  ",
+        ] {
+            assert!(
+                sanitize_output_template(template).is_ok(),
+                "{} should be accepted",
+                template
+            );
+        }
+        assert_eq!(
+            sanitize_output_template("  {title}.{output-ext} ").unwrap(),
+            "{title}.{output-ext}"
+        );
+    }
+
+    #[test]
+    fn rejects_template_traversal_and_absolute_paths() {
+        for template in [
+            "../{title}",
+            "{artist}/../../{title}",
+            "{artist}/ .. /{title}",
+            "/tmp/{title}",
+            "\\\\server\\share\\{title}",
+            "C:\\Music\\{title}",
+            "C:{title}",
+        ] {
+            assert!(
+                sanitize_output_template(template).is_err(),
+                "{} should be rejected",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_variables_and_unsafe_characters() {
+        for template in [
+            "{unknown}",
+            "{artist} - {Title}",
+            "{title",
+            "title}",
+            "{{title}}",
+            "{artist}\\{title}",
+            "{title};rm -rf",
+            "{title}$(whoami)",
+            "{title}\n{artist}",
+        ] {
+            assert!(
+                sanitize_output_template(template).is_err(),
+                "{:?} should be rejected",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn extracts_spotify_ids_from_every_form() {
+        let id = "4uLU6hMCjMI75M1A2tKUQC";
+        for input in [
+            id.to_string(),
+            format!("  {}  ", id),
+            format!("https://open.spotify.com/track/{}", id),
+            format!("https://open.spotify.com/intl-es/track/{}?si=abc123", id),
+            format!("open.spotify.com/track/{}#top", id),
+            format!("spotify:track:{}", id),
+        ] {
+            assert_eq!(
+                extract_spotify_id(&input, "track").unwrap(),
+                id,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_spotify_ids_of_another_kind_or_shape() {
+        let id = "4uLU6hMCjMI75M1A2tKUQC";
+        for input in [
+            format!("https://open.spotify.com/album/{}", id),
+            format!("spotify:artist:{}", id),
+            "https://open.spotify.com/track/".to_string(),
+            "spotify:track".to_string(),
+            "4uLU6hMCjMI75M1A2tKUQ".to_string(),
+            "4uLU6hMCjMI75M1A2tKU-C".to_string(),
+            String::new(),
+        ] {
+            assert!(
+                matches!(
+                    extract_spotify_id(&input, "track"),
+                    Err(AppError::Spotify(SpotifyError::InvalidId(_)))
+                ),
+                "{} should be rejected",
+                input
+            );
+        }
+    }
+}
@@ -7,7 +7,7 @@ import { useSpotifyAuth } from './useSpotifyAuth.svelte';
 
 /**
  * Estructura de progreso de descarga que coincide con Rust
- * Rust emite: { song: String, index: usize, total: usize, status: String, url: String }
+ * Rust emite: { song: String, index: usize, total: usize, status: String, url: String, output_path: Option<String> }
  */
 export interface DownloadProgress {
   song: string;
@@ -15,6 +15,20 @@ export interface DownloadProgress {
   total: number;
   status: string;
   url: string;
+  output_path?: string | null;
+  /** Segundos estimados para terminar la descarga (null hasta que termina el primer lote) */
+  eta_secs?: number | null;
+}
+
+/**
+ * Evento 'download-started', emitido antes de que spotdl empiece cada canción
+ * Rust emite: { song: String, index: usize, total: usize, url: String }
+ */
+export interface DownloadStarted {
+  song: string;
+  index: number;
+  total: number;
+  url: string;
 }
 
 /**
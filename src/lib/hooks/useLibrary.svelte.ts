@@ -32,15 +32,23 @@ const { getDefaultMusicFolder, scanMusicFolder, getAudioMetadata } = TauriComman
 // ═══════════════════════════════════════════════════════════════════════════
 
 interface ScanProgressEvent {
+  scanId: number;
   current: number;
   total: number;
   path: string;
+  root: string;
 }
 
 interface ScanCompleteEvent {
+  scanId: number;
   total: number;
 }
 
+interface ScanCancelledEvent {
+  scanId: number;
+  processed: number;
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // PERSISTENCIA HELPER
 // ═══════════════════════════════════════════════════════════════════════════
@@ -121,6 +129,7 @@ export function useLibrary(): UseLibraryReturn {
   let unlistenScanStart: (() => void) | null = null;
   let unlistenScanProgress: (() => void) | null = null;
   let unlistenScanComplete: (() => void) | null = null;
+  let unlistenScanCancelled: (() => void) | null = null;
 
   // Estados derivados del store
   const isEnriching = $derived(EnrichmentService.isEnriching());
@@ -134,7 +143,7 @@ export function useLibrary(): UseLibraryReturn {
     console.log('🎧 Configurando listeners de escaneo...');
 
     // Evento: inicio de escaneo
-    unlistenScanStart = await listen<{ path: string }>('library-scan-start', (event) => {
+    unlistenScanStart = await listen<{ scanId: number; path: string }>('library-scan-start', (event) => {
       console.log('🔍 Escaneo iniciado:', event.payload.path);
       libraryStore.setLoading(true);
       libraryStore.resetScanProgress();
@@ -153,6 +162,13 @@ export function useLibrary(): UseLibraryReturn {
       libraryStore.resetScanProgress();
     });
 
+    // Evento: escaneo cancelado
+    unlistenScanCancelled = await listen<ScanCancelledEvent>('library-scan-cancelled', (event) => {
+      console.log('⏹️ Escaneo cancelado tras', event.payload.processed, 'archivos');
+      libraryStore.setLoading(false);
+      libraryStore.resetScanProgress();
+    });
+
     console.log('✅ Listeners de escaneo configurados');
   }
 
@@ -434,11 +450,13 @@ export function useLibrary(): UseLibraryReturn {
     unlistenScanStart?.();
     unlistenScanProgress?.();
     unlistenScanComplete?.();
+    unlistenScanCancelled?.();
     
     // Nullificar referencias para ayudar al GC
     unlistenScanStart = null;
     unlistenScanProgress = null;
     unlistenScanComplete = null;
+    unlistenScanCancelled = null;
     
     console.log('✅ useLibrary limpiado');
   }
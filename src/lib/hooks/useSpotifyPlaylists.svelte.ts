@@ -70,7 +70,7 @@ export function useSpotifyPlaylists(): UseSpotifyPlaylistsReturn {
 
     try {
       console.log(`📋 Cargando playlists (límite: ${requestedLimit})...`);
-      const data = await getPlaylists(requestedLimit);
+      const { playlists: data } = await getPlaylists(requestedLimit);
 
       playlistStore.setPlaylists(data);
       console.log(`✅ ${data.length} playlists cargadas`);
@@ -2,12 +2,21 @@ export interface MusicFile {
   path: string;
   title: string | null;
   artist: string | null;
+  artists?: string[];
   album: string | null;
   duration: number | null;
   year: number | null;
   genre: string | null;
+  trackNumber?: number | null;
   albumArt?: string | null;
+  replaygainTrackGain?: number | null;
+  replaygainTrackPeak?: number | null;
   lastFmData?: any;
 }
 
 export type Track = MusicFile;
+
+export interface ImportedPlaylist {
+  tracks: MusicFile[];
+  missing: string[];
+}
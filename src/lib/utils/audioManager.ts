@@ -11,6 +11,10 @@
  */
 
 import { convertFileSrc } from '@tauri-apps/api/core';
+import { TauriCommands } from '@/lib/utils/tauriCommands';
+
+// Prefijo de las rutas no Unicode (ver MusicFile.path en el backend)
+const NATIVE_PATH_PREFIX = 'native-path:';
 
 // 🎯 Servicio de logging para producción
 class DebugLogger {
@@ -78,6 +82,7 @@ class AudioManager {
   private audio: HTMLAudioElement | null = null;
   private eventListeners: Map<string, EventListener> = new Map();
   private callbacks: AudioCallbacks | null = null;
+  private objectUrl: string | null = null;
 
   // Web Audio API infrastructure (for waveform visualization)
   private audioContext: AudioContext | null = null;
@@ -332,11 +337,7 @@ class AudioManager {
             throw new Error('URL de streaming inválida');
           }
         } else {
-          // Strip Windows extended path prefix and normalize to forward slashes
-          let cleanPath = filePathOrUrl.replace(/^\\\\\?\\(.+)$/, '$1');
-          cleanPath = cleanPath.replace(/\\/g, '/');
-
-          audioUrl = convertFileSrc(cleanPath);
+          audioUrl = await this.localSrc(filePathOrUrl);
         }
 
         this.audio.src = audioUrl;
@@ -395,7 +396,7 @@ class AudioManager {
 
     const src = filePathOrUrl.startsWith('http')
       ? filePathOrUrl
-      : convertFileSrc(filePathOrUrl.replace(/^\\\\\?\\(.+)$/, '$1').replace(/\\/g, '/'));
+      : await this.localSrc(filePathOrUrl);
 
     if (this.audio.src !== src) {
       this.audio.src = src;
@@ -403,6 +404,31 @@ class AudioManager {
     }
   }
 
+  /**
+   * Convierte una ruta local en una URL reproducible
+   * Las rutas 'native-path:' no pasan por el protocolo asset: se leen como bytes
+   * y se sirven desde un blob URL (se revoca el anterior)
+   */
+  private async localSrc(filePath: string): Promise<string> {
+    if (filePath.startsWith(NATIVE_PATH_PREFIX)) {
+      const bytes = await TauriCommands.readAudioFile(filePath);
+      this.revokeObjectUrl();
+      this.objectUrl = URL.createObjectURL(new Blob([bytes]));
+      return this.objectUrl;
+    }
+
+    // Strip Windows extended path prefix and normalize to forward slashes
+    const cleanPath = filePath.replace(/^\\\\\?\\(.+)$/, '$1').replace(/\\/g, '/');
+    return convertFileSrc(cleanPath);
+  }
+
+  private revokeObjectUrl(): void {
+    if (this.objectUrl) {
+      URL.revokeObjectURL(this.objectUrl);
+      this.objectUrl = null;
+    }
+  }
+
   /**
    * ✅ NUEVO: Categoriza errores para mejor UX
    */
@@ -564,6 +590,7 @@ class AudioManager {
       this.audio.pause();
       this.audio.src = '';
       this.audio = null;
+      this.revokeObjectUrl();
       this.callbacks = null;
 
       debugLogger.log('🧹 AudioManager limpiado');
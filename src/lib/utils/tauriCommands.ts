@@ -16,6 +16,7 @@ export interface SpotifyUser {
   id: string;
   displayName: string | null;
   email: string | null;
+  /** Código de país ISO 3166-1 alfa-2 (p. ej. 'ES') */
   country: string | null;
   product: string | null;
   followers: number;
@@ -34,6 +35,27 @@ export interface SpotifyTrack {
   externalUrl: string | null;
 }
 
+export interface SpotifyEpisode {
+  id: string;
+  name: string;
+  show: string;
+  publisher: string;
+  description: string;
+  image: string | null;
+  durationMs: number;
+  resumePositionMs: number | null;
+  fullyPlayed: boolean;
+  releaseDate: string;
+  externalUrl: string | null;
+}
+
+export interface SpotifyTrackPreview {
+  url: string;
+  id: string;
+  /** null si no hay sesión de Spotify (solo se validó el formato de la URL) */
+  track: SpotifyTrack | null;
+}
+
 export interface SpotifyPlaylist {
   id: string;
   name: string;
@@ -44,6 +66,12 @@ export interface SpotifyPlaylist {
   public: boolean | null;
 }
 
+export interface SpotifyPlaylistPage {
+  playlists: SpotifyPlaylist[];
+  offset: number;
+  total: number;
+}
+
 export interface SpotifyArtist {
   id: string;
   name: string;
@@ -56,6 +84,96 @@ export interface SpotifyArtist {
 
 export type TimeRange = 'short_term' | 'medium_term' | 'long_term';
 
+export interface Diagnostics {
+  appVersion: string;
+  os: string;
+  arch: string;
+  spotdlVersion: string | null;
+  spotdlMeetsMinimum: boolean | null;
+  ytDlpVersion: string | null;
+  ffmpegVersion: string | null;
+  spotifyAuthenticated: boolean;
+  lastfmConfigured: boolean;
+  defaultMusicFolder: string | null;
+}
+
+export interface AudioOutput {
+  name: string;
+  minSampleRate: number | null;
+  maxSampleRate: number | null;
+  isDefault: boolean;
+}
+
+export interface Lyrics {
+  text: string;
+  source: 'lrc' | 'embedded';
+}
+
+export interface RecentFolder {
+  path: string;
+  /** Timestamp Unix (segundos) del último escaneo */
+  lastScanned: number;
+  fileCount: number;
+}
+
+export interface LibraryMatch {
+  matched: { spotify: SpotifyTrack; local: MusicFile }[];
+  unmatched: SpotifyTrack[];
+}
+
+/** Tamaño de imagen de Last.fm; por defecto se usa la más grande disponible */
+export type LastFmImageSize = 'small' | 'medium' | 'large' | 'extralarge' | 'mega';
+
+export interface LastFmCacheStats {
+  trackEntries: number;
+  artistEntries: number;
+  albumEntries: number;
+  artistTopEntries: number;
+  hits: number;
+  misses: number;
+}
+
+/** Archivo descargado que no pasó la verificación */
+export interface DownloadProblem {
+  path: string;
+  kind: 'missing' | 'empty' | 'not_audio' | 'corrupt';
+  message: string;
+}
+
+/** Reglas de normalize_tags; las omitidas quedan desactivadas */
+export interface TagNormalizeOptions {
+  trimWhitespace?: boolean;
+  titleCase?: boolean;
+  /** "The Beatles" → "Beatles, The" (artista y artista del álbum) */
+  moveLeadingArticle?: boolean;
+  /** Quita notas como " (Remastered 2009)" del título y el álbum */
+  stripRemasterSuffix?: boolean;
+  /** Solo informa de los cambios, sin escribirlos */
+  dryRun?: boolean;
+}
+
+export interface TagNormalization {
+  path: string;
+  changes: {
+    field: 'title' | 'artist' | 'album' | 'albumArtist' | 'genre';
+    before: string;
+    after: string;
+  }[];
+}
+
+/** Resultado por archivo: `Ok` con los cambios o `Err` con el mensaje de error */
+export type TagNormalizationResult = { Ok: TagNormalization } | { Err: string };
+
+export interface Settings {
+  defaultFormat: string;
+  outputDir: string | null;
+  maxConcurrentDownloads: number;
+  /** Lotes fallidos seguidos tras los que se cancela la descarga (evento 'download-aborted') */
+  maxConsecutiveFailures: number;
+  cookieFile: string | null;
+  followSymlinks: boolean;
+}
+
 // ============================================================================
 // WRAPPER DE COMANDOS TAURI
 // ============================================================================
@@ -65,22 +183,59 @@ export const TauriCommands = {
   // 🎵 COMANDOS LAST.FM
   // ========================================================================
 
-  async getLastFmTrackInfo(artist: string, track: string): Promise<ProcessedTrackInfo> {
-    return await invoke('lastfm_get_track_info', { artist, track });
+  async getLastFmTrackInfo(artist: string, track: string, imageSize?: LastFmImageSize): Promise<ProcessedTrackInfo> {
+    return await invoke('lastfm_get_track_info', { artist, track, imageSize });
   },
 
-  async getLastFmArtistInfo(artist: string): Promise<ProcessedArtistInfo> {
-    return await invoke('lastfm_get_artist_info', { artist });
+  async getLastFmArtistInfo(artist: string, imageSize?: LastFmImageSize): Promise<ProcessedArtistInfo> {
+    return await invoke('lastfm_get_artist_info', { artist, imageSize });
   },
 
-  async getLastFmAlbumInfo(artist: string, album: string): Promise<ProcessedAlbumInfo> {
-    return await invoke('lastfm_get_album_info', { artist, album });
+  async getLastFmAlbumInfo(artist: string, album: string, imageSize?: LastFmImageSize): Promise<ProcessedAlbumInfo> {
+    return await invoke('lastfm_get_album_info', { artist, album, imageSize });
   },
 
   async enrichTracksBatch(tracks: MusicFile[]): Promise<EnrichedTrack[]> {
     return await invoke('enrich_tracks_batch', { tracks });
   },
 
+  /**
+   * Activa o desactiva el modo sin conexión de Last.fm (solo datos en caché)
+   */
+  async setLastFmOffline(offline: boolean): Promise<void> {
+    return await invoke('lastfm_set_offline', { offline });
+  },
+
+  /**
+   * Activa o desactiva la autocorrección de nombres de Last.fm (activada por defecto)
+   * Desactivada, los nombres mal escritos no encuentran nada, pero un nombre
+   * intencional nunca se cambia por otro más popular
+   */
+  async setLastFmAutocorrect(autocorrect: boolean): Promise<void> {
+    return await invoke('lastfm_set_autocorrect', { autocorrect });
+  },
+
+  /**
+   * Obtiene el tamaño de la caché de Last.fm y sus aciertos/fallos
+   */
+  async getLastFmCacheStats(): Promise<LastFmCacheStats> {
+    return await invoke('lastfm_cache_stats');
+  },
+
+  /**
+   * Vacía la caché de Last.fm
+   */
+  async clearLastFmCache(): Promise<void> {
+    return await invoke('lastfm_clear_cache');
+  },
+
+  /**
+   * Enriquece tracks con una sola consulta por álbum (menos llamadas a Last.fm)
+   */
+  async enrichTracksByAlbum(tracks: MusicFile[]): Promise<EnrichedTrack[]> {
+    return await invoke('enrich_tracks_by_album', { tracks });
+  },
+
   // ========================================================================
   // 🎵 COMANDOS DE ARCHIVOS LOCALES
 
@@ -88,10 +243,12 @@ export const TauriCommands = {
 
   /**
    * Escanea una carpeta de música y retorna metadata de archivos de audio
+   * Las rutas que no son Unicode válido llegan como 'native-path:<base64>' (ver readAudioFile)
+   * @param maxFileBytes Omite archivos más grandes (reason 'tooLarge'); sin límite por defecto
    */
-  async scanMusicFolder(folderPath: string): Promise<MusicFile[]> {
+  async scanMusicFolder(folderPath: string, maxFileBytes?: number): Promise<MusicFile[]> {
     try {
-      return await invoke<MusicFile[]>('scan_music_folder', { folderPath });
+      return await invoke<MusicFile[]>('scan_music_folder', { folderPath, maxFileBytes });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);
       console.error('❌ Error escaneando carpeta:', errorMsg);
@@ -99,6 +256,63 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Lee un archivo de audio completo como bytes
+   * Para rutas 'native-path:', que el protocolo asset no puede servir
+   */
+  async readAudioFile(filePath: string): Promise<ArrayBuffer> {
+    try {
+      return await invoke<ArrayBuffer>('read_audio_file', { filePath });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error leyendo archivo de audio:', errorMsg);
+      throw new Error(`Error leyendo archivo de audio: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Obtiene las carpetas escaneadas recientemente (la más reciente primero, máximo 10)
+   */
+  async getRecentFolders(): Promise<RecentFolder[]> {
+    try {
+      return await invoke<RecentFolder[]>('get_recent_folders');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo carpetas recientes:', errorMsg);
+      throw new Error(`Error obteniendo carpetas recientes: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Borra la lista de carpetas escaneadas recientemente
+   */
+  async clearRecentFolders(): Promise<void> {
+    try {
+      await invoke('clear_recent_folders');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error borrando carpetas recientes:', errorMsg);
+      throw new Error(`Error borrando carpetas recientes: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Normaliza etiquetas inconsistentes (espacios, mayúsculas, "The", remasterizaciones)
+   * Emite 'tag-normalize-progress' por cada archivo; los resultados siguen el orden de paths
+   */
+  async normalizeTags(
+    paths: string[],
+    options: TagNormalizeOptions
+  ): Promise<TagNormalizationResult[]> {
+    try {
+      return await invoke<TagNormalizationResult[]>('normalize_tags', { paths, options });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error normalizando etiquetas:', errorMsg);
+      throw new Error(`Error normalizando etiquetas: ${errorMsg}`);
+    }
+  },
+
   /**
    * Obtiene metadata de un archivo de audio específico
    */
@@ -112,6 +326,98 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Obtiene la letra de una canción (archivo .lrc junto al audio o letra embebida)
+   * @returns null si la canción no tiene letra
+   */
+  async getLyrics(filePath: string): Promise<Lyrics | null> {
+    try {
+      return await invoke<Lyrics | null>('get_lyrics', { filePath });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo letra:', errorMsg);
+      throw new Error(`Error obteniendo letra: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Separa las canciones de Spotify entre las que ya están en la biblioteca local y las que faltan
+   */
+  async matchSpotifyToLibrary(
+    library: MusicFile[],
+    spotifyTracks: SpotifyTrack[]
+  ): Promise<LibraryMatch> {
+    try {
+      return await invoke<LibraryMatch>('match_spotify_to_library', { library, spotifyTracks });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error comparando con la biblioteca:', errorMsg);
+      throw new Error(`Error comparando con la biblioteca: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Abre el selector de carpetas y permite a los comandos de archivos acceder a la elegida
+   * (se recuerda entre sesiones)
+   * @returns Todas las carpetas permitidas, o null si se canceló el diálogo
+   */
+  async addAllowedRoot(): Promise<string[] | null> {
+    try {
+      return await invoke<string[] | null>('add_allowed_root');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error permitiendo carpeta:', errorMsg);
+      throw new Error(`No se pudo permitir la carpeta: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Reúne versiones de spotdl/yt-dlp/ffmpeg y la configuración de la app para reportes de errores
+   */
+  async getDiagnostics(): Promise<Diagnostics> {
+    return await invoke<Diagnostics>('get_diagnostics');
+  },
+
+  /**
+   * Lista los dispositivos de salida de audio (solo informativo, no reproduce nada)
+   * Vacía si no hay dispositivos o la app se compiló sin la feature 'audio-devices'
+   */
+  async listAudioOutputs(): Promise<AudioOutput[]> {
+    try {
+      return await invoke<AudioOutput[]>('list_audio_outputs');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error listando salidas de audio:', errorMsg);
+      throw new Error(`Error listando salidas de audio: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Obtiene la configuración guardada de la app
+   */
+  async getSettings(): Promise<Settings> {
+    try {
+      return await invoke<Settings>('get_settings');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo configuración:', errorMsg);
+      throw new Error(`No se pudo obtener la configuración: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Valida y guarda la configuración de la app, devolviendo los valores guardados
+   */
+  async updateSettings(settings: Settings): Promise<Settings> {
+    try {
+      return await invoke<Settings>('update_settings', { newSettings: settings });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error guardando configuración:', errorMsg);
+      throw new Error(`No se pudo guardar la configuración: ${errorMsg}`);
+    }
+  },
+
   /**
    * Obtiene la carpeta de música por defecto del sistema
    */
@@ -125,16 +431,99 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Muestra un archivo en el explorador de archivos del sistema
+   */
+  async revealInFileManager(path: string): Promise<void> {
+    try {
+      await invoke('reveal_in_file_manager', { path });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error abriendo carpeta:', errorMsg);
+      throw new Error(`No se pudo abrir la carpeta: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Elimina una canción (a la papelera o de forma permanente)
+   * @returns Bytes liberados
+   */
+  async deleteTrack(path: string, toTrash: boolean): Promise<number> {
+    try {
+      return await invoke<number>('delete_track', { path, toTrash });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error eliminando archivo:', errorMsg);
+      throw new Error(`No se pudo eliminar el archivo: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Lee una imagen local (p. ej. una portada elegida por el usuario) como data URL base64
+   * Solo acepta JPEG, PNG, GIF, WebP o BMP de hasta 10 MB
+   */
+  async readImageAsDataUrl(path: string): Promise<string> {
+    try {
+      return await invoke<string>('read_image_as_data_url', { path });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error leyendo imagen:', errorMsg);
+      throw new Error(`Error leyendo imagen: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Descarga una imagen remota (p. ej. la portada de Spotify) como data URL base64
+   */
+  async fetchImageAsDataUrl(url: string): Promise<string> {
+    try {
+      return await invoke<string>('fetch_image_as_data_url', { url });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error descargando imagen:', errorMsg);
+      throw new Error(`Error descargando imagen: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Guarda portadas de Spotify en la caché local y devuelve sus rutas
+   * (en el mismo orden que `urls`, `null` si la descarga falló)
+   */
+  async cacheSpotifyAlbumArt(urls: string[]): Promise<(string | null)[]> {
+    try {
+      return await invoke<(string | null)[]>('cache_spotify_album_art', { urls });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error guardando portadas:', errorMsg);
+      throw new Error(`Error guardando portadas: ${errorMsg}`);
+    }
+  },
+
   // ========================================================================
   // 🔐 COMANDOS DE AUTENTICACIÓN SPOTIFY
   // ========================================================================
 
   /**
    * Inicia el flujo de autenticación OAuth con Spotify
+   * @param timeoutSecs - Segundos para completar el login (30-600, por defecto 120)
    */
-  async authenticateSpotify(): Promise<string> {
+  async authenticateSpotify(timeoutSecs?: number): Promise<string> {
     try {
-      return await invoke<string>('spotify_authenticate');
+      return await invoke<string>('spotify_authenticate', { timeoutSecs });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error de autenticación:', errorMsg);
+      throw new Error(`Error de autenticación: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Inicia el flujo OAuth con PKCE, que no necesita el client secret
+   * @param timeoutSecs - Segundos para completar el login (30-600, por defecto 120)
+   */
+  async authenticateSpotifyPkce(timeoutSecs?: number): Promise<string> {
+    try {
+      return await invoke<string>('spotify_authenticate_pkce', { timeoutSecs });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);
       console.error('❌ Error de autenticación:', errorMsg);
@@ -168,16 +557,31 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Renueva el token de Spotify (p. ej. al volver a la app tras un rato inactiva)
+   * @returns Nueva expiración como timestamp Unix en segundos
+   */
+  async refreshSpotifyToken(): Promise<number | null> {
+    try {
+      return await invoke<number | null>('spotify_refresh_token');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error renovando token:', errorMsg);
+      throw new Error(`Error renovando token: ${errorMsg}`);
+    }
+  },
+
   // ========================================================================
   // 📊 COMANDOS DE DATOS SPOTIFY
   // ========================================================================
 
   /**
    * Obtiene el perfil del usuario autenticado
+   * Se usa el perfil en memoria salvo que forceRefresh sea true
    */
-  async getSpotifyProfile(): Promise<SpotifyUser> {
+  async getSpotifyProfile(forceRefresh?: boolean): Promise<SpotifyUser> {
     try {
-      return await invoke<SpotifyUser>('spotify_get_profile');
+      return await invoke<SpotifyUser>('spotify_get_profile', { forceRefresh });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);
       console.error('❌ Error obteniendo perfil:', errorMsg);
@@ -202,6 +606,35 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Obtiene el total de canciones guardadas (para paginar getSavedTracks)
+   */
+  async getSavedTracksCount(): Promise<number> {
+    try {
+      return await invoke<number>('spotify_get_saved_tracks_count');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo total de canciones guardadas:', errorMsg);
+      throw new Error(`Error obteniendo total de canciones guardadas: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Obtiene episodios de podcast guardados con paginación (limit: 1-50)
+   */
+  async getSavedEpisodes(limit?: number, offset?: number): Promise<SpotifyEpisode[]> {
+    try {
+      return await invoke<SpotifyEpisode[]>('spotify_get_saved_episodes', {
+        limit: limit ?? undefined,
+        offset: offset ?? undefined
+      });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo episodios guardados:', errorMsg);
+      throw new Error(`Error obteniendo episodios guardados: ${errorMsg}`);
+    }
+  },
+
   /**
    * 🔥 Inicia streaming progresivo de todas las canciones guardadas
    * Emite eventos 'spotify-tracks-batch' con batches de 50 tracks
@@ -217,13 +650,14 @@ export const TauriCommands = {
   },
 
   /**
-   * Obtiene las playlists del usuario con paginación
-   * El parámetro limit es opcional en Rust
+   * Obtiene una página de playlists del usuario, con el total para paginar
+   * Los parámetros limit y offset son opcionales en Rust
    */
-  async getPlaylists(limit?: number): Promise<SpotifyPlaylist[]> {
+  async getPlaylists(limit?: number, offset?: number): Promise<SpotifyPlaylistPage> {
     try {
-      return await invoke<SpotifyPlaylist[]>('spotify_get_playlists', {
-        limit: limit ?? undefined
+      return await invoke<SpotifyPlaylistPage>('spotify_get_playlists', {
+        limit: limit ?? undefined,
+        offset: offset ?? undefined
       });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);
@@ -232,6 +666,19 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Obtiene todas las playlists del usuario (sin el límite de 50)
+   */
+  async getAllPlaylists(): Promise<SpotifyPlaylist[]> {
+    try {
+      return await invoke<SpotifyPlaylist[]>('spotify_get_all_playlists');
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error obteniendo playlists:', errorMsg);
+      throw new Error(`Error obteniendo playlists: ${errorMsg}`);
+    }
+  },
+
   /**
    * Obtiene las canciones de una playlist específica
    * Los parámetros limit y offset son opcionales en Rust
@@ -293,18 +740,47 @@ export const TauriCommands = {
   // ========================================================================
 
   /**
-   * Verifica si spotdl está instalado
+   * Verifica si spotdl está instalado y su versión es compatible
    */
   async checkSpotdlInstalled(): Promise<boolean> {
     try {
-      const version = await invoke<string>('check_spotdl_installed');
-      return Boolean(version);
+      const spotdl = await invoke<{ version: string; meets_minimum: boolean }>(
+        'check_spotdl_installed'
+      );
+      return spotdl.meets_minimum;
     } catch (error) {
       // spotdl no está instalado
       return false;
     }
   },
 
+  /**
+   * Verifica archivos descargados y devuelve los que faltan, están vacíos o dañados
+   * Una lista vacía significa que todas las descargas son válidas
+   */
+  async verifyDownloads(paths: string[]): Promise<DownloadProblem[]> {
+    try {
+      return await invoke<DownloadProblem[]>('verify_downloads', { paths });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error verificando descargas:', errorMsg);
+      throw new Error(`Error verificando descargas: ${errorMsg}`);
+    }
+  },
+
+  /**
+   * Comprueba que una URL de Spotify corresponde a una canción antes de descargar
+   */
+  async previewSpotifyTrack(url: string): Promise<SpotifyTrackPreview> {
+    try {
+      return await invoke<SpotifyTrackPreview>('preview_spotify_track', { url });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error verificando URL:', errorMsg);
+      throw new Error(`Error verificando URL: ${errorMsg}`);
+    }
+  },
+
   /**
    * Descarga una sola canción de Spotify
    */
@@ -318,14 +794,15 @@ export const TauriCommands = {
     }
 
     try {
-      // Obtener carpeta de música
-      const musicFolder = await this.getDefaultMusicFolder();
+      // Carpeta configurada o, si no hay, la carpeta de música
+      const settings = await this.getSettings();
+      const outputDir = settings.outputDir ?? await this.getDefaultMusicFolder();
 
       await invoke('download_single_spotify_track', {
         url: track.externalUrl,
         outputTemplate: '{artist}/{album}/{title}',
-        format: 'mp3',
-        outputDir: musicFolder
+        // El formato se toma de la configuración
+        outputDir
       });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);
@@ -334,6 +811,30 @@ export const TauriCommands = {
     }
   },
 
+  /**
+   * Descarga un álbum completo de Spotify (spotdl obtiene sus canciones)
+   * Emite 'download-progress' por cada canción y 'download-finished' al terminar
+   * Devuelve el número de canciones descargadas
+   */
+  async downloadAlbum(album: string, outputTemplate?: string): Promise<number> {
+    try {
+      // Carpeta configurada o, si no hay, la carpeta de música
+      const settings = await this.getSettings();
+      const outputDir = settings.outputDir ?? await this.getDefaultMusicFolder();
+
+      return await invoke<number>('download_spotify_album', {
+        album,
+        outputTemplate,
+        // El formato se toma de la configuración
+        outputDir
+      });
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      console.error('❌ Error descargando álbum:', errorMsg);
+      throw new Error(`Error descargando álbum: ${errorMsg}`);
+    }
+  },
+
   /**
    * 🔥 Descarga múltiples tracks de forma segmentada con progreso
    * Emite eventos 'download-progress', 'download-finished', 'download-error'
@@ -357,16 +858,17 @@ export const TauriCommands = {
     }
 
     try {
-      // Obtener carpeta de música
-      const musicFolder = await this.getDefaultMusicFolder();
+      // Carpeta configurada o, si no hay, la carpeta de música
+      const settings = await this.getSettings();
+      const outputDir = settings.outputDir ?? await this.getDefaultMusicFolder();
 
       await invoke('download_spotify_tracks_segmented', {
         urls,
         segmentSize: Math.max(1, Math.min(segmentSize, 50)),
         delay: Math.max(2, Math.min(delayBetweenSegments, 10)),
         outputTemplate: '{artist}/{album}/{title}',
-        format: 'mp3',
-        outputDir: musicFolder
+        // El formato se toma de la configuración
+        outputDir
       });
     } catch (error) {
       const errorMsg = error instanceof Error ? error.message : String(error);